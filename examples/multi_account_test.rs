@@ -1,4 +1,4 @@
-use scraper_service::{EtcScraper, ScraperConfig, Scraper};
+use scraper_service::{AccountStore, ScraperConfig, Scraper};
 use std::path::PathBuf;
 
 #[tokio::main]
@@ -10,25 +10,19 @@ async fn main() {
 
     // 環境変数から認証情報を取得（JSON形式）
     // 例: ETC_ACCOUNTS='[{"user_id":"user1","password":"pass1"},{"user_id":"user2","password":"pass2"}]'
-    let accounts_json = std::env::var("ETC_ACCOUNTS")
-        .expect("ETC_ACCOUNTS environment variable not set");
-
-    let accounts: Vec<serde_json::Value> = serde_json::from_str(&accounts_json)
-        .expect("Failed to parse ETC_ACCOUNTS JSON");
+    // download_path を指定したアカウントはそちらにダウンロード
+    let store = AccountStore::from_env("ETC_ACCOUNTS")
+        .expect("Failed to load ETC_ACCOUNTS");
 
     println!("=== ETC Scraper Multi-Account Test ===\n");
 
-    for (i, account) in accounts.iter().enumerate() {
-        let username = account["user_id"].as_str().expect("user_id not found");
-        let password = account["password"].as_str().expect("password not found");
-
-        println!("--- Account {}: {} ---", i + 1, username);
-
-        let config = ScraperConfig::new(username, password)
-            .with_download_path(PathBuf::from("./downloads"))
-            .with_headless(false);  // デバッグ用に表示モード
+    let base = ScraperConfig::default()
+        .with_download_path(PathBuf::from("./downloads"))
+        .with_namespace_by_user(true)  // アカウントごとにサブディレクトリを分ける
+        .with_headless(false);  // デバッグ用に表示モード
 
-        let mut scraper = EtcScraper::new(config);
+    for (i, (account, mut scraper)) in store.accounts().iter().zip(store.scrapers(&base)).enumerate() {
+        println!("--- Account {}: {} ---", i + 1, account.user_id);
 
         match scraper.execute().await {
             Ok(path) => {
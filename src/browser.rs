@@ -0,0 +1,632 @@
+//! スクレイパー共通のブラウザ操作ヘルパー
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chromiumoxide::cdp::browser_protocol::browser::{
+    SetDownloadBehaviorBehavior, SetDownloadBehaviorParams,
+};
+use chromiumoxide::cdp::browser_protocol::emulation::SetLocaleOverrideParams;
+use chromiumoxide::cdp::browser_protocol::log::{EnableParams as LogEnableParams, EventEntryAdded};
+use chromiumoxide::cdp::browser_protocol::network::{Headers, SetExtraHttpHeadersParams};
+use chromiumoxide::cdp::browser_protocol::target::GetTargetInfoParams;
+use chromiumoxide::cdp::js_protocol::runtime::EventConsoleApiCalled;
+use chromiumoxide::error::CdpError;
+use chromiumoxide::handler::{Handler, HandlerConfig};
+use chromiumoxide::page::ScreenshotParams;
+use chromiumoxide::{Browser, Page};
+use futures::StreamExt;
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+
+use crate::error::ScraperError;
+
+/// ページのロケールを上書き（Emulation.setLocaleOverride）
+///
+/// `--lang` 引数だけではJavaScriptの `Intl` 等に反映されない場合があるため併用する。
+pub(crate) async fn set_locale_override(page: &Page, locale: &str) -> Result<(), ScraperError> {
+    let params = SetLocaleOverrideParams::builder()
+        .locale(icu_locale(locale))
+        .build();
+    page.execute(params)
+        .await
+        .map_err(|e| ScraperError::BrowserInit(format!("ロケール設定エラー: {}", e)))?;
+    Ok(())
+}
+
+/// ページのダウンロード先を設定（Browser.setDownloadBehavior）
+///
+/// 設定はブラウザコンテキスト単位のため、ページが属するコンテキストを指定して適用する。
+/// 並行して別ディレクトリにダウンロードするページは、別々のコンテキストで開くこと。
+pub(crate) async fn set_download_dir(page: &Page, dir: &str) -> Result<(), ScraperError> {
+    let target = page
+        .execute(
+            GetTargetInfoParams::builder()
+                .target_id(page.target_id().clone())
+                .build(),
+        )
+        .await
+        .map_err(|e| ScraperError::BrowserInit(format!("ダウンロード設定エラー: {}", e)))?;
+
+    let mut builder = SetDownloadBehaviorParams::builder()
+        .behavior(SetDownloadBehaviorBehavior::AllowAndName)
+        .download_path(dir)
+        .events_enabled(true);
+    if let Some(context_id) = target.result.target_info.browser_context_id.clone() {
+        builder = builder.browser_context_id(context_id);
+    }
+    let params = builder
+        .build()
+        .map_err(|e| ScraperError::BrowserInit(format!("ダウンロード設定エラー: {}", e)))?;
+
+    page.execute(params)
+        .await
+        .map_err(|e| ScraperError::BrowserInit(format!("ダウンロード設定エラー: {}", e)))?;
+    tracing::debug!(dir, "ダウンロード先を設定");
+    Ok(())
+}
+
+/// 起動済みのブラウザのCDPエンドポイントに接続（`Browser::launch` の代わり）
+///
+/// `ws://` / `wss://` のWebSocket URLのほか、`http://host:9222` を指定した場合は
+/// `/json/version` からWebSocket URLを解決する。
+pub(crate) async fn connect(
+    endpoint: &str,
+    request_timeout: Duration,
+) -> Result<(Browser, Handler), ScraperError> {
+    tracing::info!(endpoint, "CDPエンドポイントに接続");
+    let config = HandlerConfig {
+        request_timeout,
+        ..Default::default()
+    };
+    Browser::connect_with_config(endpoint, config)
+        .await
+        .map_err(|e| {
+            ScraperError::BrowserInit(format!(
+                "CDPエンドポイントに接続できません ({}): {}",
+                endpoint, e
+            ))
+        })
+}
+
+/// 起動したブラウザの終了待ちの上限
+pub(crate) const BROWSER_CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// ドロップ時に強制終了したブラウザプロセスの終了待ちの上限
+pub(crate) const BROWSER_KILL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 起動したブラウザのプロセスを同期的に強制終了（`Drop` 用）
+///
+/// 終了シグナルを送り、`timeout` まで終了を待つ。プロセスの終了を確認できた場合
+/// （起動したプロセスがない・終了済みの場合を含む）に `true` を返す。
+/// `false` の場合はプロセスがまだユーザーデータディレクトリを使用している可能性がある。
+pub(crate) fn kill_browser_blocking(browser: &mut Browser, timeout: Duration) -> bool {
+    let Some(child) = browser.get_mut_child() else {
+        return true;
+    };
+    let child = child.as_mut_inner();
+    if let Ok(Some(_)) = child.try_wait() {
+        return true;
+    }
+    if let Err(e) = child.start_kill() {
+        tracing::warn!("ブラウザプロセスを強制終了できません: {}", e);
+    }
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => return true,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(50)),
+            _ => return false,
+        }
+    }
+}
+
+/// 起動したブラウザを終了（CDP `Browser.close` → プロセス終了待ち）
+///
+/// `timeout` 以内に終了しない場合は警告を出し、子プロセスを強制終了する。
+/// 終了処理がハングしてもシャットダウンが `timeout` 程度で完了するようにする。
+/// 戻り値は [`kill_browser_blocking`] と同じく、プロセスの終了を確認できたかどうか。
+pub(crate) async fn close_browser(browser: &mut Browser, timeout: Duration) -> bool {
+    let graceful = tokio::time::timeout(timeout, async {
+        if let Err(e) = browser.close().await {
+            tracing::debug!("Browser.close エラー: {}", e);
+        }
+        browser.wait().await
+    })
+    .await;
+
+    match graceful {
+        Ok(Ok(status)) => {
+            tracing::debug!(?status, "ブラウザプロセス終了");
+            true
+        }
+        Ok(Err(e)) => {
+            tracing::warn!("ブラウザ終了待ちエラー: {}", e);
+            false
+        }
+        Err(_) => {
+            tracing::warn!(
+                timeout_secs = timeout.as_secs(),
+                "ブラウザが時間内に終了しないため強制終了します"
+            );
+            match browser.kill().await {
+                Some(Err(e)) => {
+                    tracing::warn!("ブラウザプロセスを強制終了できません: {}", e);
+                    false
+                }
+                _ => true,
+            }
+        }
+    }
+}
+
+/// CDPエンドポイントのURL形式を確認（ws / wss / http / https）
+pub(crate) fn validate_cdp_endpoint(endpoint: &str) -> Result<(), ScraperError> {
+    let valid = endpoint.split_once("://").is_some_and(|(scheme, rest)| {
+        matches!(scheme, "ws" | "wss" | "http" | "https") && !rest.is_empty()
+    });
+    if !valid {
+        return Err(ScraperError::Config(format!(
+            "invalid cdp_endpoint '{}': expected ws://, wss://, http:// or https:// URL",
+            endpoint
+        )));
+    }
+    Ok(())
+}
+
+/// `wait_for_selector` / `wait_for_js_fn` のポーリング間隔
+const SELECTOR_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// セレクタに一致する要素が現れるまで待機
+///
+/// 出現すれば `Ok(true)`、`timeout` までに現れなければ `Ok(false)` を返す（タイムアウトはエラーにしない）。
+/// ページ遷移中の評価エラーは未出現とみなして待機を続け、
+/// セレクタの構文エラー等のスクリプト例外のみ `ScraperError::JavaScript` を返す。
+pub(crate) async fn wait_for_selector(
+    page: &Page,
+    selector: &str,
+    timeout: Duration,
+) -> Result<bool, ScraperError> {
+    let script = format!(
+        "document.querySelector({}) !== null",
+        serde_json::Value::String(selector.to_string())
+    );
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    loop {
+        match page.evaluate(script.as_str()).await {
+            Ok(result) => {
+                if result.into_value::<bool>().unwrap_or(false) {
+                    return Ok(true);
+                }
+            }
+            Err(CdpError::JavascriptException(details)) => {
+                let message = details
+                    .exception
+                    .as_ref()
+                    .and_then(|e| e.description.clone())
+                    .unwrap_or_else(|| details.text.clone());
+                return Err(ScraperError::JavaScript(format!(
+                    "invalid selector {}: {}",
+                    selector, message
+                )));
+            }
+            Err(e) => tracing::debug!("Element check for {} failed: {}", selector, e),
+        }
+
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if remaining.is_zero() {
+            return Ok(false);
+        }
+        tokio::time::sleep(SELECTOR_POLL_INTERVAL.min(remaining)).await;
+    }
+}
+
+/// グローバルオブジェクトのメソッド（`object.method`）が関数として定義されるまで待機
+///
+/// 定義されれば `Ok(true)`、`timeout` までに定義されなければ `Ok(false)` を返す。
+/// `timeout` が0の場合は1回だけ確認する。`object` はグローバル変数名（識別子）を指定する。
+/// ページ遷移中の評価エラーは未定義とみなして待機を続ける。
+pub(crate) async fn wait_for_js_fn(
+    page: &Page,
+    object: &str,
+    method: &str,
+    timeout: Duration,
+) -> Result<bool, ScraperError> {
+    let script = format!(
+        "typeof {object} !== 'undefined' && {object} !== null && typeof {object}[{}] === 'function'",
+        serde_json::Value::String(method.to_string())
+    );
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    loop {
+        match page.evaluate(script.as_str()).await {
+            Ok(result) => {
+                if result.into_value::<bool>().unwrap_or(false) {
+                    return Ok(true);
+                }
+            }
+            Err(e) => tracing::debug!("Function check for {}.{} failed: {}", object, method, e),
+        }
+
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if remaining.is_zero() {
+            return Ok(false);
+        }
+        tokio::time::sleep(SELECTOR_POLL_INTERVAL.min(remaining)).await;
+    }
+}
+
+/// バックグラウンドタスクを起動
+///
+/// `runtime` を指定した場合はそのランタイムで、未指定の場合は呼び出し元のランタイムで実行する。
+pub(crate) fn spawn_on<F>(runtime: Option<&Handle>, future: F) -> JoinHandle<F::Output>
+where
+    F: std::future::Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    match runtime {
+        Some(handle) => handle.spawn(future),
+        None => tokio::spawn(future),
+    }
+}
+
+/// CDPハンドラータスクの監視（ブラウザとの接続断の検出用）
+///
+/// ハンドラーのイベントループが終了した時点で接続断とみなし、
+/// 以降の [`ConnectionMonitor::check`] がエラーを返すようにする。
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConnectionMonitor {
+    lost: Arc<AtomicBool>,
+    last_error: Arc<Mutex<Option<String>>>,
+    task: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl ConnectionMonitor {
+    /// ハンドラーをバックグラウンドで実行し、終了を監視（`runtime` は [`spawn_on`] を参照）
+    pub(crate) fn spawn(mut handler: Handler, runtime: Option<&Handle>) -> Self {
+        let monitor = Self::default();
+        let task_monitor = monitor.clone();
+        let task = spawn_on(runtime, async move {
+            while let Some(event) = handler.next().await {
+                if let Err(e) = event {
+                    tracing::debug!("Browser event error: {}", e);
+                    *task_monitor
+                        .last_error
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner()) = Some(e.to_string());
+                }
+            }
+            tracing::warn!("ブラウザのイベントハンドラーが終了しました（接続断）");
+            task_monitor.lost.store(true, Ordering::SeqCst);
+        });
+        *monitor.task.lock().unwrap_or_else(|e| e.into_inner()) = Some(task);
+        monitor
+    }
+
+    /// ハンドラータスクのハンドルを取り出す（呼び出し側での監視・join 用、2回目以降は `None`）
+    pub(crate) fn take_task(&self) -> Option<JoinHandle<()>> {
+        self.task.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+
+    /// 接続が切れていれば `BrowserInit` エラーを返す
+    pub(crate) fn check(&self) -> Result<(), ScraperError> {
+        if !self.lost.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        let last_error = self
+            .last_error
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        Err(ScraperError::BrowserInit(match last_error {
+            Some(e) => format!("browser connection lost (last error: {})", e),
+            None => "browser connection lost".to_string(),
+        }))
+    }
+}
+
+/// サイト側が設定するためブラウザに任せるヘッダー（上書きしない）
+const PROTECTED_HEADERS: [&str; 4] = ["cookie", "authorization", "host", "content-length"];
+
+/// 全リクエストに追加HTTPヘッダーを付与（Network.setExtraHTTPHeaders）
+///
+/// `Cookie` / `Authorization` 等、セッションを壊しうるヘッダーは警告を出して除外する。
+pub(crate) async fn set_extra_headers(
+    page: &Page,
+    headers: &HashMap<String, String>,
+) -> Result<(), ScraperError> {
+    let headers = filter_extra_headers(headers);
+    if headers.is_empty() {
+        return Ok(());
+    }
+
+    page.execute(SetExtraHttpHeadersParams::new(Headers::new(
+        serde_json::Value::Object(headers),
+    )))
+    .await
+    .map_err(|e| ScraperError::BrowserInit(format!("追加ヘッダー設定エラー: {}", e)))?;
+    Ok(())
+}
+
+fn filter_extra_headers(
+    headers: &HashMap<String, String>,
+) -> serde_json::Map<String, serde_json::Value> {
+    headers
+        .iter()
+        .filter(|(name, _)| {
+            let protected = PROTECTED_HEADERS
+                .iter()
+                .any(|p| name.eq_ignore_ascii_case(p));
+            if protected {
+                tracing::warn!(header = %name, "セッション管理用のヘッダーは追加ヘッダーに指定できません");
+            }
+            !protected
+        })
+        .map(|(name, value)| (name.clone(), serde_json::Value::String(value.clone())))
+        .collect()
+}
+
+/// ログイン画面のエラーメッセージ欄
+///
+/// ログイン画面の注意書きにも「別のブラウザでログイン」等の文言が載るため、判定はこの欄に限定する。
+const LOGIN_ERROR_SELECTOR: &str = ".error, [role='alert']";
+
+/// エラーメッセージ欄の文言が、別の場所でのログインによる強制ログアウトの表示かどうか
+pub(crate) fn is_session_superseded(text: &str) -> bool {
+    const MARKERS: [&str; 4] = [
+        "別のブラウザでログイン",
+        "別の端末でログイン",
+        "他の端末でログイン",
+        "二重ログイン",
+    ];
+    MARKERS.iter().any(|marker| text.contains(marker))
+}
+
+/// 強制ログアウトの表示があれば `SessionSuperseded` エラーを返す
+///
+/// 強制ログアウト後は `evaluate` の結果が想定外の `null` 等になり、
+/// 要素・JSONのエラーとして表面化するため、エラー時の原因特定に使う。
+pub(crate) async fn check_session_superseded(page: &Page) -> Result<(), ScraperError> {
+    let (url, text): (String, String) = page
+        .evaluate(format!(
+            r#"[
+                window.location.href,
+                Array.from(document.querySelectorAll("{}")).map(function(e) {{ return e.innerText; }}).join('\n')
+            ]"#,
+            LOGIN_ERROR_SELECTOR
+        ))
+        .await
+        .ok()
+        .and_then(|v| v.into_value().ok())
+        .unwrap_or_default();
+
+    if is_session_superseded(&text) {
+        tracing::warn!(url = %url, "別の場所でのログインにより強制ログアウトされました");
+        return Err(ScraperError::SessionSuperseded(url));
+    }
+    Ok(())
+}
+
+/// ページ全体のスクリーンショットをPNGで保存
+pub(crate) async fn save_full_page_screenshot(
+    page: &Page,
+    path: &Path,
+) -> Result<(), ScraperError> {
+    let png = page
+        .screenshot(ScreenshotParams::builder().full_page(true).build())
+        .await
+        .map_err(|e| ScraperError::Screenshot(e.to_string()))?;
+    std::fs::write(path, png)?;
+    Ok(())
+}
+
+/// ブラウザのコンソール出力の収集
+///
+/// `Runtime.consoleAPICalled` と `Log.entryAdded` を購読し、1行ずつ文字列で保持する。
+/// ドロップ時に購読タスクを停止する。
+pub(crate) struct ConsoleCapture {
+    entries: Arc<Mutex<Vec<String>>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl ConsoleCapture {
+    /// ページのコンソール出力の収集を開始（`runtime` は [`spawn_on`] を参照）
+    pub(crate) async fn start(page: &Page, runtime: Option<&Handle>) -> Result<Self, ScraperError> {
+        let entries = Arc::new(Mutex::new(Vec::new()));
+
+        let mut console_events = page
+            .event_listener::<EventConsoleApiCalled>()
+            .await
+            .map_err(|e| ScraperError::BrowserInit(format!("コンソール購読エラー: {}", e)))?;
+        let mut log_events = page
+            .event_listener::<EventEntryAdded>()
+            .await
+            .map_err(|e| ScraperError::BrowserInit(format!("ログ購読エラー: {}", e)))?;
+        page.execute(LogEnableParams::default())
+            .await
+            .map_err(|e| ScraperError::BrowserInit(format!("Log.enable エラー: {}", e)))?;
+
+        let console_entries = Arc::clone(&entries);
+        let console_task = spawn_on(runtime, async move {
+            while let Some(event) = console_events.next().await {
+                let args: Vec<String> = event
+                    .args
+                    .iter()
+                    .map(|arg| match (&arg.value, &arg.description) {
+                        (Some(serde_json::Value::String(s)), _) => s.clone(),
+                        (Some(value), _) => value.to_string(),
+                        (None, Some(description)) => description.clone(),
+                        (None, None) => String::new(),
+                    })
+                    .collect();
+                let line = format!("[console.{:?}] {}", event.r#type, args.join(" "));
+                push_entry(&console_entries, line);
+            }
+        });
+
+        let log_entries = Arc::clone(&entries);
+        let log_task = spawn_on(runtime, async move {
+            while let Some(event) = log_events.next().await {
+                let entry = &event.entry;
+                let line = match entry.url {
+                    Some(ref url) => format!("[{:?}] {} ({})", entry.level, entry.text, url),
+                    None => format!("[{:?}] {}", entry.level, entry.text),
+                };
+                push_entry(&log_entries, line);
+            }
+        });
+
+        Ok(Self {
+            entries,
+            tasks: vec![console_task, log_task],
+        })
+    }
+
+    /// これまでに収集したコンソール出力
+    pub(crate) fn entries(&self) -> Vec<String> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// 別ページで収集したコンソール出力を追加（並列取得したページの出力をまとめる用）
+    pub(crate) fn append(&self, lines: impl IntoIterator<Item = String>) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(lines);
+    }
+}
+
+impl Drop for ConsoleCapture {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+fn push_entry(entries: &Mutex<Vec<String>>, line: String) {
+    entries.lock().unwrap_or_else(|e| e.into_inner()).push(line);
+}
+
+/// BCP 47形式 ("ja-JP") をICU形式 ("ja_JP") に変換
+fn icu_locale(locale: &str) -> String {
+    locale.replace('-', "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `LOGIN_ERROR_SELECTOR` に一致する `class="error"` 要素の文言を取り出す
+    fn error_text(html: &str) -> String {
+        assert!(LOGIN_ERROR_SELECTOR.starts_with(".error"));
+        html.split(r#"class="error">"#)
+            .skip(1)
+            .filter_map(|rest| rest.split('<').next())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_is_session_superseded() {
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/session_superseded.html"
+        ));
+        assert!(is_session_superseded(&error_text(fixture)));
+        assert!(!is_session_superseded(&error_text(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/etc_password_expired.html"
+        )))));
+
+        // 注意書きに同じ文言がある通常のログイン画面（通常のセッション切れ）は該当しない
+        let guidance = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/login_guidance.html"
+        ));
+        assert!(is_session_superseded(guidance));
+        assert!(!is_session_superseded(&error_text(guidance)));
+    }
+
+    #[test]
+    fn test_connection_monitor_check() {
+        let monitor = ConnectionMonitor::default();
+        assert!(monitor.check().is_ok());
+
+        monitor.lost.store(true, Ordering::SeqCst);
+        *monitor.last_error.lock().unwrap() = Some("ws closed".to_string());
+        let err = monitor.check().unwrap_err();
+        assert!(err.to_string().contains("browser connection lost"));
+        assert!(err.is_retryable());
+    }
+
+    #[test]
+    fn test_spawn_on_runtime() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .unwrap();
+        // ランタイムの外からでも指定したランタイムで実行される
+        let task = spawn_on(Some(runtime.handle()), async { 42 });
+        assert_eq!(runtime.block_on(task).unwrap(), 42);
+    }
+
+    #[tokio::test]
+    #[ignore] // Chromeが必要: cargo test -p scraper-service test_wait_for_selector -- --ignored
+    async fn test_wait_for_selector() {
+        use chromiumoxide::browser::{Browser, BrowserConfig};
+
+        let mut builder = BrowserConfig::builder().no_sandbox();
+        if let Ok(path) = std::env::var("CHROME_PATH") {
+            builder = builder.chrome_executable(path);
+        }
+        let (browser, handler) = Browser::launch(builder.build().unwrap()).await.unwrap();
+        let _monitor = ConnectionMonitor::spawn(handler, None);
+
+        let page = browser
+            .new_page("data:text/html,<div id='ready'></div>")
+            .await
+            .unwrap();
+        let timeout = Duration::from_secs(1);
+
+        assert!(wait_for_selector(&page, "#ready", timeout).await.unwrap());
+        assert!(!wait_for_selector(&page, "#missing", timeout).await.unwrap());
+        assert!(wait_for_selector(&page, "##", timeout).await.is_err());
+    }
+
+    #[test]
+    fn test_filter_extra_headers() {
+        let headers = HashMap::from([
+            ("X-Request-Id".to_string(), "req-1".to_string()),
+            ("Cookie".to_string(), "a=b".to_string()),
+            ("authorization".to_string(), "Bearer x".to_string()),
+        ]);
+        let filtered = filter_extra_headers(&headers);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered["X-Request-Id"], "req-1");
+    }
+
+    #[test]
+    fn test_validate_cdp_endpoint() {
+        assert!(validate_cdp_endpoint("ws://browserless:3000/devtools/browser/abc").is_ok());
+        assert!(validate_cdp_endpoint("http://localhost:9222").is_ok());
+        assert!(validate_cdp_endpoint("localhost:9222").is_err());
+        assert!(validate_cdp_endpoint("ftp://host").is_err());
+        assert!(validate_cdp_endpoint("ws://").is_err());
+    }
+
+    #[test]
+    fn test_icu_locale() {
+        assert_eq!(icu_locale("ja-JP"), "ja_JP");
+        assert_eq!(icu_locale("en_US"), "en_US");
+    }
+}
@@ -0,0 +1,217 @@
+//! スクレイプ結果の zip バンドル出力
+//!
+//! `bundle` feature 有効時のみ。アーカイブ用に1回の実行の成果物（ETCのCSV、Dtakologの車両データ・
+//! 映像通知、スクリーンショット等の追加ファイル）と、結果のメタデータ（`metrics.json`）、
+//! 各ファイルのサイズ・SHA-256 を記録した `manifest.json` を1つの zip にまとめる。
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use zip::write::SimpleFileOptions;
+
+use crate::error::ScraperError;
+use crate::service::{ScrapeResult, ScrapeTarget};
+
+/// マニフェストのファイル名
+const MANIFEST_NAME: &str = "manifest.json";
+/// 結果のメタデータのファイル名
+const METRICS_NAME: &str = "metrics.json";
+
+/// バンドル内の1ファイル
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BundleEntry {
+    /// zip 内のパス
+    pub name: String,
+    pub size_bytes: usize,
+    /// 内容の SHA-256（小文字16進）
+    pub sha256: String,
+}
+
+/// バンドルの内容一覧（`manifest.json` として zip に含める）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BundleManifest {
+    pub target: ScrapeTarget,
+    pub created_at: DateTime<Utc>,
+    /// `manifest.json` 以外のファイル
+    pub files: Vec<BundleEntry>,
+}
+
+/// 結果のメタデータ（`metrics.json`）
+#[derive(Debug, Serialize)]
+struct BundleMetrics {
+    size_bytes: Option<usize>,
+    content_hash: Option<String>,
+    record_count: Option<usize>,
+    vehicle_count: Option<usize>,
+    video_notification_count: Option<usize>,
+    pending_download_count: Option<usize>,
+    branch_errors: Vec<(String, String)>,
+}
+
+fn bundle_error(e: impl std::fmt::Display) -> ScraperError {
+    ScraperError::FileIO(std::io::Error::other(format!("バンドル出力エラー: {}", e)))
+}
+
+fn to_json(value: &impl Serialize) -> Result<Vec<u8>, ScraperError> {
+    serde_json::to_vec_pretty(value).map_err(|e| ScraperError::Json(e.to_string()))
+}
+
+/// スクレイプ結果を zip にまとめて出力
+///
+/// `out` は上書きされる。追加ファイルが不要な場合は [`bundle_result_with_files`] に空のスライスを渡すのと同じ。
+pub fn bundle_result(result: &ScrapeResult, out: &Path) -> Result<BundleManifest, ScraperError> {
+    bundle_result_with_files(result, out, &[])
+}
+
+/// スクレイプ結果と追加ファイル（スクリーンショット・HAR 等）を zip にまとめて出力
+///
+/// 追加ファイルは `files/{ファイル名}` として格納する。
+/// Dtakolog の動画はURLのみのため、`video_notifications.json` にURLを記録する。
+pub fn bundle_result_with_files(
+    result: &ScrapeResult,
+    out: &Path,
+    extra_files: &[PathBuf],
+) -> Result<BundleManifest, ScraperError> {
+    let mut contents: Vec<(String, Vec<u8>)> = Vec::new();
+
+    let target = match result {
+        ScrapeResult::Etc(etc) => {
+            let name = etc
+                .csv_path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "meisai.csv".to_string());
+            contents.push((name, etc.csv_content.clone()));
+            ScrapeTarget::Etc
+        }
+        ScrapeResult::Dtakolog(dtakolog) => {
+            contents.push(("vehicles.json".to_string(), to_json(&dtakolog.vehicles)?));
+            contents.push(("raw_data.json".to_string(), to_json(&dtakolog.raw_data)?));
+            contents.push((
+                "video_notifications.json".to_string(),
+                to_json(&dtakolog.video_notifications)?,
+            ));
+            if !dtakolog.console_logs.is_empty() {
+                contents.push((
+                    "console.log".to_string(),
+                    dtakolog.console_logs.join("\n").into_bytes(),
+                ));
+            }
+            ScrapeTarget::Dtakolog
+        }
+    };
+
+    let dtakolog = result.as_dtakolog();
+    let metrics = BundleMetrics {
+        size_bytes: result.size_bytes(),
+        content_hash: result.content_hash(),
+        record_count: result
+            .as_etc()
+            .and_then(|etc| etc.records.as_ref())
+            .map(Vec::len),
+        vehicle_count: dtakolog.map(|d| d.vehicles.len()),
+        video_notification_count: dtakolog.map(|d| d.video_notifications.len()),
+        pending_download_count: dtakolog.map(|d| d.pending_downloads.len()),
+        branch_errors: dtakolog
+            .map(|d| {
+                d.branch_errors
+                    .iter()
+                    .map(|(branch, e)| (branch.clone(), e.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+    contents.push((METRICS_NAME.to_string(), to_json(&metrics)?));
+
+    for path in extra_files {
+        let name = path
+            .file_name()
+            .ok_or_else(|| bundle_error(format!("ファイル名がありません: {}", path.display())))?;
+        contents.push((
+            format!("files/{}", name.to_string_lossy()),
+            std::fs::read(path)?,
+        ));
+    }
+
+    let manifest = BundleManifest {
+        target,
+        created_at: Utc::now(),
+        files: contents
+            .iter()
+            .map(|(name, data)| BundleEntry {
+                name: name.clone(),
+                size_bytes: data.len(),
+                sha256: format!("{:x}", Sha256::digest(data)),
+            })
+            .collect(),
+    };
+
+    let mut writer = zip::ZipWriter::new(std::fs::File::create(out)?);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    writer
+        .start_file(MANIFEST_NAME, options)
+        .map_err(bundle_error)?;
+    writer.write_all(&to_json(&manifest)?)?;
+    for (name, data) in &contents {
+        writer
+            .start_file(name.as_str(), options)
+            .map_err(bundle_error)?;
+        writer.write_all(data)?;
+    }
+    writer.finish().map_err(bundle_error)?;
+
+    tracing::info!(path = ?out, files = manifest.files.len(), "バンドルを出力");
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_bundle_result() {
+        let dir = std::env::temp_dir().join(format!("bundle_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let csv_path = dir.join("meisai.csv");
+        std::fs::write(&csv_path, "利用年月日（自）,車両番号\n").unwrap();
+        let screenshot = dir.join("screenshot.png");
+        std::fs::write(&screenshot, b"\x89PNG").unwrap();
+
+        let result = ScrapeResult::Etc(crate::EtcScrapeResult::new(csv_path).unwrap());
+        let out = dir.join("bundle.zip");
+        let manifest = bundle_result_with_files(&result, &out, &[screenshot]).unwrap();
+
+        let names: Vec<&str> = manifest.files.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["meisai.csv", "metrics.json", "files/screenshot.png"]
+        );
+        assert_eq!(
+            Some(manifest.files[0].sha256.clone()),
+            result.content_hash()
+        );
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&out).unwrap()).unwrap();
+        assert_eq!(archive.len(), 4);
+        let mut manifest_json = String::new();
+        archive
+            .by_name(MANIFEST_NAME)
+            .unwrap()
+            .read_to_string(&mut manifest_json)
+            .unwrap();
+        assert!(manifest_json.contains("\"target\": \"Etc\""));
+        let mut csv = Vec::new();
+        archive
+            .by_name("meisai.csv")
+            .unwrap()
+            .read_to_end(&mut csv)
+            .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(csv, "利用年月日（自）,車両番号\n".as_bytes());
+    }
+}
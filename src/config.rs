@@ -1,14 +1,411 @@
+use std::collections::HashMap;
+use std::future::Future;
 use std::path::PathBuf;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+use chromiumoxide::Page;
+use futures::future::BoxFuture;
+
+use crate::error::ScraperError;
+use crate::etc::{DownloadKind, PageLink, ReportType};
+
+/// ワンタイムパスワード（OTP）取得コールバック
+///
+/// パスワード入力後にOTP入力欄が表示された場合のみ `login` から呼び出される。
+/// メール等で受け取ったコードを返すこと。`Err` を返すとログインはそのエラーで中断される。
+/// OTP入力欄が表示されないアカウントでは一度も呼び出されない。
+#[derive(Clone)]
+pub struct OtpProvider(
+    Arc<dyn Fn() -> BoxFuture<'static, Result<String, ScraperError>> + Send + Sync>,
+);
+
+impl OtpProvider {
+    pub fn new<F, Fut>(f: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String, ScraperError>> + Send + 'static,
+    {
+        Self(Arc::new(move || Box::pin(f())))
+    }
+
+    /// OTPコードを取得
+    pub async fn fetch(&self) -> Result<String, ScraperError> {
+        (self.0)().await
+    }
+}
+
+impl std::fmt::Debug for OtpProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("OtpProvider(..)")
+    }
+}
+
+/// ページ遷移ごとに呼び出されるフック
+///
+/// `EtcScraper` のトップページ表示・ログイン・検索条件/検索結果への遷移の後に呼び出される。
+/// アカウント固有のモーダルやバナーを閉じる等、任意のJavaScript実行・クリックに使う。
+/// `Err` を返すと実行中の処理はそのエラーで中断される。
+#[derive(Clone)]
+pub struct NavigateHook(Arc<NavigateFn>);
+
+type NavigateFn = dyn for<'a> Fn(&'a Page) -> BoxFuture<'a, Result<(), ScraperError>> + Send + Sync;
+
+impl NavigateHook {
+    pub fn new<F>(f: F) -> Self
+    where
+        F: for<'a> Fn(&'a Page) -> BoxFuture<'a, Result<(), ScraperError>> + Send + Sync + 'static,
+    {
+        Self(Arc::new(f))
+    }
+
+    /// フックを実行
+    pub async fn run(&self, page: &Page) -> Result<(), ScraperError> {
+        (self.0)(page).await
+    }
+}
+
+impl std::fmt::Debug for NavigateHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("NavigateHook(..)")
+    }
+}
+
+/// ダウンロードリンクの選択関数
+///
+/// 検索結果ページの全 `<a>` 要素を受け取り、クリックするリンクの（スライス内の）位置を返す。
+/// `None` を返した場合はダウンロードリンクが見つからないエラーになる。
+/// 未設定時は `download_kind` に一致するリンク（例: 「明細」と「CSV」を含む）を選ぶ。
+/// サイトの文言変更にクレートの更新なしで追従する用途向け。
+#[derive(Clone)]
+pub struct LinkMatcher(Arc<LinkMatchFn>);
+
+type LinkMatchFn = dyn Fn(&[PageLink]) -> Option<usize> + Send + Sync;
+
+impl LinkMatcher {
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(&[PageLink]) -> Option<usize> + Send + Sync + 'static,
+    {
+        Self(Arc::new(f))
+    }
+
+    /// クリックするリンクを選択
+    pub fn select(&self, links: &[PageLink]) -> Option<usize> {
+        (self.0)(links)
+    }
+}
+
+impl std::fmt::Debug for LinkMatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LinkMatcher(..)")
+    }
+}
+
+/// ログイン方法（保存済みセッションの再利用）
+///
+/// ETC・Dtakolog 共通。Dtakolog の `scrape(.., force_login: true)` は `Always` と同じ。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoginStrategy {
+    /// 保存済みセッションを使わず、常にログインする
+    Always,
+    /// 保存済みセッションを試し、無効ならログインする
+    #[default]
+    ReuseElseLogin,
+    /// 保存済みセッションのみ使う（無効なら `ScraperError::Session`、ログインしない）
+    ///
+    /// 重複ログインによるアカウントロックを確実に避けたい場合に使う。
+    ReuseOnly,
+}
+
+/// ブラウザのヘッドレスモード
+///
+/// 一部の Chromium のバージョンでは `New` でダウンロードしたファイルが保存されないことがあるため、
+/// その場合は `Old` に切り替える（新しい Chrome では旧ヘッドレスモードが chrome-headless-shell に分離されている）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeadlessMode {
+    /// 新ヘッドレスモード（`--headless=new`）
+    #[default]
+    New,
+    /// 旧ヘッドレスモード（`--headless`）
+    Old,
+    /// ウィンドウを表示する
+    Off,
+}
+
+/// リトライ設定（指数バックオフ）
 #[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// 最大試行回数（初回を含む）
+    pub max_attempts: u32,
+    /// 初回リトライまでの待機時間
+    pub initial_backoff: Duration,
+    /// 待機時間の上限
+    pub max_backoff: Duration,
+    /// 複数のスクレイプで共有するリトライ予算（尽きた場合はリトライせずに失敗する）
+    pub budget: Option<Arc<RetryBudget>>,
+}
+
+impl PartialEq for RetryPolicy {
+    fn eq(&self, other: &Self) -> bool {
+        self.max_attempts == other.max_attempts
+            && self.initial_backoff == other.initial_backoff
+            && self.max_backoff == other.max_backoff
+            && match (&self.budget, &other.budget) {
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+}
+
+impl Eq for RetryPolicy {}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(10),
+            budget: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// リトライしない
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// `attempt` 回目（0始まり）の失敗後の待機時間
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
+
+    /// 共有のリトライ予算を設定
+    pub fn with_budget(mut self, budget: Arc<RetryBudget>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+}
+
+/// 時間経過で補充するトークンの上限（`min_per_sec` の秒数分）
+const RETRY_BUDGET_RESERVE_SECS: f64 = 10.0;
+/// スクレイプごとの積み増しで貯められるトークンの上限
+const RETRY_BUDGET_MAX_TOKENS: f64 = 10.0;
+
+/// バッチ全体で共有するリトライ予算（トークンバケット）
+///
+/// 多数のアカウントを実行する際に、各スクレイプが個別にリトライして障害中のサイトへ
+/// 負荷を集中させる（リトライの増幅）のを防ぐ。`Arc` で共有し、[`RetryPolicy::with_budget`] で設定する。
+///
+/// スクレイプ1回ごとに `ratio` トークンを積み、リトライ1回につき1トークンを消費する。
+/// これとは別に毎秒 `min_per_sec` トークン（最大10秒分）を補充する。
+/// 積み増しは10トークン（補充の上限の方が大きければその値）までで、
+/// 平常時に貯まったトークンで障害時のリトライが一斉に許可されることはない。
+#[derive(Debug)]
+pub struct RetryBudget {
+    ratio: f64,
+    min_per_sec: f64,
+    state: Mutex<RetryBudgetState>,
+}
+
+#[derive(Debug)]
+struct RetryBudgetState {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RetryBudget {
+    /// `ratio`: スクレイプ1回あたりに許可するリトライ数（例: `0.2` で20%）
+    ///
+    /// `min_per_sec`: スクレイプ数に関わらず毎秒許可するリトライ数
+    pub fn new(ratio: f64, min_per_sec: u32) -> Self {
+        let min_per_sec = f64::from(min_per_sec);
+        Self {
+            ratio: ratio.max(0.0),
+            min_per_sec,
+            state: Mutex::new(RetryBudgetState {
+                tokens: min_per_sec,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// スクレイプ開始時にトークンを積む
+    pub fn deposit(&self) {
+        let cap = (self.min_per_sec * RETRY_BUDGET_RESERVE_SECS).max(RETRY_BUDGET_MAX_TOKENS);
+        let mut state = self.lock();
+        state.tokens = (state.tokens + self.ratio).min(cap);
+    }
+
+    /// リトライ1回分のトークンを消費（予算が尽きていれば `false`）
+    pub fn try_withdraw(&self) -> bool {
+        let mut state = self.lock();
+        self.refill(&mut state);
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// 現在のトークン数
+    pub fn available(&self) -> f64 {
+        let mut state = self.lock();
+        self.refill(&mut state);
+        state.tokens
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, RetryBudgetState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn refill(&self, state: &mut RetryBudgetState) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.refilled_at).as_secs_f64();
+        state.refilled_at = now;
+
+        let reserve = self.min_per_sec * RETRY_BUDGET_RESERVE_SECS;
+        if state.tokens < reserve {
+            state.tokens = (state.tokens + self.min_per_sec * elapsed).min(reserve);
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct ScraperConfig {
     pub user_id: String,
     pub password: String,
     pub download_path: PathBuf,
+    /// `false` の場合は `headless_mode` に関わらずウィンドウを表示する
     pub headless: bool,
+    /// `headless` が `true` の場合のヘッドレスモード（実際のモードは [`Self::effective_headless_mode`]）
+    pub headless_mode: HeadlessMode,
     pub timeout: Duration,
     pub chrome_path: Option<PathBuf>,
+    /// ダウンロード完了待機のポーリング間隔
+    pub download_poll_interval: Duration,
+    /// 二段階認証用のOTP取得コールバック
+    pub otp_provider: Option<OtpProvider>,
+    /// ページ遷移ごとに呼び出すフック（サイト固有のモーダル対応等）
+    pub on_navigate: Option<NavigateHook>,
+    /// ダウンロードするファイルの種類
+    pub download_kind: DownloadKind,
+    /// ダウンロードリンクの選択関数（未設定時は `download_kind` で判定）
+    pub link_matcher: Option<LinkMatcher>,
+    /// ダウンロードする明細（利用明細 / 請求明細）
+    ///
+    /// 請求明細は列構成が異なるため `validate_schema` のヘッダー検証の対象外。
+    pub report_type: ReportType,
+    /// ブラウザのロケール（`--lang` と Accept-Language に反映）
+    pub locale: String,
+    /// 前回セッションのクッキー (name, value, domain, path)
+    pub session_cookies: Vec<(String, String, String, String)>,
+    /// ブラウザ起動（initialize）失敗時のリトライ設定
+    pub init_retry: RetryPolicy,
+    /// ブラウザの全リクエストに付与する追加HTTPヘッダー（`Cookie` / `Authorization` 等は無視）
+    pub extra_headers: HashMap<String, String>,
+    /// 初期化時にダウンロードディレクトリ内の既存CSV（`.csv` / `.crdownload`）を削除する
+    pub clean_download_dir: bool,
+    /// `download_path/{user_id}/` にダウンロードする（複数アカウント同時実行時の衝突回避）
+    pub namespace_by_user: bool,
+    /// クッキー復元後に直接開くログイン後ページのURL（検索画面）
+    ///
+    /// `session_cookies` と併せて指定した場合のみ、ログイン操作を省略して直接遷移を試みる。
+    pub session_url: Option<String>,
+    /// 保存済みセッション（`session_cookies` / `session_url`）の使い方
+    pub login_strategy: LoginStrategy,
+    /// ダウンロードした明細CSVのヘッダー行を検証する（列構成の変更を検出）
+    pub validate_schema: bool,
+    /// ブラウザのウィンドウサイズ (幅, 高さ)
+    ///
+    /// レイアウトによってはCSVリンクが一定以上の幅でしか表示されないため調整可能にしている。
+    pub window_size: (u32, u32),
+    /// ネットワークログをHAR 1.2形式で保存するパス（`close` 時・実行失敗時に書き出す）
+    pub capture_har: Option<PathBuf>,
+    /// CSVとして受け付けるダウンロードファイルの拡張子（先頭の `.` なし、大文字小文字は区別しない）
+    ///
+    /// エクスポート設定によって `.tsv` / `.txt` で配信される場合に追加する。
+    /// `.crdownload` / `.tmp` の書き込み途中ファイルはこの設定に関わらず除外される。
+    pub accepted_download_extensions: Vec<String>,
+    /// ダウンロードしたCSVを BOM 付き UTF-8 に変換して保存する（デフォルトは元のバイト列のまま）
+    pub csv_utf8_bom: bool,
+    /// ダウンロードファイルの最大サイズ（バイト）。超えた場合は読み込まずにエラーにする
+    pub max_download_size: Option<u64>,
+    /// リネーム後のCSVが存在し、空でなくテキストであることを確認する（デフォルト有効）
+    ///
+    /// ダウンロード中にセッションが切れると0バイトのファイルが保存されることがあるため。
+    pub verify_csv: bool,
+    /// 起動済みブラウザのCDPエンドポイント（例: `ws://browserless:3000`）
+    ///
+    /// 指定した場合はブラウザを起動せずに接続する。`headless` / `chrome_path` / `window_size` /
+    /// `locale` の起動引数は無視される（ロケールの上書きは接続後も適用される）。
+    /// ダウンロード先はブラウザ側のファイルシステムになるため、`download_path` を共有しておくこと。
+    pub cdp_endpoint: Option<String>,
+    /// テスト専用: ブラウザを使わず、このディレクトリの `{user_id}.csv` を取得結果として返す
+    ///
+    /// `execute` の先頭で分岐し、リネーム・サイズ確認・BOM変換・ヘッダー検証等の
+    /// ダウンロード後の処理はそのまま実行する。本番では指定しないこと。
+    /// `{user_id}.html` がある場合はログイン後のページとみなし、アクセス制限ページの判定を行う。
+    pub fixture_dir: Option<PathBuf>,
+    /// CDPハンドラー・ダイアログ応答・HAR記録のタスクを起動するランタイム（未指定時は呼び出し元のランタイム）
+    ///
+    /// ハンドラーはスクレイパーの処理と並行して動作し続ける必要がある。
+    /// 呼び出し元がシングルスレッドのランタイムでブロックする構成の場合はマルチスレッドのランタイムを指定する。
+    pub runtime: Option<tokio::runtime::Handle>,
+}
+
+impl std::fmt::Debug for ScraperConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScraperConfig")
+            .field("user_id", &self.user_id)
+            .field("password", &"***")
+            .field("download_path", &self.download_path)
+            .field("headless", &self.headless)
+            .field("headless_mode", &self.headless_mode)
+            .field("timeout", &self.timeout)
+            .field("chrome_path", &self.chrome_path)
+            .field("download_poll_interval", &self.download_poll_interval)
+            .field("otp_provider", &self.otp_provider)
+            .field("on_navigate", &self.on_navigate)
+            .field("download_kind", &self.download_kind)
+            .field("link_matcher", &self.link_matcher)
+            .field("report_type", &self.report_type)
+            .field("locale", &self.locale)
+            .field("session_cookies", &self.session_cookies.len())
+            .field("init_retry", &self.init_retry)
+            // ヘッダー値・セッションURLは認証トークンを含み得るため出力しない
+            .field(
+                "extra_headers",
+                &self.extra_headers.keys().collect::<Vec<_>>(),
+            )
+            .field("clean_download_dir", &self.clean_download_dir)
+            .field("namespace_by_user", &self.namespace_by_user)
+            .field("session_url", &self.session_url.as_ref().map(|_| "***"))
+            .field("login_strategy", &self.login_strategy)
+            .field("validate_schema", &self.validate_schema)
+            .field("window_size", &self.window_size)
+            .field("capture_har", &self.capture_har)
+            .field(
+                "accepted_download_extensions",
+                &self.accepted_download_extensions,
+            )
+            .field("csv_utf8_bom", &self.csv_utf8_bom)
+            .field("max_download_size", &self.max_download_size)
+            .field("verify_csv", &self.verify_csv)
+            .field("cdp_endpoint", &self.cdp_endpoint)
+            .field("fixture_dir", &self.fixture_dir)
+            .field("runtime", &self.runtime)
+            .finish()
+    }
 }
 
 impl Default for ScraperConfig {
@@ -18,8 +415,33 @@ impl Default for ScraperConfig {
             password: String::new(),
             download_path: PathBuf::from("./downloads"),
             headless: true,
+            headless_mode: HeadlessMode::default(),
             timeout: Duration::from_secs(60),
             chrome_path: std::env::var("CHROME_PATH").ok().map(PathBuf::from),
+            download_poll_interval: Duration::from_millis(500),
+            otp_provider: None,
+            on_navigate: None,
+            download_kind: DownloadKind::default(),
+            link_matcher: None,
+            report_type: ReportType::default(),
+            locale: "ja-JP".to_string(),
+            session_cookies: Vec::new(),
+            init_retry: RetryPolicy::default(),
+            extra_headers: HashMap::new(),
+            clean_download_dir: false,
+            namespace_by_user: false,
+            session_url: None,
+            login_strategy: LoginStrategy::default(),
+            validate_schema: false,
+            window_size: (1280, 800),
+            capture_har: None,
+            accepted_download_extensions: vec!["csv".to_string()],
+            csv_utf8_bom: false,
+            max_download_size: None,
+            verify_csv: true,
+            runtime: None,
+            cdp_endpoint: None,
+            fixture_dir: None,
         }
     }
 }
@@ -38,13 +460,255 @@ impl ScraperConfig {
         self
     }
 
+    /// `true` は `HeadlessMode::New`、`false` は `HeadlessMode::Off` と同じ
     pub fn with_headless(mut self, headless: bool) -> Self {
         self.headless = headless;
+        self.headless_mode = if headless {
+            HeadlessMode::New
+        } else {
+            HeadlessMode::Off
+        };
         self
     }
 
+    pub fn with_headless_mode(mut self, mode: HeadlessMode) -> Self {
+        self.headless = mode != HeadlessMode::Off;
+        self.headless_mode = mode;
+        self
+    }
+
+    /// `headless` / `headless_mode` から決まる実際のヘッドレスモード
+    ///
+    /// `headless` が `false` なら `Off`。`headless` が `true` で `headless_mode` が `Off` の場合は `New`。
+    pub fn effective_headless_mode(&self) -> HeadlessMode {
+        match (self.headless, self.headless_mode) {
+            (false, _) => HeadlessMode::Off,
+            (true, HeadlessMode::Off) => HeadlessMode::New,
+            (true, mode) => mode,
+        }
+    }
+
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
         self.timeout = timeout;
         self
     }
+
+    /// 設定値を検証（ブラウザ起動前の早期エラー検出用）
+    pub fn validate(&self) -> Result<(), ScraperError> {
+        if self.user_id.trim().is_empty() {
+            return Err(ScraperError::Config("user_id is empty".into()));
+        }
+        // 保存済みセッションがあればパスワードなしでも実行できる（セッション無効時はログインで失敗）
+        if self.password.is_empty() && !self.has_session() {
+            return Err(ScraperError::Config("password is empty".into()));
+        }
+        if self.window_size.0 == 0 || self.window_size.1 == 0 {
+            return Err(ScraperError::Config("window_size must be non-zero".into()));
+        }
+        if self.accepted_download_extensions.is_empty() {
+            return Err(ScraperError::Config(
+                "accepted_download_extensions must not be empty".into(),
+            ));
+        }
+        if self.download_poll_interval.is_zero() {
+            return Err(ScraperError::Config(
+                "download_poll_interval must be non-zero".into(),
+            ));
+        }
+        if let Some(ref endpoint) = self.cdp_endpoint {
+            crate::browser::validate_cdp_endpoint(endpoint)?;
+        }
+        Ok(())
+    }
+
+    pub fn with_download_poll_interval(mut self, interval: Duration) -> Self {
+        self.download_poll_interval = interval;
+        self
+    }
+
+    pub fn with_otp_provider(mut self, provider: OtpProvider) -> Self {
+        self.otp_provider = Some(provider);
+        self
+    }
+
+    pub fn with_on_navigate(mut self, hook: NavigateHook) -> Self {
+        self.on_navigate = Some(hook);
+        self
+    }
+
+    pub fn with_download_kind(mut self, kind: DownloadKind) -> Self {
+        self.download_kind = kind;
+        self
+    }
+
+    pub fn with_runtime(mut self, runtime: tokio::runtime::Handle) -> Self {
+        self.runtime = Some(runtime);
+        self
+    }
+
+    pub fn with_login_strategy(mut self, strategy: LoginStrategy) -> Self {
+        self.login_strategy = strategy;
+        self
+    }
+
+    pub fn with_link_matcher(mut self, matcher: LinkMatcher) -> Self {
+        self.link_matcher = Some(matcher);
+        self
+    }
+
+    pub fn with_report_type(mut self, report_type: ReportType) -> Self {
+        self.report_type = report_type;
+        self
+    }
+
+    pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = locale.into();
+        self
+    }
+
+    pub fn with_init_retry(mut self, policy: RetryPolicy) -> Self {
+        self.init_retry = policy;
+        self
+    }
+
+    /// 追加HTTPヘッダーを設定（例: トレース用の `X-Request-Id`）
+    pub fn with_extra_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extra_headers.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn with_clean_download_dir(mut self, clean: bool) -> Self {
+        self.clean_download_dir = clean;
+        self
+    }
+
+    pub fn with_namespace_by_user(mut self, namespace_by_user: bool) -> Self {
+        self.namespace_by_user = namespace_by_user;
+        self
+    }
+
+    /// テスト専用: ブラウザを使わずに `dir/{user_id}.csv` を返すフィクスチャモードにする
+    pub fn with_fixture_mode(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.fixture_dir = Some(dir.into());
+        self
+    }
+
+    /// 起動済みブラウザのCDPエンドポイントに接続する（起動オプションは無視される）
+    pub fn with_cdp_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.cdp_endpoint = Some(endpoint.into());
+        self
+    }
+
+    pub fn with_window_size(mut self, width: u32, height: u32) -> Self {
+        self.window_size = (width, height);
+        self
+    }
+
+    pub fn with_accepted_download_extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.accepted_download_extensions = extensions
+            .into_iter()
+            .map(|ext| ext.into().trim_start_matches('.').to_string())
+            .collect();
+        self
+    }
+
+    pub fn with_csv_utf8_bom(mut self, csv_utf8_bom: bool) -> Self {
+        self.csv_utf8_bom = csv_utf8_bom;
+        self
+    }
+
+    pub fn with_max_download_size(mut self, max_bytes: u64) -> Self {
+        self.max_download_size = Some(max_bytes);
+        self
+    }
+
+    /// ネットワークログをHARファイルに記録（サイト側障害の事後調査用）
+    pub fn with_har_capture(mut self, path: impl Into<PathBuf>) -> Self {
+        self.capture_har = Some(path.into());
+        self
+    }
+
+    pub fn with_verify_csv(mut self, verify_csv: bool) -> Self {
+        self.verify_csv = verify_csv;
+        self
+    }
+
+    pub fn with_validate_schema(mut self, validate_schema: bool) -> Self {
+        self.validate_schema = validate_schema;
+        self
+    }
+
+    /// ブラウザ起動時にこの設定から追加される引数
+    ///
+    /// chromiumoxide が常に付与する既定の引数や、起動ごとに決まる `--user-data-dir` /
+    /// `--remote-debugging-port` は含まない。`cdp_endpoint` 指定時は使われない。
+    pub fn effective_browser_args(&self) -> Vec<String> {
+        let download_dir = self.download_dir().to_string_lossy().to_string();
+        self.browser_args(Some(&download_dir))
+    }
+
+    /// ブラウザ起動引数を構築（`download_dir` はブラウザに渡す形式に変換済みのパス）
+    pub(crate) fn browser_args(&self, download_dir: Option<&str>) -> Vec<String> {
+        let mut args = vec![
+            format!(
+                "--window-size={},{}",
+                self.window_size.0, self.window_size.1
+            ),
+            format!("--lang={}", self.locale),
+        ];
+        if let Some(download_dir) = download_dir {
+            args.push(format!("--download.default_directory={}", download_dir));
+        }
+        // headless-shell使用時はsandbox無効化が必要
+        if self.chrome_path.is_some() {
+            args.push("--no-sandbox".to_string());
+        }
+        match self.effective_headless_mode() {
+            HeadlessMode::New => args.push("--headless=new".to_string()),
+            HeadlessMode::Old => args.push("--headless".to_string()),
+            HeadlessMode::Off => {}
+        }
+        args
+    }
+
+    /// 実際のダウンロード先ディレクトリ
+    ///
+    /// `namespace_by_user` 有効時は `download_path/{user_id}/`（パス区切り等は `_` に置換）。
+    pub fn download_dir(&self) -> PathBuf {
+        if !self.namespace_by_user {
+            return self.download_path.clone();
+        }
+        let namespace: String = self
+            .user_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '@') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.download_path.join(namespace)
+    }
+
+    /// 保存済みセッション（クッキーと遷移先URL）が指定されているか
+    pub fn has_session(&self) -> bool {
+        !self.session_cookies.is_empty() && self.session_url.is_some()
+    }
+
+    /// 保存済みセッションを指定（ログイン済みならログイン操作を省略）
+    pub fn with_session(
+        mut self,
+        cookies: Vec<(String, String, String, String)>,
+        url: impl Into<String>,
+    ) -> Self {
+        self.session_cookies = cookies;
+        self.session_url = Some(url.into());
+        self
+    }
 }
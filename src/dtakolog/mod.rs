@@ -7,6 +7,6 @@ mod types;
 
 pub use scraper::DtakologScraper;
 pub use types::{
-    DtakologConfig, DtakologData, DtakologResult, GrpcResponse, VehicleData,
-    VideoNotificationResult,
+    flatten_records, DtakologConfig, DtakologData, DtakologResult, DvrNotification, GrpcResponse,
+    PendingDownload, RequestedDownloads, SendSummary, VehicleData, VideoNotificationResult,
 };
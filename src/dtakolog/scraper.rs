@@ -2,28 +2,39 @@
 //!
 //! Vehicleデータを取得してgRPC経由でrust-logiに送信する
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use chrono::{offset::FixedOffset, Utc};
+use async_trait::async_trait;
+use chrono::Utc;
 use chromiumoxide::browser::{Browser, BrowserConfig};
-use chromiumoxide::cdp::browser_protocol::network::CookieParam;
+use chromiumoxide::cdp::browser_protocol::network::{CookieParam, GetCookiesParams};
+use chromiumoxide::handler::Handler;
 use chromiumoxide::page::ScreenshotParams;
 use chromiumoxide::Page;
-use futures::StreamExt;
+use futures::stream::{self, StreamExt};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use tokio::time::sleep;
 use tracing::{debug, error, info, warn};
 
+use crate::browser::{wait_for_js_fn, wait_for_selector, ConnectionMonitor, ConsoleCapture};
+use crate::config::LoginStrategy;
 use crate::error::ScraperError;
+use crate::telemetry;
+use crate::traits::Scraper;
 
 use super::types::{
     DtakologConfig, DtakologData, DtakologResult, DvrFileInfo, DvrNotification, GrpcResponse,
-    VehicleData, VideoNotificationResult,
+    PendingDownload, SendSummary, VehicleData, VideoNotificationResult,
 };
 
 /// リトライ設定
 const MAX_RETRIES: u32 = 3;
 const INITIAL_BACKOFF_MS: u64 = 1000;
+const MAX_BACKOFF_MS: u64 = 10_000;
 
 /// ネットワークアイドル待機のタイムアウト（ミリ秒）
 const NETWORK_IDLE_TIMEOUT_MS: u64 = 30000;
@@ -31,11 +42,351 @@ const NETWORK_IDLE_TIMEOUT_MS: u64 = 30000;
 const NETWORK_IDLE_CHECK_INTERVAL_MS: u64 = 500;
 /// ページ安定待機のタイムアウト（ミリ秒）
 const PAGE_STABLE_TIMEOUT_MS: u64 = 10000;
+/// サイトのJavaScript API（車両データ・DVR関連の各メソッドを持つグローバルオブジェクト）
+const VENUS_BRIDGE_SERVICE: &str = "VenusBridgeService";
+/// 車両一覧グリッド
+const VEHICLE_GRID_SELECTOR: &str = "#igGrid-VenusMain-VehicleList";
+/// 車両一覧グリッドのデータ行
+const VEHICLE_GRID_ROW_SELECTOR: &str = "#igGrid-VenusMain-VehicleList tbody tr";
+/// グリッドにデータ行が表示されるまでの待機上限
+const GRID_ROWS_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 動画URLのデフォルトベース
+const DEFAULT_DVR_BASE_URL: &str = "http://theearth-np.com/dvrData";
+
+/// CDPリクエストのタイムアウト（デフォルトより延長）
+const CDP_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 車両データ取得の待機上限に対するCDPリクエストタイムアウトの余裕
+const CDP_REQUEST_TIMEOUT_MARGIN: Duration = Duration::from_secs(10);
+
+/// 動画URLの到達確認（HEAD）のタイムアウト
+const VIDEO_URL_VERIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 書き込み途中の一時ファイルの拡張子
+const TMP_EXTENSION: &str = "tmp";
+
+/// この時間より前に更新された一時ファイルのみ、前回の実行の残骸として削除する
+///
+/// 同じ `./data` に並行して書き込み中の他のスクレイパーの一時ファイルを消さないため。
+const ORPHANED_TMP_MIN_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// `console.error` と未捕捉例外を `window.__scraperConsoleErrors` に記録するスクリプト
+const CONSOLE_ERROR_CAPTURE_SCRIPT: &str = r#"
+    window.__scraperConsoleErrors = [];
+    const origError = console.error;
+    console.error = (...args) => {
+        window.__scraperConsoleErrors.push(args.map(String).join(' '));
+        origError.apply(console, args);
+    };
+    window.addEventListener('error', (e) => {
+        window.__scraperConsoleErrors.push(String(e.message));
+    });
+"#;
+
+/// 一時ファイルに書き込んでからリネームする（書き込み途中のファイルを見せない）
+fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let tmp_path = tmp_path_for(path);
+    if let Err(e) = std::fs::write(&tmp_path, contents) {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+    std::fs::rename(&tmp_path, path)
+}
+
+/// `foo.json` → `foo.json.tmp`
+fn tmp_path_for(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(TMP_EXTENSION);
+    path.with_file_name(name)
+}
+
+/// 既出の VehicleCD を除いた車両データを返す（`seen` を更新）
+///
+/// VehicleCD を持たない要素は重複判定できないためそのまま残す。
+fn dedup_by_vehicle_cd(data: DtakologData, seen: &mut HashSet<String>) -> DtakologData {
+    data.into_iter()
+        .filter(|item| match item.get("VehicleCD") {
+            Some(serde_json::Value::String(cd)) => seen.insert(cd.clone()),
+            Some(cd) if !cd.is_null() => seen.insert(cd.to_string()),
+            _ => true,
+        })
+        .collect()
+}
+
+/// ブランチごとの取得結果 (branch_id, 車両データ)
+type BranchResult = (String, Result<DtakologData, ScraperError>);
+
+/// 抽出した車両データ (車両, 生データ, 失敗したブランチ)
+type ExtractedVehicles = (Vec<VehicleData>, DtakologData, Vec<(String, ScraperError)>);
+
+/// ASP.NET のセッションクッキー名
+const SESSION_COOKIE_NAME: &str = "ASP.NET_SessionId";
+
+/// クッキー (name, value) 一覧からセッションIDを取得
+fn session_id_from_cookies(cookies: &[(String, String)]) -> Option<String> {
+    cookies
+        .iter()
+        .find(|(name, value)| name.eq_ignore_ascii_case(SESSION_COOKIE_NAME) && !value.is_empty())
+        .map(|(_, value)| value.clone())
+}
+
+/// 車両データ取得のスクリプトが VenusBridgeService を見つけられなかった場合のエラーメッセージ
+const VEHICLE_FETCH_UNAVAILABLE: &str = "VEHICLE_FETCH_UNAVAILABLE";
+/// 車両データ取得のスクリプトがタイムアウトした場合のエラーメッセージ
+const VEHICLE_FETCH_TIMED_OUT: &str = "VEHICLE_FETCH_TIMED_OUT";
+
+/// 車両データ取得スクリプトの失敗をエラーに変換
+///
+/// タイムアウト・サービス未定義は `Extraction`、それ以外（サービス側のエラー等）は `JavaScript` とする。
+fn vehicle_fetch_error(message: &str, timeout_secs: u64) -> ScraperError {
+    if message.contains(VEHICLE_FETCH_TIMED_OUT) {
+        ScraperError::Extraction(format!(
+            "VehicleStateTableForBranchEx timed out after {}s (vehicle_fetch_timeout_secs)",
+            timeout_secs
+        ))
+    } else if message.contains(VEHICLE_FETCH_UNAVAILABLE) {
+        ScraperError::Extraction(format!(
+            "{}.VehicleStateTableForBranchEx not found",
+            VENUS_BRIDGE_SERVICE
+        ))
+    } else {
+        ScraperError::JavaScript(message.to_string())
+    }
+}
+
+/// VenusBridgeService の応答JSONをパース
+///
+/// セッション切れでHTMLのエラーページが返る場合と区別できるよう、
+/// 失敗時は応答の先頭部分をエラーメッセージに含める。
+fn parse_vehicle_json(json_str: &str) -> Result<DtakologData, ScraperError> {
+    const PREVIEW_CHARS: usize = 200;
+
+    serde_json::from_str(json_str).map_err(|e| {
+        let preview: String = json_str.chars().take(PREVIEW_CHARS).collect();
+        let ellipsis = if json_str.chars().count() > PREVIEW_CHARS {
+            "..."
+        } else {
+            ""
+        };
+        ScraperError::Json(format!("{} (payload: {:?}{})", e, preview, ellipsis))
+    })
+}
+
+/// CDPリクエストのタイムアウト
+///
+/// 車両データ取得の `evaluate` は応答（Promise の解決）までCDPリクエストとして待つため、
+/// `vehicle_fetch_timeout_secs` に余裕を加えた値が既定値を超える場合はそちらを使う。
+fn cdp_request_timeout(config: &DtakologConfig) -> Duration {
+    CDP_REQUEST_TIMEOUT
+        .max(Duration::from_secs(config.vehicle_fetch_timeout_secs) + CDP_REQUEST_TIMEOUT_MARGIN)
+}
+
+/// 動画URL確認用のHTTPクライアント（プロセス内で1つを共有し、接続を使い回す）
+fn video_http_client() -> Result<&'static reqwest::Client, ScraperError> {
+    static CLIENT: std::sync::OnceLock<Result<reqwest::Client, String>> =
+        std::sync::OnceLock::new();
+    CLIENT
+        .get_or_init(|| {
+            reqwest::Client::builder()
+                .timeout(VIDEO_URL_VERIFY_TIMEOUT)
+                .build()
+                .map_err(|e| e.to_string())
+        })
+        .as_ref()
+        .map_err(|e| ScraperError::Config(format!("Failed to build HTTP client: {}", e)))
+}
+
+/// `url` に送信されるクッキーのみの Cookie ヘッダー（該当なし・取得失敗時は `None`）
+///
+/// ドメイン・パス・Secure 属性の判定はブラウザ（Network.getCookies の `urls`）に任せる。
+async fn cookie_header_for_url(page: &Page, url: &str) -> Option<String> {
+    let cookies = match page
+        .execute(GetCookiesParams::builder().url(url).build())
+        .await
+    {
+        Ok(response) => response.result.cookies,
+        Err(e) => {
+            debug!("Failed to get cookies for video URL verification: {}", e);
+            return None;
+        }
+    };
+    if cookies.is_empty() {
+        return None;
+    }
+    Some(
+        cookies
+            .iter()
+            .map(|c| format!("{}={}", c.name, c.value))
+            .collect::<Vec<_>>()
+            .join("; "),
+    )
+}
+
+/// エラーに収集済みのコンソール出力を付加
+///
+/// 全件はログに出力し、メッセージを持つエラーには末尾の数行を追記する。
+fn attach_console(error: ScraperError, console: Option<&ConsoleCapture>) -> ScraperError {
+    const TAIL_LINES: usize = 10;
+
+    let Some(entries) = console.map(|c| c.entries()).filter(|e| !e.is_empty()) else {
+        return error;
+    };
+    warn!(error = %error, console = ?entries, "browser console output at failure");
+
+    let tail = entries[entries.len().saturating_sub(TAIL_LINES)..].join(" | ");
+    match error {
+        ScraperError::Extraction(msg) => {
+            ScraperError::Extraction(format!("{} [console: {}]", msg, tail))
+        }
+        ScraperError::JavaScript(msg) => {
+            ScraperError::JavaScript(format!("{} [console: {}]", msg, tail))
+        }
+        ScraperError::Login(msg) => ScraperError::Login(format!("{} [console: {}]", msg, tail)),
+        ScraperError::Session(msg) => ScraperError::Session(format!("{} [console: {}]", msg, tail)),
+        other => other,
+    }
+}
+
+/// リトライ待機時間（full jitter: 0 〜 min(初期値 * 2^attempt, 上限) の一様乱数）
+///
+/// 複数のスクレイパーが同時に失敗した場合に、再試行が同じタイミングに集中しないようにする。
+/// 乱数は `rng` から取る（テストではシードを固定して待機時間を再現する）。
+fn backoff_with_jitter(attempt: u32, rng: &mut impl Rng) -> Duration {
+    let cap = INITIAL_BACKOFF_MS
+        .saturating_mul(2u64.saturating_pow(attempt))
+        .min(MAX_BACKOFF_MS);
+    Duration::from_millis(rng.gen_range(0..=cap))
+}
+
+/// `operation` を最大 `MAX_RETRIES` 回試行（リトライ可能なエラーのみ再試行）
+///
+/// 試行の間は [`backoff_with_jitter`] だけ待機し、最後の試行の後は待たずにそのエラーを返す。
+async fn retry_with_backoff<T, F, Fut>(
+    rng: &mut StdRng,
+    mut operation: F,
+) -> Result<T, ScraperError>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, ScraperError>>,
+{
+    let mut last_error = None;
+
+    for attempt in 0..MAX_RETRIES {
+        match operation().await {
+            Ok(value) => return Ok(value),
+            Err(e) if e.is_retryable() => {
+                if attempt + 1 < MAX_RETRIES {
+                    let backoff = backoff_with_jitter(attempt, rng);
+                    warn!(
+                        attempt = attempt + 1,
+                        backoff_ms = backoff.as_millis() as u64,
+                        error = %e,
+                        "gRPC attempt failed, retrying"
+                    );
+                    sleep(backoff).await;
+                }
+                last_error = Some(e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Err(
+        last_error.unwrap_or_else(|| ScraperError::GrpcConnectionFailed {
+            retries: MAX_RETRIES,
+            message: "Max retries exceeded".to_string(),
+        }),
+    )
+}
+
+/// 生データの保存ファイル名（`vehicles_{timestamp}_{pid}-{token}.json`）
+///
+/// タイムスタンプは秒単位のため、同じ作業ディレクトリで複数のスクレイパーが
+/// 同じ秒に完了しても上書きし合わないよう、プロセスIDと乱数トークンを付与する。
+fn raw_data_filename(timestamp: &str) -> String {
+    let token: u32 = rand::thread_rng().gen();
+    format!(
+        "vehicles_{}_{}-{:08x}.json",
+        timestamp,
+        std::process::id(),
+        token
+    )
+}
+
+/// ログイン完了確認の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LoginState {
+    /// ログインボタン押下後、ホームボタンの出現待ち
+    AwaitingHome,
+    /// 既ログインのポップアップを閉じた後
+    PopupDismissed,
+    /// ログインボタンを再押下した後
+    LoginRetried,
+}
+
+impl LoginState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LoginState::AwaitingHome => "awaiting-home-button",
+            LoginState::PopupDismissed => "popup-dismissed",
+            LoginState::LoginRetried => "login-retried",
+        }
+    }
+}
+
+/// JSON値の型名
+fn json_type_name(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "bool",
+        serde_json::Value::Number(_) => "number",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    }
+}
+
+/// 前回の実行で残った一時ファイル（更新から `min_age` 以上経過したもの）を削除
+fn cleanup_orphaned_tmp_files(dir: &Path, min_age: Duration) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let is_stale = |path: &Path| {
+        std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age >= min_age)
+    };
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+        if path.extension().is_some_and(|ext| ext == TMP_EXTENSION) && is_stale(&path) {
+            match std::fs::remove_file(&path) {
+                Ok(_) => info!("Removed orphaned temp file: {}", path.display()),
+                Err(e) => warn!(
+                    "Failed to remove orphaned temp file {}: {}",
+                    path.display(),
+                    e
+                ),
+            }
+        }
+    }
+}
 
 /// Dtakolog スクレイパー
 pub struct DtakologScraper {
     config: DtakologConfig,
     browser: Option<Browser>,
+    connection: Option<ConnectionMonitor>,
+    /// 直近に取得した先頭のVehicleオブジェクト（スキーマ監視用）
+    last_vehicle_sample: Mutex<Option<serde_json::Value>>,
+    /// 直近のログイン直後のページURL
+    last_url: Mutex<Option<String>>,
+    /// 直近に `open_main_page` で開いたページ（`screenshot` の対象）
+    current_page: Mutex<Option<Page>>,
+    /// 起動したブラウザのユーザーデータディレクトリ（終了時に削除）
+    user_data_dir: Option<PathBuf>,
 }
 
 impl DtakologScraper {
@@ -44,14 +395,76 @@ impl DtakologScraper {
         Self {
             config,
             browser: None,
+            connection: None,
+            last_vehicle_sample: Mutex::new(None),
+            last_url: Mutex::new(None),
+            current_page: Mutex::new(None),
+            user_data_dir: None,
         }
     }
 
+    /// 直近のログイン直後に表示されていたページのURL（ログインしていなければ `None`）
+    pub fn last_url(&self) -> Option<String> {
+        self.last_url
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// 直近に取得したVehicleオブジェクトのフィールド名とJSON型を返す
+    ///
+    /// サイト側のフィールド追加・削除（スキーマ変更）の監視用。
+    /// `scrape` で先頭のVehicleオブジェクトを取得した後に呼び出すこと。
+    pub fn sample_schema(&self) -> Result<BTreeMap<String, String>, ScraperError> {
+        let sample = self
+            .last_vehicle_sample
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let obj = sample
+            .as_ref()
+            .and_then(|v| v.as_object())
+            .ok_or_else(|| ScraperError::Extraction("No vehicle data fetched yet".to_string()))?;
+
+        Ok(obj
+            .iter()
+            .map(|(k, v)| (k.clone(), json_type_name(v).to_string()))
+            .collect())
+    }
+
     /// ブラウザを初期化
     pub async fn initialize(&mut self) -> Result<(), ScraperError> {
         info!("Initializing browser for dtakolog scraper...");
+        self.config.validate()?;
+        if self.config.verify_video_urls {
+            video_http_client()?;
+        }
 
-        // ユニークなユーザーデータディレクトリを生成
+        let (browser, handler) = match self.config.cdp_endpoint {
+            Some(ref endpoint) => {
+                crate::browser::connect(endpoint, cdp_request_timeout(&self.config)).await?
+            }
+            None => {
+                // 起動に失敗しても作成済みのディレクトリを削除できるよう先に記録する
+                let user_data_dir = self.new_user_data_dir();
+                self.user_data_dir = Some(user_data_dir.clone());
+                self.launch_browser(&user_data_dir).await?
+            }
+        };
+
+        // ハンドラータスクを起動（接続断を監視）
+        self.connection = Some(ConnectionMonitor::spawn(
+            handler,
+            self.config.runtime.as_ref(),
+        ));
+
+        self.browser = Some(browser);
+        info!("Browser initialized successfully");
+
+        Ok(())
+    }
+
+    /// ユニークなユーザーデータディレクトリのパスを生成（`user_data_base` 未指定時はシステムの一時ディレクトリ）
+    fn new_user_data_dir(&self) -> PathBuf {
         let unique_id = format!(
             "{}-{}",
             std::process::id(),
@@ -60,8 +473,18 @@ impl DtakologScraper {
                 .unwrap_or_default()
                 .as_nanos()
         );
-        let user_data_dir = std::env::temp_dir().join(format!("dtakolog-{}", unique_id));
+        self.config
+            .user_data_base
+            .clone()
+            .unwrap_or_else(std::env::temp_dir)
+            .join(format!("dtakolog-{}", unique_id))
+    }
 
+    /// ブラウザを起動
+    async fn launch_browser(
+        &self,
+        user_data_dir: &Path,
+    ) -> Result<(Browser, Handler), ScraperError> {
         // Chrome パスを取得
         let chrome_path = std::env::var("CHROME_PATH")
             .or_else(|_| std::env::var("CHROMIUM_PATH"))
@@ -70,131 +493,120 @@ impl DtakologScraper {
         // ブラウザ設定を構築
         let mut builder = BrowserConfig::builder()
             .chrome_executable(chrome_path)
-            .user_data_dir(&user_data_dir);
+            .user_data_dir(user_data_dir);
 
         if !self.config.headless {
             builder = builder.with_head();
         }
 
+        let args = self.config.effective_browser_args();
+        debug!(args = ?args, "browser launch args");
         builder = builder
             .no_sandbox()
-            .request_timeout(Duration::from_secs(60)) // CDPリクエストタイムアウトを延長
-            .arg("--disable-blink-features=AutomationControlled")
-            .arg("--disable-dev-shm-usage")
-            .arg("--disable-gpu")
-            .arg("--disable-web-security") // CORS制限を無効化
-            .arg("--allow-running-insecure-content");
-
-        if self.config.debug {
-            builder = builder.arg("--enable-logging=stderr").arg("--v=1");
-        }
+            .request_timeout(cdp_request_timeout(&self.config)) // CDPリクエストタイムアウトを延長
+            .args(args);
 
         let browser_config = builder
             .build()
             .map_err(|e| ScraperError::BrowserInit(e.to_string()))?;
+        debug!(config = ?browser_config, "browser config");
 
-        // ブラウザを起動
-        let (browser, mut handler) = Browser::launch(browser_config)
+        Browser::launch(browser_config)
             .await
-            .map_err(|e| ScraperError::BrowserInit(e.to_string()))?;
-
-        // ハンドラータスクを起動
-        tokio::spawn(async move {
-            while let Some(event) = handler.next().await {
-                debug!("Browser event: {:?}", event);
-            }
-        });
-
-        self.browser = Some(browser);
-        info!("Browser initialized successfully");
-
-        Ok(())
+            .map_err(|e| ScraperError::BrowserInit(e.to_string()))
     }
 
     /// Vehicleデータを取得
+    #[tracing::instrument(
+        skip(self, session_cookies),
+        fields(
+            scraper = "dtakolog",
+            comp_id = %self.config.comp_id,
+            user_name = %self.config.user_name
+        )
+    )]
     pub async fn scrape(
         &self,
         session_cookies: Option<Vec<(String, String, String, String)>>, // (name, value, domain, path)
         force_login: bool,
+    ) -> Result<DtakologResult, ScraperError> {
+        let start = tokio::time::Instant::now();
+        let result = self.scrape_inner(session_cookies, force_login).await;
+        telemetry::record_scrape("dtakolog", result.is_ok(), start.elapsed());
+        result
+    }
+
+    async fn scrape_inner(
+        &self,
+        session_cookies: Option<Vec<(String, String, String, String)>>,
+        force_login: bool,
     ) -> Result<DtakologResult, ScraperError> {
         info!("Starting dtakolog scrape...");
 
-        let browser = self
-            .browser
-            .as_ref()
-            .ok_or_else(|| ScraperError::BrowserInit("Browser not initialized".to_string()))?;
+        let (page, session_id, console) = self.open_main_page(session_cookies, force_login).await?;
 
-        // 新しいページを作成
-        let page = browser
-            .new_page("about:blank")
+        // データを抽出
+        let extracted = async {
+            let data = self.extract_vehicle_data(&page, console.as_ref()).await?;
+
+            // 映像通知処理前にページ安定化を待機（ヘッドレスモードで重要）
+            info!("Waiting for page to stabilize after vehicle data extraction...");
+            self.wait_request_idle(&page).await?;
+            self.wait_stable(&page).await?;
+            Ok(data)
+        };
+        let (vehicles, raw_data, branch_errors) = extracted
             .await
-            .map_err(|e| ScraperError::BrowserInit(e.to_string()))?;
+            .map_err(|e| attach_console(e, console.as_ref()))?;
 
-        // セッションクッキーを復元
-        if let Some(cookies) = session_cookies {
-            if !force_login {
-                for (name, value, domain, path) in cookies {
-                    let cookie_param = CookieParam::builder()
-                        .name(&name)
-                        .value(&value)
-                        .domain(&domain)
-                        .path(&path)
-                        .build();
+        // データをファイルに保存（設定で無効化可能）
+        let raw_data_path = if self.config.save_raw_data {
+            self.save_raw_data(&raw_data).await
+        } else {
+            debug!("Raw data saving disabled, skipping");
+            None
+        };
 
-                    if let Ok(param) = cookie_param {
-                        if let Err(e) = page.set_cookie(param).await {
-                            debug!("Failed to set cookie: {}", e);
-                        }
-                    }
-                }
-            }
-        }
+        // 接続断の場合は以降の処理がタイムアウトまで待たされるため、ここで打ち切る
+        self.check_connection()?;
 
-        // メインページにナビゲーション試行
-        let session_id = match self.navigate_to_main(&page).await {
-            Ok(_) => {
-                info!("Navigation successful without login");
-                format!("session_{}", Utc::now().timestamp())
-            }
-            Err(e) => {
-                info!("First navigation failed, attempting login: {}", e);
-                let sid = self.login(&page).await?;
-                self.navigate_to_main(&page).await?;
-                sid
+        // 映像通知の動画処理（エラーがあってもジョブ失敗にはしない）
+        let (video_notifications, pending_downloads) = if !self.config.process_videos {
+            info!("Video notification processing disabled, skipping");
+            (Vec::new(), Vec::new())
+        } else {
+            match self.collect_video_notifications(&page).await {
+                Ok(collected) => collected,
+                Err(e) => {
+                    warn!(error = %e, "video notification processing failed");
+                    (Vec::new(), Vec::new())
+                }
             }
         };
 
-        // データを抽出
-        let (vehicles, raw_data) = self.extract_vehicle_data(&page).await?;
-
-        // 映像通知処理前にページ安定化を待機（ヘッドレスモードで重要）
-        info!("Waiting for page to stabilize after vehicle data extraction...");
-        self.wait_request_idle(&page).await?;
-        self.wait_stable(&page).await?;
-
-        // データをファイルに保存
-        self.save_raw_data(&raw_data).await;
-
         // gRPC送信（設定がある場合）
-        let grpc_response = if self.config.grpc_url.is_some() {
-            match self.send_to_grpc_with_retry(&raw_data).await {
-                Ok(resp) => Some(resp),
+        let (grpc_response, send_summary) = if self.config.grpc_url.is_some() {
+            match self
+                .send_to_grpc_with_retry(&raw_data, &video_notifications)
+                .await
+            {
+                Ok(resp) => {
+                    let summary = SendSummary::new(&raw_data, &vehicles, &video_notifications);
+                    info!(
+                        vehicles_sent = summary.vehicles_sent,
+                        vehicles_filtered = summary.vehicles_filtered,
+                        notifications_sent = summary.notifications_sent,
+                        "sent to gRPC"
+                    );
+                    (Some(resp), Some(summary))
+                }
                 Err(e) => {
-                    warn!("Failed to send to gRPC: {}", e);
-                    None
+                    warn!(error = %e, "failed to send to gRPC");
+                    (None, None)
                 }
             }
         } else {
-            None
-        };
-
-        // 映像通知の動画処理（エラーがあってもジョブ失敗にはしない）
-        let video_notifications = match self.process_video_notifications(&page).await {
-            Ok(notifications) => notifications,
-            Err(e) => {
-                warn!("Video notification processing failed: {}", e);
-                Vec::new()
-            }
+            (None, None)
         };
 
         // ページを閉じる
@@ -207,7 +619,16 @@ impl DtakologScraper {
             raw_data,
             session_id,
             grpc_response,
+            send_summary,
             video_notifications,
+            pending_downloads,
+            raw_data_path,
+            last_url: self.last_url(),
+            console_logs: console.map(|c| c.entries()).unwrap_or_default(),
+            branch_errors: branch_errors
+                .into_iter()
+                .map(|(branch_id, e)| (branch_id, Arc::new(e)))
+                .collect(),
         })
     }
 
@@ -234,7 +655,9 @@ impl DtakologScraper {
             .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
 
         if !has_pass_field.into_value::<bool>().unwrap_or(false) {
-            return Err(ScraperError::Login("Login form not found".to_string()));
+            return Err(ScraperError::Login(
+                "Login form not found (state: form-not-found)".to_string(),
+            ));
         }
 
         // ポップアップ処理
@@ -291,133 +714,145 @@ impl DtakologScraper {
         self.wait_request_idle(page).await?;
         sleep(Duration::from_secs(5)).await;
 
-        // ログイン成功確認（リトライ付き）
-        let mut login_success = false;
-        for i in 0..5 {
-            match page
-                .evaluate("document.querySelector('#Button1st_7') !== null")
-                .await
-            {
-                Ok(result) => {
-                    login_success = result.into_value::<bool>().unwrap_or(false);
-                    if login_success {
-                        break;
-                    }
-                }
-                Err(e) => {
-                    debug!("Login check attempt {} failed: {}", i + 1, e);
-                }
-            }
-            sleep(Duration::from_secs(1)).await;
-        }
-        let login_success = login_success;
+        // ログイン完了確認（上限時間付きの状態遷移）
+        self.await_login_completion(page).await?;
 
-        // ログイン成功時、ホームボタンをクリックしてメインページへ遷移
-        if login_success {
-            info!("Login successful, clicking home button to navigate to main page...");
-            page.evaluate("document.querySelector('#Button1st_7').click()")
-                .await
-                .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
+        // ログイン成功後、ページが安定するまで待機
+        info!("Login completed, waiting for page to stabilize...");
+        self.wait_request_idle(page).await?;
+        self.wait_stable(page).await?;
 
-            // ナビゲーション完了を待機
-            self.wait_request_idle(page).await?;
-            sleep(Duration::from_secs(5)).await;
-        }
+        // ログイン後のページURLを記録（呼び出し側の分岐・エラー報告用）
+        let current_url = page
+            .evaluate("window.location.href")
+            .await
+            .map_err(|e| ScraperError::JavaScript(e.to_string()))?
+            .into_value::<String>()
+            .unwrap_or_default();
+        info!("Post-login URL: {}", current_url);
+        *self.last_url.lock().unwrap_or_else(|e| e.into_inner()) = Some(current_url);
+
+        let session_id = self.current_session_id(page).await;
+        info!("login successful");
+        Ok(session_id)
+    }
 
-        if !login_success {
-            // 既にログイン済みの場合（ポップアップが表示される）
-            info!("Button1st_7 not found, checking for popup...");
-            let has_popup = page
-                .evaluate("document.querySelector('#popup_1') !== null")
-                .await
-                .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
+    /// 現在の認証済みセッションのID
+    ///
+    /// ASP.NET のセッションクッキー（`ASP.NET_SessionId`）の値を返す。
+    /// クッキーが取得できない場合は `session_<UNIXタイムスタンプ>` 形式の合成値を返す
+    /// （この場合はセッション再利用には使えない）。
+    async fn current_session_id(&self, page: &Page) -> String {
+        let cookies = match page.get_cookies().await {
+            Ok(cookies) => cookies
+                .into_iter()
+                .map(|c| (c.name, c.value))
+                .collect::<Vec<_>>(),
+            Err(e) => {
+                debug!("Failed to get cookies: {}", e);
+                Vec::new()
+            }
+        };
 
-            if has_popup.into_value::<bool>().unwrap_or(false) {
-                info!("Popup found, clicking to dismiss...");
-                page.evaluate("document.querySelector('#popup_1').click()")
-                    .await
-                    .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
+        session_id_from_cookies(&cookies).unwrap_or_else(|| {
+            warn!("ASP.NET session cookie not found, using synthetic session_id");
+            format!("session_{}", Utc::now().timestamp())
+        })
+    }
 
-                sleep(Duration::from_secs(3)).await;
-                self.wait_request_idle(page).await?;
+    /// ログインボタン押下後、ホームボタン（#Button1st_7）が出るまで状態遷移で待機
+    ///
+    /// - ホームボタン待ち → 出なければ既ログインのポップアップを閉じる
+    /// - ポップアップを閉じた後もホームボタンが出なければログインボタンを再押下
+    /// - 再押下後も出なければエラー
+    ///
+    /// 全体を `login_timeout_secs` で打ち切り、停止した状態をエラーに含める。
+    async fn await_login_completion(&self, page: &Page) -> Result<(), ScraperError> {
+        const WAIT_PER_STATE: Duration = Duration::from_secs(5);
+
+        let timeout = Duration::from_secs(self.config.login_timeout_secs);
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut state = LoginState::AwaitingHome;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            if remaining.is_zero() {
+                return Err(ScraperError::Login(format!(
+                    "login did not complete within {}s (state: {})",
+                    timeout.as_secs(),
+                    state.as_str()
+                )));
+            }
 
-                // ポップアップ閉じた後の状態を確認
-                let current_url = page
-                    .evaluate("window.location.href")
+            if wait_for_selector(page, "#Button1st_7", WAIT_PER_STATE.min(remaining)).await? {
+                info!(
+                    state = state.as_str(),
+                    "Login successful, clicking home button to navigate to main page..."
+                );
+                page.evaluate("document.querySelector('#Button1st_7').click()")
                     .await
                     .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
-                let url = current_url.into_value::<String>().unwrap_or_default();
-                info!("URL after popup dismiss: {}", url);
+                self.wait_request_idle(page).await?;
+                sleep(Duration::from_secs(5)).await;
+                return Ok(());
+            }
 
-                // ホームボタンを確認
-                let has_home = page
-                    .evaluate("document.querySelector('#Button1st_7') !== null")
-                    .await
-                    .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
+            // 全体の上限に達した場合は現在の状態のままタイムアウトとして報告する
+            if tokio::time::Instant::now() >= deadline {
+                continue;
+            }
 
-                if has_home.into_value::<bool>().unwrap_or(false) {
-                    info!("Home button found, clicking...");
-                    page.evaluate("document.querySelector('#Button1st_7').click()")
+            state = match state {
+                LoginState::AwaitingHome => {
+                    // 既にログイン済みの場合（ポップアップが表示される）
+                    info!("Button1st_7 not found, checking for popup...");
+                    if !self.element_exists(page, "#popup_1").await {
+                        return Err(ScraperError::Login(
+                            "Login verification failed: neither home button nor popup appeared (state: button-never-appeared)".to_string(),
+                        ));
+                    }
+
+                    info!("Popup found, clicking to dismiss...");
+                    page.evaluate("document.querySelector('#popup_1').click()")
                         .await
                         .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
-                    self.wait_request_idle(page).await?;
                     sleep(Duration::from_secs(3)).await;
-                } else {
-                    info!("Home button not found after popup, trying to click login button again...");
-                    // ログインボタンをもう一度クリック
-                    if let Ok(_) = page
-                        .evaluate("document.querySelector('#imgLogin').click()")
+                    self.wait_request_idle(page).await?;
+                    LoginState::PopupDismissed
+                }
+                LoginState::PopupDismissed => {
+                    info!(
+                        "Home button not found after popup, trying to click login button again..."
+                    );
+                    page.evaluate("document.querySelector('#imgLogin').click()")
                         .await
-                    {
-                        info!("Clicked login button again, waiting for navigation...");
-                        self.wait_request_idle(page).await?;
-                        sleep(Duration::from_secs(5)).await;
-
-                        // ログイン後にホームボタンを再確認
-                        for i in 0..5 {
-                            let has_home_retry = page
-                                .evaluate("document.querySelector('#Button1st_7') !== null")
-                                .await
-                                .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
-
-                            if has_home_retry.into_value::<bool>().unwrap_or(false) {
-                                info!("Home button found after retry (attempt {}), clicking...", i + 1);
-                                page.evaluate("document.querySelector('#Button1st_7').click()")
-                                    .await
-                                    .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
-                                self.wait_request_idle(page).await?;
-                                sleep(Duration::from_secs(3)).await;
-                                break;
-                            }
-                            sleep(Duration::from_secs(1)).await;
-                        }
-                    }
+                        .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
+                    self.wait_request_idle(page).await?;
+                    sleep(Duration::from_secs(5)).await;
+                    LoginState::LoginRetried
                 }
-            } else {
-                return Err(ScraperError::Login(
-                    "Login verification failed".to_string(),
-                ));
-            }
+                LoginState::LoginRetried => {
+                    return Err(ScraperError::Login(
+                        "Home button did not appear after popup dismissal and re-login (state: popup-stuck)".to_string(),
+                    ));
+                }
+            };
+            info!(state = state.as_str(), "login state changed");
         }
+    }
 
-        // ログイン成功後、ページが安定するまで待機
-        info!("Login completed, waiting for page to stabilize...");
-        self.wait_request_idle(page).await?;
-        self.wait_stable(page).await?;
-
-        // ログイン後のページURLを確認（デバッグ用）
-        let current_url = page
-            .evaluate("window.location.href")
+    /// 要素が存在するか（評価エラーは遷移中とみなして false）
+    async fn element_exists(&self, page: &Page, selector: &str) -> bool {
+        match page
+            .evaluate(format!("document.querySelector('{}') !== null", selector))
             .await
-            .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
-        info!(
-            "Post-login URL: {}",
-            current_url.into_value::<String>().unwrap_or_default()
-        );
-
-        let session_id = format!("session_{}", Utc::now().timestamp());
-        info!("Login successful, session ID: {}", session_id);
-        Ok(session_id)
+        {
+            Ok(result) => result.into_value::<bool>().unwrap_or(false),
+            Err(e) => {
+                debug!("Element check for {} failed: {}", selector, e);
+                false
+            }
+        }
     }
 
     /// メインページにナビゲート
@@ -464,162 +899,487 @@ impl DtakologScraper {
         info!("Current URL: {}", url);
 
         if url.contains("Login") || url.contains("OES1010") {
+            crate::browser::check_session_superseded(page).await?;
             return Err(ScraperError::Session(
                 "Redirected to login page - session expired".to_string(),
             ));
         }
 
-        // VenusBridgeServiceの初期化を待機（最大15秒）
-        info!("Waiting for VenusBridgeService initialization...");
-        let mut service_ready = false;
-        for i in 0..15 {
-            let has_service = page
-                .evaluate(
-                    r#"
-                    typeof VenusBridgeService !== 'undefined' &&
-                    typeof VenusBridgeService.VehicleStateTableForBranchEx === 'function'
-                "#,
-                )
-                .await
-                .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
+        Ok(())
+    }
 
-            if has_service.into_value::<bool>().unwrap_or(false) {
-                info!("VenusBridgeService ready after {}s in navigate_to_main", i + 1);
-                service_ready = true;
-                break;
+    /// 作成したページの共通設定（ロケール、コンソールエラーの記録、コンソール出力の収集）
+    ///
+    /// `capture_console` が有効な場合は収集中の `ConsoleCapture` を返す。
+    async fn prepare_page(&self, page: &Page) -> Result<Option<ConsoleCapture>, ScraperError> {
+        crate::browser::set_locale_override(page, &self.config.locale).await?;
+
+        // タイムアウト時の診断用にコンソールエラーを記録
+        page.evaluate_on_new_document(CONSOLE_ERROR_CAPTURE_SCRIPT)
+            .await
+            .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
+
+        if self.config.capture_console {
+            Ok(Some(
+                ConsoleCapture::start(page, self.config.runtime.as_ref()).await?,
+            ))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// 新しいページを作成してメインページを開く（必要に応じてログイン）
+    ///
+    /// 作成したページとセッションIDを返す。ページのクローズは呼び出し側で行う。
+    async fn open_main_page(
+        &self,
+        session_cookies: Option<Vec<(String, String, String, String)>>,
+        force_login: bool,
+    ) -> Result<(Page, String, Option<ConsoleCapture>), ScraperError> {
+        self.check_connection()?;
+        let browser = self
+            .browser
+            .as_ref()
+            .ok_or_else(|| ScraperError::BrowserInit("Browser not initialized".to_string()))?;
+
+        // 新しいページを作成
+        let page = browser
+            .new_page("about:blank")
+            .await
+            .map_err(|e| ScraperError::BrowserInit(e.to_string()))?;
+        *self.current_page.lock().unwrap_or_else(|e| e.into_inner()) = Some(page.clone());
+        let console = self.prepare_page(&page).await?;
+
+        let strategy = if force_login {
+            LoginStrategy::Always
+        } else {
+            self.config.login_strategy
+        };
+
+        // セッションクッキーを復元
+        if let Some(cookies) = session_cookies {
+            if strategy != LoginStrategy::Always {
+                for (name, value, domain, path) in cookies {
+                    let cookie_param = CookieParam::builder()
+                        .name(&name)
+                        .value(&value)
+                        .domain(&domain)
+                        .path(&path)
+                        .build();
+
+                    if let Ok(param) = cookie_param {
+                        if let Err(e) = page.set_cookie(param).await {
+                            debug!("Failed to set cookie: {}", e);
+                        }
+                    }
+                }
             }
+        }
 
-            if i % 3 == 0 {
-                info!("VenusBridgeService not ready yet... ({}/15)", i + 1);
+        // メインページにナビゲーション試行（Always の場合は直接ログイン）
+        let navigated = match strategy {
+            LoginStrategy::Always => Err(ScraperError::Session("login forced".to_string())),
+            _ => self.navigate_to_main(&page).await,
+        };
+        let session_id = match navigated {
+            Ok(_) => {
+                info!("Navigation successful without login");
+                self.current_session_id(&page).await
             }
-            sleep(Duration::from_secs(1)).await;
+            Err(e) if strategy == LoginStrategy::ReuseOnly => {
+                if let Err(close_err) = page.close().await {
+                    debug!("Failed to close page: {}", close_err);
+                }
+                return Err(ScraperError::Session(format!(
+                    "saved session is invalid and login_strategy is ReuseOnly: {}",
+                    e
+                )));
+            }
+            Err(e) => {
+                info!("First navigation failed, attempting login: {}", e);
+                let login = async {
+                    let sid = self.login(&page).await?;
+                    self.navigate_to_main(&page).await?;
+                    Ok(sid)
+                };
+                login
+                    .await
+                    .map_err(|e| attach_console(e, console.as_ref()))?
+            }
+        };
+
+        Ok((page, session_id, console))
+    }
+
+    /// スクレイパーが開いたページ全体のスクリーンショットをPNGで保存
+    ///
+    /// `scrape` 実行中に別タスクから呼び出すことを想定（完了後はページが閉じられている）。
+    /// ブラウザ上の他のタブ（接続先ブラウザで開かれているページ等）は対象にしない。
+    pub async fn screenshot(&self, path: &Path) -> Result<(), ScraperError> {
+        let page = self
+            .current_page
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+            .ok_or_else(|| ScraperError::Screenshot("no page has been opened".to_string()))?;
+
+        crate::browser::save_full_page_screenshot(&page, path).await?;
+        info!(path = ?path, "Saved screenshot");
+        Ok(())
+    }
+
+    /// 映像通知の一覧のみを取得（動画ファイルの確認・URL生成は行わない）
+    pub async fn fetch_notifications(&self) -> Result<Vec<DvrNotification>, ScraperError> {
+        let (page, _session_id, _console) = self.open_main_page(None, false).await?;
+
+        let result = self.get_video_notifications(&page).await;
+
+        if let Err(e) = page.close().await {
+            debug!("Failed to close page: {}", e);
         }
 
-        if !service_ready {
-            warn!("VenusBridgeService not ready after 15s in navigate_to_main, proceeding anyway...");
+        result
+    }
+
+    /// VenusBridgeService の指定メソッドが使えるようになるまで待機（最大 `service_wait_secs` 秒）
+    ///
+    /// タイムアウト時はスクリーンショットを保存し、URL・タイトル・コンソールエラーを
+    /// `Extraction` エラーに含める（ログイン失敗／サイト障害／サイト側の変更の切り分け用）。
+    async fn wait_for_venus_method(&self, page: &Page, method: &str) -> Result<(), ScraperError> {
+        let wait_secs = self.config.service_wait_secs;
+        let poll_interval = Duration::from_millis(self.config.service_poll_interval_ms);
+        let start = tokio::time::Instant::now();
+        let mut last_report = start;
+        loop {
+            if wait_for_js_fn(page, VENUS_BRIDGE_SERVICE, method, Duration::ZERO).await? {
+                info!(
+                    "{}.{} ready after {}s",
+                    VENUS_BRIDGE_SERVICE,
+                    method,
+                    start.elapsed().as_secs()
+                );
+                return Ok(());
+            }
+
+            self.check_connection()?;
+            if start.elapsed() >= Duration::from_secs(wait_secs) {
+                break;
+            }
+
+            if last_report.elapsed() >= Duration::from_secs(5) {
+                info!(
+                    "Waiting for {}.{}... ({}/{}s)",
+                    VENUS_BRIDGE_SERVICE,
+                    method,
+                    start.elapsed().as_secs(),
+                    wait_secs
+                );
+                last_report = tokio::time::Instant::now();
+            }
+            sleep(poll_interval).await;
         }
 
-        Ok(())
+        let diagnostics = self.collect_page_diagnostics(page).await;
+        Err(ScraperError::Extraction(format!(
+            "{}.{} still not defined after {}s ({})",
+            VENUS_BRIDGE_SERVICE, method, wait_secs, diagnostics
+        )))
+    }
+
+    /// エラー報告用にページの状態を収集（収集自体の失敗は無視）
+    async fn collect_page_diagnostics(&self, page: &Page) -> String {
+        let info = page
+            .evaluate(
+                r#"
+                JSON.stringify({
+                    url: window.location.href,
+                    title: document.title,
+                    consoleErrors: window.__scraperConsoleErrors || []
+                })
+            "#,
+            )
+            .await
+            .ok()
+            .and_then(|v| v.into_value::<String>().ok())
+            .unwrap_or_else(|| "page state unavailable".to_string());
+
+        let timestamp = self.file_timestamp();
+        let screenshot_path =
+            Path::new("./data").join(format!("venus_service_timeout_{}.png", timestamp));
+        let screenshot = match page
+            .screenshot(ScreenshotParams::builder().full_page(true).build())
+            .await
+        {
+            Ok(png) => match std::fs::create_dir_all("./data")
+                .and_then(|_| std::fs::write(&screenshot_path, png))
+            {
+                Ok(()) => format!("screenshot={}", screenshot_path.display()),
+                Err(e) => format!("screenshot save failed: {}", e),
+            },
+            Err(e) => format!("screenshot failed: {}", e),
+        };
+
+        format!("page={}, {}", info, screenshot)
     }
 
     /// Vehicleデータを抽出
+    ///
+    /// 一部のブランチの取得に失敗した場合は、失敗したブランチとエラーを合わせて返す。
+    /// 並列取得したページのコンソール出力は `console` に追加する。
     async fn extract_vehicle_data(
         &self,
         page: &Page,
-    ) -> Result<(Vec<VehicleData>, DtakologData), ScraperError> {
-        // VenusBridgeService のロードを待機（最大30秒）
-        let mut has_service = false;
+        console: Option<&ConsoleCapture>,
+    ) -> Result<ExtractedVehicles, ScraperError> {
+        // VenusBridgeService のロードを待機
+        self.wait_for_venus_method(page, "VehicleStateTableForBranchEx")
+            .await?;
+
+        // ページ安定待機（Go の WaitStable 相当）
+        self.wait_stable(page).await?;
+        sleep(Duration::from_secs(2)).await;
+
+        // グリッドの出現を待機
+        info!("Waiting for page structure...");
+        if wait_for_selector(page, VEHICLE_GRID_SELECTOR, Duration::from_secs(30)).await? {
+            info!("Venus main grid detected");
+        } else {
+            warn!("Venus main grid not found after 30s, continuing");
+        }
+
+        // ローディング表示の消失を待機
+        info!("Checking for loading messages...");
+        let mut loading_cleared = false;
         for i in 0..30 {
-            let result = page
+            let has_loading = page
                 .evaluate(
                     r#"
-                    typeof VenusBridgeService !== 'undefined' &&
-                    typeof VenusBridgeService.VehicleStateTableForBranchEx === 'function'
+                    (() => {
+                        const waitMsg = document.querySelector('#pMsg_wait, [id*="pMsg_wait"], [id*="pMsg"], [class*="pMsg"]');
+                        const loadingDivs = document.querySelectorAll('[id*="loading"], [id*="Loading"], .loading-message, .wait-message');
+                        const allLoading = waitMsg ? [waitMsg, ...loadingDivs] : [...loadingDivs];
+
+                        const visibleLoading = allLoading.filter(elem => {
+                            if (!elem) return false;
+                            const style = window.getComputedStyle(elem);
+                            const rect = elem.getBoundingClientRect();
+                            return style.display !== 'none' &&
+                                   style.visibility !== 'hidden' &&
+                                   style.opacity !== '0' &&
+                                   (rect.width > 0 || rect.height > 0);
+                        });
+                        return visibleLoading.length > 0;
+                    })()
                 "#,
                 )
                 .await
                 .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
 
-            if result.into_value::<bool>().unwrap_or(false) {
-                has_service = true;
+            if !has_loading.into_value::<bool>().unwrap_or(false) {
+                loading_cleared = true;
+                info!("No loading messages detected, proceeding...");
                 break;
             }
 
             if i % 5 == 0 {
-                info!("Waiting for VenusBridgeService... ({}/30)", i + 1);
+                info!("Loading message still visible, waiting... ({}/30)", i + 1);
             }
             sleep(Duration::from_secs(1)).await;
         }
 
-        if !has_service {
-            return Err(ScraperError::Extraction(
-                "VenusBridgeService not found after 30s".to_string(),
-            ));
+        if !loading_cleared {
+            warn!("Loading message timeout after 30 seconds, proceeding anyway...");
         }
 
-        info!(
-            "Calling VenusBridgeService with branchID='{}', filterID='{}'",
-            self.config.branch_id, self.config.filter_id
-        );
-
-        // ページ安定待機（Go の WaitStable 相当）
-        self.wait_stable(page).await?;
-        sleep(Duration::from_secs(2)).await;
-
-        // グリッドの出現を待機
-        for i in 0..30 {
-            let grid_exists = page
-                .evaluate("document.querySelector('#igGrid-VenusMain-VehicleList') !== null")
-                .await
-                .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
+        // グリッドにデータ行が描画されるまで待機（車両0台の営業所もあるためタイムアウトはエラーにしない）
+        if wait_for_selector(page, VEHICLE_GRID_ROW_SELECTOR, GRID_ROWS_TIMEOUT).await? {
+            info!("Venus main grid rows detected");
+        } else {
+            warn!(
+                "Venus main grid has no rows after {}s, continuing",
+                GRID_ROWS_TIMEOUT.as_secs()
+            );
+        }
 
-            if grid_exists.into_value::<bool>().unwrap_or(false) {
-                info!("Venus main grid detected");
-                break;
+        // ブランチごとに取得（設定により別ページで並列取得）
+        let branch_filters = self.config.branch_filters();
+        let concurrency = self.config.max_concurrent_pages.max(1);
+        let branch_results = if concurrency > 1 && branch_filters.len() > 1 {
+            self.fetch_branches_concurrently(&branch_filters, concurrency, console)
+                .await?
+        } else {
+            let mut branch_results = Vec::new();
+            for (branch_id, filter_id) in branch_filters {
+                // 接続断の場合は残りのブランチもタイムアウトまで待たされるため打ち切る
+                self.check_connection()?;
+                let result = self
+                    .fetch_branch_vehicle_data(page, &branch_id, &filter_id)
+                    .await;
+                branch_results.push((branch_id, result));
             }
+            branch_results
+        };
 
-            if i % 5 == 0 {
-                info!("Waiting for page structure... ({}/30)", i + 1);
+        // 失敗したブランチは除外して記録（全ブランチ失敗時のみエラー）
+        let mut fetched_branches = Vec::new();
+        let mut branch_errors = Vec::new();
+        for (branch_id, result) in branch_results {
+            match result {
+                Ok(data) => fetched_branches.push((branch_id, data)),
+                Err(e) => {
+                    warn!(branch_id = %branch_id, error = %e, "branch fetch failed, skipping");
+                    branch_errors.push((branch_id, e));
+                }
             }
-            sleep(Duration::from_secs(1)).await;
+        }
+        if fetched_branches.is_empty() && !branch_errors.is_empty() {
+            // 強制ログアウト後は取得結果が null になり抽出エラーとして表面化するため、原因を判別する
+            crate::browser::check_session_superseded(page).await?;
+            return Err(branch_errors.swap_remove(0).1);
         }
 
-        // ローディング表示の消失を待機
-        info!("Checking for loading messages...");
-        let mut loading_cleared = false;
-        for i in 0..30 {
-            let has_loading = page
-                .evaluate(
-                    r#"
-                    (() => {
-                        const waitMsg = document.querySelector('#pMsg_wait, [id*="pMsg_wait"], [id*="pMsg"], [class*="pMsg"]');
-                        const loadingDivs = document.querySelectorAll('[id*="loading"], [id*="Loading"], .loading-message, .wait-message');
-                        const allLoading = waitMsg ? [waitMsg, ...loadingDivs] : [...loadingDivs];
+        // 複数ブランチに現れる車両は VehicleCD で重複排除
+        let mut raw_data = DtakologData::new();
+        let mut vehicles = Vec::new();
+        let mut seen_vehicle_cds = HashSet::new();
+        for (branch_id, branch_data) in fetched_branches {
+            let fetched = branch_data.len();
+            let added = dedup_by_vehicle_cd(branch_data, &mut seen_vehicle_cds);
+            info!(
+                branch_id = %branch_id,
+                fetched,
+                added = added.len(),
+                "merged branch vehicle data"
+            );
+
+            vehicles.extend(self.parse_vehicle_data(&added, &branch_id));
+            raw_data.extend(added);
+        }
+
+        // スキーマ監視用に先頭オブジェクトを保持
+        if let Some(first) = raw_data.first() {
+            *self
+                .last_vehicle_sample
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()) = Some(first.clone());
+        }
+
+        info!(
+            vehicle_count = vehicles.len(),
+            failed_branches = branch_errors.len(),
+            "extracted vehicles"
+        );
+
+        Ok((vehicles, raw_data, branch_errors))
+    }
+
+    /// 複数ブランチの車両データを同一ブラウザ内の別ページで並列取得
+    ///
+    /// 結果はブランチの指定順に、ブランチごとの成否を返す。
+    /// 一部のブランチが失敗しても他のブランチの取得は継続する。
+    async fn fetch_branches_concurrently(
+        &self,
+        branch_filters: &[(String, String)],
+        concurrency: usize,
+        console: Option<&ConsoleCapture>,
+    ) -> Result<Vec<BranchResult>, ScraperError> {
+        let browser = self
+            .browser
+            .as_ref()
+            .ok_or_else(|| ScraperError::BrowserInit("Browser not initialized".to_string()))?;
+        info!(
+            branches = branch_filters.len(),
+            concurrency, "fetching branches in parallel pages"
+        );
 
-                        const visibleLoading = allLoading.filter(elem => {
-                            if (!elem) return false;
-                            const style = window.getComputedStyle(elem);
-                            const rect = elem.getBoundingClientRect();
-                            return style.display !== 'none' &&
-                                   style.visibility !== 'hidden' &&
-                                   style.opacity !== '0' &&
-                                   (rect.width > 0 || rect.height > 0);
-                        });
-                        return visibleLoading.length > 0;
-                    })()
-                "#,
-                )
-                .await
-                .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
+        let fetches: Vec<_> = branch_filters
+            .iter()
+            .cloned()
+            .map(|(branch_id, filter_id)| async move {
+                let result = self
+                    .fetch_branch_in_new_page(browser, &branch_id, &filter_id, console)
+                    .await;
+                (branch_id, result)
+            })
+            .collect();
+        Ok(stream::iter(fetches).buffered(concurrency).collect().await)
+    }
 
-            if !has_loading.into_value::<bool>().unwrap_or(false) {
-                loading_cleared = true;
-                info!("No loading messages detected, proceeding...");
-                break;
-            }
+    /// 新しいページでメインページを開き、指定ブランチの車両データを取得
+    ///
+    /// ページは成否に関わらず閉じる。ページのコンソール出力はブランチIDを付けて
+    /// `console`（メインページの収集先）に追加する。
+    async fn fetch_branch_in_new_page(
+        &self,
+        browser: &Browser,
+        branch_id: &str,
+        filter_id: &str,
+        console: Option<&ConsoleCapture>,
+    ) -> Result<DtakologData, ScraperError> {
+        self.check_connection()?;
+        let page = browser
+            .new_page("about:blank")
+            .await
+            .map_err(|e| ScraperError::BrowserInit(e.to_string()))?;
 
-            if i % 5 == 0 {
-                info!("Loading message still visible, waiting... ({}/30)", i + 1);
+        let result = async {
+            let branch_console = self.prepare_page(&page).await?;
+            let fetched = async {
+                self.navigate_to_main(&page).await?;
+                self.wait_for_venus_method(&page, "VehicleStateTableForBranchEx")
+                    .await?;
+                self.fetch_branch_vehicle_data(&page, branch_id, filter_id)
+                    .await
+            };
+            let fetched = fetched
+                .await
+                .map_err(|e| attach_console(e, branch_console.as_ref()));
+            if let (Some(main), Some(branch)) = (console, branch_console.as_ref()) {
+                main.append(
+                    branch
+                        .entries()
+                        .into_iter()
+                        .map(|line| format!("[branch {}] {}", branch_id, line)),
+                );
             }
-            sleep(Duration::from_secs(1)).await;
+            fetched
         }
+        .await;
 
-        if !loading_cleared {
-            warn!("Loading message timeout after 30 seconds, proceeding anyway...");
+        if let Err(e) = page.close().await {
+            debug!("Failed to close branch page: {}", e);
         }
+        result
+    }
 
-        sleep(Duration::from_secs(3)).await;
+    /// 指定ブランチ/フィルターの車両データを VenusBridgeService から取得
+    async fn fetch_branch_vehicle_data(
+        &self,
+        page: &Page,
+        branch_id: &str,
+        filter_id: &str,
+    ) -> Result<DtakologData, ScraperError> {
+        info!(
+            "Calling VenusBridgeService with branchID='{}', filterID='{}'",
+            branch_id, filter_id
+        );
 
         // JavaScriptを実行してデータを取得（Promiseでラップ）
         info!("Fetching vehicle data via VenusBridgeService...");
-        let start = std::time::Instant::now();
+        let start = tokio::time::Instant::now();
 
+        let timeout_secs = self.config.vehicle_fetch_timeout_secs;
         let promise_script = format!(
             r#"
             new Promise((resolve, reject) => {{
+                if (typeof VenusBridgeService === 'undefined' ||
+                    typeof VenusBridgeService.VehicleStateTableForBranchEx !== 'function') {{
+                    reject(new Error('{unavailable}'));
+                    return;
+                }}
                 const timeout = setTimeout(() => {{
-                    reject(new Error('Vehicle data fetch timeout after 60s'));
-                }}, 60000);
+                    reject(new Error('{timed_out}'));
+                }}, {timeout_ms});
 
                 VenusBridgeService.VehicleStateTableForBranchEx('{}', '{}',
                     (data) => {{
@@ -633,30 +1393,30 @@ impl DtakologScraper {
                 );
             }})
         "#,
-            self.config.branch_id, self.config.filter_id
+            branch_id,
+            filter_id,
+            unavailable = VEHICLE_FETCH_UNAVAILABLE,
+            timed_out = VEHICLE_FETCH_TIMED_OUT,
+            timeout_ms = timeout_secs * 1000,
         );
 
         let result = page
             .evaluate(promise_script.as_str())
             .await
-            .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
+            .map_err(|e| vehicle_fetch_error(&e.to_string(), timeout_secs))?;
 
         let json_str = result.into_value::<String>().unwrap_or_default();
-        info!("Got vehicle data after {:?}", start.elapsed());
+        info!(
+            elapsed_ms = start.elapsed().as_millis() as u64,
+            "got vehicle data"
+        );
 
         // JSONをパース
-        let raw_data: DtakologData =
-            serde_json::from_str(&json_str).map_err(|e| ScraperError::Json(e.to_string()))?;
-
-        // VehicleDataに変換
-        let vehicles = self.parse_vehicle_data(&raw_data);
-        info!("Extracted {} vehicles", vehicles.len());
-
-        Ok((vehicles, raw_data))
+        parse_vehicle_json(&json_str)
     }
 
     /// 生データをVehicleDataに変換
-    fn parse_vehicle_data(&self, raw_data: &DtakologData) -> Vec<VehicleData> {
+    fn parse_vehicle_data(&self, raw_data: &DtakologData, branch_id: &str) -> Vec<VehicleData> {
         raw_data
             .iter()
             .filter_map(|item| {
@@ -680,7 +1440,7 @@ impl DtakologScraper {
                     .unwrap_or_default()
                     .to_string();
 
-                let mut metadata = HashMap::new();
+                let mut metadata = BTreeMap::new();
                 for (k, v) in obj {
                     if k != "VehicleCD" && k != "VehicleName" && k != "Status" {
                         metadata.insert(k.clone(), format!("{}", v));
@@ -692,31 +1452,51 @@ impl DtakologScraper {
                     vehicle_name,
                     status,
                     metadata,
+                    branch_id: branch_id.to_string(),
                 })
             })
             .collect()
     }
 
-    /// 生データをファイルに保存
-    async fn save_raw_data(&self, raw_data: &DtakologData) {
-        let jst = FixedOffset::east_opt(9 * 3600).unwrap();
-        let timestamp = Utc::now().with_timezone(&jst).format("%Y%m%d_%H%M%S");
-        let filename = format!("./data/vehicles_{}.json", timestamp);
+    /// ファイル名用の現在時刻（`timestamp_offset` のタイムゾーン）
+    fn file_timestamp(&self) -> String {
+        Utc::now()
+            .with_timezone(&self.config.timestamp_offset)
+            .format("%Y%m%d_%H%M%S")
+            .to_string()
+    }
 
-        if let Err(e) = std::fs::create_dir_all("./data") {
+    /// 生データをファイルに保存
+    ///
+    /// `./data` を監視するパイプラインが書き込み途中のJSONを読まないよう、
+    /// 一時ファイルに書き込んでからリネームする。
+    async fn save_raw_data(&self, raw_data: &DtakologData) -> Option<PathBuf> {
+        let timestamp = self.file_timestamp();
+        let data_dir = Path::new("./data");
+        let filename = data_dir.join(raw_data_filename(&timestamp));
+
+        if let Err(e) = std::fs::create_dir_all(data_dir) {
             warn!("Failed to create data directory: {}", e);
-            return;
+            return None;
         }
 
+        // 前回クラッシュ時の一時ファイルを削除
+        cleanup_orphaned_tmp_files(data_dir, ORPHANED_TMP_MIN_AGE);
+
         match serde_json::to_string_pretty(raw_data) {
             Ok(json) => {
-                if let Err(e) = std::fs::write(&filename, json) {
+                if let Err(e) = write_atomic(&filename, json.as_bytes()) {
                     error!("Failed to save vehicle data: {}", e);
+                    None
                 } else {
-                    info!("Saved vehicle data to {}", filename);
+                    info!(path = %filename.display(), "saved vehicle data");
+                    Some(filename)
                 }
             }
-            Err(e) => error!("Failed to serialize vehicle data: {}", e),
+            Err(e) => {
+                error!("Failed to serialize vehicle data: {}", e);
+                None
+            }
         }
     }
 
@@ -724,35 +1504,20 @@ impl DtakologScraper {
     async fn send_to_grpc_with_retry(
         &self,
         raw_data: &DtakologData,
+        video_notifications: &[VideoNotificationResult],
     ) -> Result<GrpcResponse, ScraperError> {
-        let mut last_error = None;
-
-        for attempt in 0..MAX_RETRIES {
-            match self.send_to_grpc(raw_data).await {
-                Ok(resp) => return Ok(resp),
-                Err(e) if e.is_retryable() => {
-                    let backoff = INITIAL_BACKOFF_MS * 2u64.pow(attempt);
-                    warn!(
-                        "gRPC attempt {} failed, retrying in {}ms: {}",
-                        attempt + 1,
-                        backoff,
-                        e
-                    );
-                    sleep(Duration::from_millis(backoff)).await;
-                    last_error = Some(e);
-                }
-                Err(e) => return Err(e),
-            }
-        }
-
-        Err(last_error.unwrap_or_else(|| ScraperError::GrpcConnectionFailed {
-            retries: MAX_RETRIES,
-            message: "Max retries exceeded".to_string(),
-        }))
+        retry_with_backoff(&mut StdRng::from_entropy(), || {
+            self.send_to_grpc(raw_data, video_notifications)
+        })
+        .await
     }
 
     /// gRPCに送信（プレースホルダー - 実際の実装は grpc feature で有効化）
-    async fn send_to_grpc(&self, _raw_data: &DtakologData) -> Result<GrpcResponse, ScraperError> {
+    async fn send_to_grpc(
+        &self,
+        _raw_data: &DtakologData,
+        _video_notifications: &[VideoNotificationResult],
+    ) -> Result<GrpcResponse, ScraperError> {
         // この実装はプレースホルダー
         // 実際のgRPC送信は browser-render-rust の grpc feature を使用
         Err(ScraperError::Grpc(
@@ -762,14 +1527,61 @@ impl DtakologScraper {
 
     /// ブラウザを閉じる
     pub async fn close(&mut self) -> Result<(), ScraperError> {
-        self.browser = None;
+        if let Some(mut browser) = self.browser.take() {
+            // 起動したブラウザのみ終了を待つ（接続先のブラウザは終了させない）
+            if self.user_data_dir.is_some()
+                && !crate::browser::close_browser(
+                    &mut browser,
+                    crate::browser::BROWSER_CLOSE_TIMEOUT,
+                )
+                .await
+            {
+                if let Some(dir) = self.user_data_dir.take() {
+                    warn!(path = %dir.display(), "browser process did not exit, keeping user data dir");
+                }
+            }
+        }
+        self.connection = None;
+        *self.current_page.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        self.remove_user_data_dir();
         Ok(())
     }
 
+    /// 起動時に作成したユーザーデータディレクトリを削除
+    fn remove_user_data_dir(&mut self) {
+        let Some(dir) = self.user_data_dir.take() else {
+            return;
+        };
+        match std::fs::remove_dir_all(&dir) {
+            Ok(()) => debug!(path = %dir.display(), "removed browser user data dir"),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                warn!(path = %dir.display(), error = %e, "failed to remove browser user data dir")
+            }
+        }
+    }
+
+    /// CDPハンドラータスクのハンドルを取り出す（`initialize` 後の1回のみ `Some`）
+    ///
+    /// タスクはブラウザとの接続が切れると終了する。呼び出し側で終了を監視・join する用途向け。
+    pub fn take_handler_task(&mut self) -> Option<tokio::task::JoinHandle<()>> {
+        self.connection
+            .as_ref()
+            .and_then(ConnectionMonitor::take_task)
+    }
+
+    /// ブラウザとの接続が切れていればエラー
+    fn check_connection(&self) -> Result<(), ScraperError> {
+        match self.connection {
+            Some(ref connection) => connection.check(),
+            None => Ok(()),
+        }
+    }
+
     /// ネットワークリクエストがアイドル状態になるまで待機（Go の WaitRequestIdle 相当）
     async fn wait_request_idle(&self, page: &Page) -> Result<(), ScraperError> {
         info!("Waiting for network to become idle...");
-        let start = std::time::Instant::now();
+        let start = tokio::time::Instant::now();
         let timeout = Duration::from_millis(NETWORK_IDLE_TIMEOUT_MS);
 
         // Performance API を使ってアクティブなリクエストを監視
@@ -834,7 +1646,7 @@ impl DtakologScraper {
     /// ページが安定するまで待機（Go の WaitStable 相当）
     async fn wait_stable(&self, page: &Page) -> Result<(), ScraperError> {
         info!("Waiting for page to stabilize...");
-        let start = std::time::Instant::now();
+        let start = tokio::time::Instant::now();
         let timeout = Duration::from_millis(PAGE_STABLE_TIMEOUT_MS);
 
         let mut last_html_len: Option<usize> = None;
@@ -894,6 +1706,8 @@ impl DtakologScraper {
         &self,
         page: &Page,
     ) -> Result<Vec<DvrNotification>, ScraperError> {
+        self.wait_for_venus_method(page, "Monitoring_DvrNotification2")
+            .await?;
         info!("Fetching video notifications...");
 
         // Step 1: API呼び出しを開始し、結果をグローバル変数に保存
@@ -1033,7 +1847,10 @@ impl DtakologScraper {
             info!("Video notification count from API: {}", count);
         }
 
-        info!("Found {} video notifications", response.data.len());
+        info!(
+            notification_count = response.data.len(),
+            "found video notifications"
+        );
         Ok(response.data)
     }
 
@@ -1043,6 +1860,8 @@ impl DtakologScraper {
         page: &Page,
         vehicle_cd: i64,
     ) -> Result<Vec<DvrFileInfo>, ScraperError> {
+        self.wait_for_venus_method(page, "Request_DvrFileList")
+            .await?;
         let script = format!(
             r#"
             new Promise((resolve, reject) => {{
@@ -1089,6 +1908,8 @@ impl DtakologScraper {
         serial_no: &str,
         file_name: &str,
     ) -> Result<bool, ScraperError> {
+        self.wait_for_venus_method(page, "Request_DvrFileTransfer_MultiTarget")
+            .await?;
         let script = format!(
             r#"
             new Promise((resolve, reject) => {{
@@ -1125,20 +1946,207 @@ impl DtakologScraper {
     }
 
     /// 動画URLを構築
-    fn build_video_url(file_path: &str, file_name: &str) -> String {
-        let base_name = file_name.replace(".vdf", "");
+    fn build_video_url(base_url: &str, file_path: &str, file_name: &str) -> String {
+        // 末尾の ".vdf" のみ除去（途中に含まれる文字列は変更しない）
+        let base_name = file_name
+            .len()
+            .checked_sub(4)
+            .filter(|&i| file_name.is_char_boundary(i))
+            .filter(|&i| file_name[i..].eq_ignore_ascii_case(".vdf"))
+            .map_or(file_name, |i| &file_name[..i]);
         format!(
-            "http://theearth-np.com/dvrData/{}/{}-1.mp4",
-            file_path, base_name
+            "{}/{}/{}-1.mp4",
+            base_url.trim_end_matches('/'),
+            file_path.trim_matches('/'),
+            base_name
         )
     }
 
+    /// 設定された動画URLのベース
+    fn dvr_base_url(&self) -> &str {
+        self.config
+            .dvr_base_url
+            .as_deref()
+            .unwrap_or(DEFAULT_DVR_BASE_URL)
+    }
+
     /// 映像通知の動画を処理（メインエントリ）
     /// 準備完了した動画のVideoNotificationResultリストを返す
     pub async fn process_video_notifications(
         &self,
         page: &Page,
     ) -> Result<Vec<VideoNotificationResult>, ScraperError> {
+        let (results, _pending) = self.collect_video_notifications(page).await?;
+        Ok(results)
+    }
+
+    /// 以前にダウンロードをリクエストした動画を再確認し、準備完了したものを返す
+    ///
+    /// 未完了のものは結果に含まれないため、呼び出し側で保持して再度ポーリングすること
+    /// （ダウンロードリクエストは再送しない）。
+    pub async fn poll_pending_downloads(
+        &self,
+        pending: &[PendingDownload],
+    ) -> Result<Vec<VideoNotificationResult>, ScraperError> {
+        if pending.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (page, _session_id, _console) = self.open_main_page(None, false).await?;
+
+        let result = async {
+            // 同一車両の確認はまとめて1回にする
+            let mut vehicle_cds: Vec<i64> = pending.iter().map(|p| p.vehicle_cd).collect();
+            vehicle_cds.sort_unstable();
+            vehicle_cds.dedup();
+
+            let page = &page;
+            let checked: Vec<(i64, Result<Vec<DvrFileInfo>, ScraperError>)> =
+                stream::iter(vehicle_cds.into_iter().map(|vehicle_cd| async move {
+                    (vehicle_cd, self.check_video_files(page, vehicle_cd).await)
+                }))
+                .buffer_unordered(self.config.dvr_concurrency.max(1))
+                .collect()
+                .await;
+
+            let mut file_lists: HashMap<i64, Vec<DvrFileInfo>> = HashMap::new();
+            for (vehicle_cd, files) in checked {
+                file_lists.insert(vehicle_cd, files?);
+            }
+
+            let mut results = Vec::new();
+            for download in pending {
+                let matching_file = file_lists.get(&download.vehicle_cd).and_then(|files| {
+                    files
+                        .iter()
+                        .find(|f| f.file_name == download.file_name && !f.file_path.is_empty())
+                });
+                if let Some(file) = matching_file {
+                    let url = Self::build_video_url(
+                        self.dvr_base_url(),
+                        &file.file_path,
+                        &file.file_name,
+                    );
+                    info!(
+                        vehicle = %download.vehicle_name,
+                        serial_no = %download.serial_no,
+                        mp4 = %url,
+                        "pending video ready"
+                    );
+                    results.push(download.clone().into_result(url));
+                }
+            }
+            self.verify_video_urls(page, &mut results).await;
+
+            info!(
+                pending = pending.len(),
+                ready_videos = results.len(),
+                "pending video downloads polled"
+            );
+            Ok(results)
+        }
+        .await;
+
+        if let Err(e) = page.close().await {
+            debug!("Failed to close page: {}", e);
+        }
+
+        result
+    }
+
+    /// `dvr_request_dedup_secs` 以内にダウンロードリクエスト済みの動画か（期限切れの記録は削除）
+    fn recently_requested(&self, notification: &DvrNotification) -> bool {
+        let ttl = Duration::from_secs(self.config.dvr_request_dedup_secs);
+        if ttl.is_zero() {
+            return false;
+        }
+        let mut requested = self
+            .config
+            .requested_downloads
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        requested.retain(|_, requested_at| requested_at.elapsed() < ttl);
+        requested.contains_key(&(
+            notification.serial_no.clone(),
+            notification.file_name.clone(),
+        ))
+    }
+
+    /// ダウンロードリクエスト済みとして記録
+    fn mark_requested(&self, notification: &DvrNotification) {
+        if self.config.dvr_request_dedup_secs == 0 {
+            return;
+        }
+        self.config
+            .requested_downloads
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(
+                (
+                    notification.serial_no.clone(),
+                    notification.file_name.clone(),
+                ),
+                std::time::Instant::now(),
+            );
+    }
+
+    /// 動画URLへHEADリクエストを送り、到達できたものを `verified` にする
+    ///
+    /// `verify_video_urls` が無効なら何もしない。
+    /// クッキーは動画URLのドメイン・パスに該当するもののみ付与する（ログインセッションを別オリジンに送らない）。
+    async fn verify_video_urls(&self, page: &Page, results: &mut [VideoNotificationResult]) {
+        if !self.config.verify_video_urls || results.is_empty() {
+            return;
+        }
+        let http = match video_http_client() {
+            Ok(http) => http,
+            Err(e) => {
+                warn!("Skipping video URL verification: {}", e);
+                return;
+            }
+        };
+
+        let checks: Vec<_> = results
+            .iter()
+            .enumerate()
+            .map(|(idx, result)| async move {
+                let mut request = http.head(&result.mp4_url);
+                if let Some(cookie_header) = cookie_header_for_url(page, &result.mp4_url).await {
+                    request = request.header(reqwest::header::COOKIE, cookie_header);
+                }
+                (idx, request.send().await)
+            })
+            .collect();
+        let checked: Vec<(usize, reqwest::Result<reqwest::Response>)> = stream::iter(checks)
+            .buffer_unordered(self.config.dvr_concurrency.max(1))
+            .collect()
+            .await;
+
+        for (idx, response) in checked {
+            let result = &mut results[idx];
+            match response {
+                Ok(response) if response.status().is_success() => result.verified = true,
+                Ok(response) => warn!(
+                    vehicle = %result.vehicle_name,
+                    mp4 = %result.mp4_url,
+                    status = %response.status(),
+                    "video URL not reachable"
+                ),
+                Err(e) => warn!(
+                    vehicle = %result.vehicle_name,
+                    mp4 = %result.mp4_url,
+                    error = %e,
+                    "video URL verification failed"
+                ),
+            }
+        }
+    }
+
+    /// 映像通知の動画を処理し、(準備完了した動画, ダウンロードをリクエストした動画) を返す
+    async fn collect_video_notifications(
+        &self,
+        page: &Page,
+    ) -> Result<(Vec<VideoNotificationResult>, Vec<PendingDownload>), ScraperError> {
         info!("Processing video notifications...");
 
         // ネットワークアイドル待機（多層防御）
@@ -1153,21 +2161,47 @@ impl DtakologScraper {
 
         if notifications.is_empty() {
             info!("No video notifications to process");
-            return Ok(Vec::new());
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        // FilePathが空の通知はRequest_DvrFileListで確認が必要（並列実行）
+        let concurrency = self.config.dvr_concurrency.max(1);
+        let checks: Vec<_> = notifications
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| n.file_path.is_empty())
+            .map(|(idx, n)| {
+                let vehicle_cd = n.vehicle_cd;
+                async move { (idx, self.check_video_files(page, vehicle_cd).await) }
+            })
+            .collect();
+        let checked: Vec<(usize, Result<Vec<DvrFileInfo>, ScraperError>)> = stream::iter(checks)
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
+
+        let mut file_lists: HashMap<usize, Vec<DvrFileInfo>> = HashMap::new();
+        for (idx, files) in checked {
+            file_lists.insert(idx, files?);
         }
 
         let mut results: Vec<VideoNotificationResult> = Vec::new();
+        let mut pending: Vec<PendingDownload> = Vec::new();
 
-        for notification in notifications {
+        for (idx, notification) in notifications.into_iter().enumerate() {
             // 通知にFilePathがあれば直接URL構築可能
             if !notification.file_path.is_empty() {
-                let url = Self::build_video_url(&notification.file_path, &notification.file_name);
+                let url = Self::build_video_url(
+                    self.dvr_base_url(),
+                    &notification.file_path,
+                    &notification.file_name,
+                );
                 info!(
-                    "Video ready: vehicle={}, event={}, datetime={}, mp4={}",
-                    notification.vehicle_name,
-                    notification.event_type,
-                    notification.dvr_datetime,
-                    url
+                    vehicle = %notification.vehicle_name,
+                    event = %notification.event_type,
+                    datetime = %notification.dvr_datetime,
+                    mp4 = %url,
+                    "video ready"
                 );
                 results.push(VideoNotificationResult {
                     vehicle_cd: notification.vehicle_cd,
@@ -1178,14 +2212,13 @@ impl DtakologScraper {
                     dvr_datetime: notification.dvr_datetime.clone(),
                     driver_name: notification.driver_name.clone(),
                     mp4_url: url,
+                    verified: false,
                 });
                 continue;
             }
 
-            // FilePathが空の場合、Request_DvrFileListで確認
-            let files = self
-                .check_video_files(page, notification.vehicle_cd)
-                .await?;
+            // FilePathが空の場合、Request_DvrFileListの結果を参照
+            let files = file_lists.remove(&idx).unwrap_or_default();
 
             // 通知のFileNameと一致するファイルを探す
             let matching_file = files
@@ -1193,13 +2226,14 @@ impl DtakologScraper {
                 .find(|f| f.file_name == notification.file_name && !f.file_path.is_empty());
 
             if let Some(file) = matching_file {
-                let url = Self::build_video_url(&file.file_path, &file.file_name);
+                let url =
+                    Self::build_video_url(self.dvr_base_url(), &file.file_path, &file.file_name);
                 info!(
-                    "Video ready: vehicle={}, event={}, datetime={}, mp4={}",
-                    notification.vehicle_name,
-                    notification.event_type,
-                    notification.dvr_datetime,
-                    url
+                    vehicle = %notification.vehicle_name,
+                    event = %notification.event_type,
+                    datetime = %notification.dvr_datetime,
+                    mp4 = %url,
+                    "video ready"
                 );
                 results.push(VideoNotificationResult {
                     vehicle_cd: notification.vehicle_cd,
@@ -1210,7 +2244,17 @@ impl DtakologScraper {
                     dvr_datetime: notification.dvr_datetime.clone(),
                     driver_name: notification.driver_name.clone(),
                     mp4_url: url,
+                    verified: false,
                 });
+            } else if self.recently_requested(&notification) {
+                // 前回までのポーリングでリクエスト済み（DVRからのアップロード待ち）
+                debug!(
+                    vehicle = %notification.vehicle_name,
+                    serial_no = %notification.serial_no,
+                    file = %notification.file_name,
+                    "video download already requested, skipping"
+                );
+                pending.push(PendingDownload::from(&notification));
             } else {
                 // ダウンロードリクエスト送信
                 let success = self
@@ -1219,31 +2263,440 @@ impl DtakologScraper {
 
                 if success {
                     info!(
-                        "Video download requested: vehicle={}, event={}, datetime={}",
-                        notification.vehicle_name,
-                        notification.event_type,
-                        notification.dvr_datetime
+                        vehicle = %notification.vehicle_name,
+                        event = %notification.event_type,
+                        datetime = %notification.dvr_datetime,
+                        "video download requested"
                     );
+                    self.mark_requested(&notification);
+                    pending.push(PendingDownload::from(&notification));
                 } else {
                     warn!(
-                        "Video download request failed: vehicle={}, event={}",
-                        notification.vehicle_name, notification.event_type
+                        vehicle = %notification.vehicle_name,
+                        event = %notification.event_type,
+                        "video download request failed"
                     );
                 }
             }
         }
 
+        self.verify_video_urls(page, &mut results).await;
+
         info!(
-            "Video notification processing completed: {} ready videos",
-            results.len()
+            ready_videos = results.len(),
+            pending_downloads = pending.len(),
+            "video notification processing completed"
         );
-        Ok(results)
+        Ok((results, pending))
+    }
+}
+
+impl Drop for DtakologScraper {
+    fn drop(&mut self) {
+        // ブラウザプロセスの終了を確認してからディレクトリを削除（close 済みなら何もしない）
+        if let Some(mut browser) = self.browser.take() {
+            if !crate::browser::kill_browser_blocking(
+                &mut browser,
+                crate::browser::BROWSER_KILL_TIMEOUT,
+            ) {
+                if let Some(dir) = self.user_data_dir.take() {
+                    warn!(path = %dir.display(), "browser process did not exit, keeping user data dir");
+                }
+                return;
+            }
+        }
+        self.remove_user_data_dir();
+    }
+}
+
+/// 汎用の `Scraper` としての実装（`ScraperFactory` 経由で他のスクレイパーと同様に扱う用）
+///
+/// ログインは `download`（= `scrape`）内で必要に応じて行うため `login` は何もしない。
+/// `download` は保存した生データJSONのパスを返す。
+#[async_trait]
+impl Scraper for DtakologScraper {
+    async fn initialize(&mut self) -> Result<(), ScraperError> {
+        DtakologScraper::initialize(self).await
+    }
+
+    async fn login(&mut self) -> Result<(), ScraperError> {
+        Ok(())
+    }
+
+    async fn download(&mut self) -> Result<PathBuf, ScraperError> {
+        if !self.config.save_raw_data {
+            return Err(ScraperError::Config(
+                "download requires save_raw_data to be enabled".to_string(),
+            ));
+        }
+        let result = self.scrape(None, false).await?;
+        result
+            .raw_data_path
+            .ok_or_else(|| ScraperError::Download("failed to save vehicle data".to_string()))
+    }
+
+    async fn close(&mut self) -> Result<(), ScraperError> {
+        DtakologScraper::close(self).await
+    }
+
+    async fn screenshot(&self, path: &Path) -> Result<(), ScraperError> {
+        DtakologScraper::screenshot(self, path).await
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dtakolog::RequestedDownloads;
+
+    #[test]
+    fn test_parse_vehicle_json_error_includes_payload() {
+        assert_eq!(
+            parse_vehicle_json(r#"[{"VehicleCD":"001"}]"#)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let html = format!(
+            "<html><title>セッションが切れました</title>{}</html>",
+            "x".repeat(500)
+        );
+        let message = parse_vehicle_json(&html).unwrap_err().to_string();
+        assert!(message.contains("payload: \"<html><title>セッションが切れました"));
+        assert!(message.ends_with("...)"));
+        assert!(!message.contains(&"x".repeat(300)));
+    }
+
+    #[test]
+    fn test_vehicle_fetch_error() {
+        let err = vehicle_fetch_error(
+            &format!("Error: {} at <anonymous>", VEHICLE_FETCH_TIMED_OUT),
+            90,
+        );
+        assert!(
+            matches!(&err, ScraperError::Extraction(msg) if msg.contains("timed out after 90s"))
+        );
+
+        let err = vehicle_fetch_error(VEHICLE_FETCH_UNAVAILABLE, 60);
+        assert!(matches!(&err, ScraperError::Extraction(msg) if msg.contains("not found")));
+
+        let err = vehicle_fetch_error("Error: Unknown service error", 60);
+        assert!(matches!(err, ScraperError::JavaScript(_)));
+    }
+
+    #[test]
+    fn test_cdp_request_timeout() {
+        let config = DtakologConfig {
+            vehicle_fetch_timeout_secs: 30,
+            ..Default::default()
+        };
+        assert_eq!(cdp_request_timeout(&config), CDP_REQUEST_TIMEOUT);
+
+        let config = DtakologConfig {
+            vehicle_fetch_timeout_secs: 180,
+            ..Default::default()
+        };
+        assert_eq!(cdp_request_timeout(&config), Duration::from_secs(190));
+    }
+
+    #[test]
+    fn test_sample_schema() {
+        let scraper = DtakologScraper::new(DtakologConfig::default());
+        assert!(scraper.sample_schema().is_err());
+
+        *scraper.last_vehicle_sample.lock().unwrap() = Some(serde_json::json!({
+            "VehicleCD": "001",
+            "Speed": 42,
+            "Running": true,
+            "Driver": null,
+        }));
+        let schema = scraper.sample_schema().unwrap();
+        assert_eq!(schema.get("VehicleCD").map(String::as_str), Some("string"));
+        assert_eq!(schema.get("Speed").map(String::as_str), Some("number"));
+        assert_eq!(schema.get("Running").map(String::as_str), Some("bool"));
+        assert_eq!(schema.get("Driver").map(String::as_str), Some("null"));
+    }
+
+    #[test]
+    fn test_parse_vehicle_data_stable_json() {
+        let scraper = DtakologScraper::new(DtakologConfig::default());
+        let raw_data: DtakologData = vec![serde_json::json!({
+            "VehicleCD": "001",
+            "VehicleName": "1号車",
+            "Status": "走行中",
+            "Speed": 42,
+            "Address": "東京都",
+            "DriverName": "山田",
+            "Latitude": 35.0,
+        })];
+
+        let first =
+            serde_json::to_string(&scraper.parse_vehicle_data(&raw_data, "00000000")).unwrap();
+        let second =
+            serde_json::to_string(&scraper.parse_vehicle_data(&raw_data, "00000000")).unwrap();
+        assert_eq!(first, second);
+
+        let vehicles = scraper.parse_vehicle_data(&raw_data, "00000000");
+        let keys: Vec<&str> = vehicles[0].metadata.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["Address", "DriverName", "Latitude", "Speed"]);
+    }
+
+    #[test]
+    fn test_raw_data_filename_unique() {
+        let first = raw_data_filename("20240105_080000");
+        let second = raw_data_filename("20240105_080000");
+        assert_ne!(first, second);
+        assert!(first.starts_with(&format!("vehicles_20240105_080000_{}-", std::process::id())));
+        assert!(first.ends_with(".json"));
+    }
+
+    #[test]
+    fn test_session_id_from_cookies() {
+        let cookie = |name: &str, value: &str| (name.to_string(), value.to_string());
+
+        assert_eq!(
+            session_id_from_cookies(
+                &[cookie("other", "x"), cookie("ASP.NET_SessionId", "abc123"),]
+            ),
+            Some("abc123".to_string())
+        );
+        assert_eq!(
+            session_id_from_cookies(&[cookie("ASP.NET_SessionId", "")]),
+            None
+        );
+        assert_eq!(session_id_from_cookies(&[]), None);
+    }
+
+    #[tokio::test]
+    async fn test_screenshot_without_open_page() {
+        let scraper = DtakologScraper::new(DtakologConfig::default());
+        let err = scraper
+            .screenshot(Path::new("unused.png"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ScraperError::Screenshot(_)), "{:?}", err);
+        assert_eq!(err.category(), crate::ErrorCategory::Io);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_send_to_grpc_with_retry_backoff() {
+        let scraper = DtakologScraper::new(DtakologConfig::default());
+        let start = tokio::time::Instant::now();
+        let err = scraper
+            .send_to_grpc_with_retry(&DtakologData::new(), &[])
+            .await
+            .unwrap_err();
+
+        // リトライ可能なエラーは MAX_RETRIES 回試行した後、最後のエラーを返す
+        assert!(matches!(err, ScraperError::Grpc(_)));
+        let max_total: u64 = (0..MAX_RETRIES - 1)
+            .map(|attempt| (INITIAL_BACKOFF_MS << attempt).min(MAX_BACKOFF_MS))
+            .sum();
+        assert!(start.elapsed() <= Duration::from_millis(max_total));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_with_backoff_seeded() {
+        let seed = 42;
+        let expected: Duration = {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..MAX_RETRIES - 1)
+                .map(|attempt| backoff_with_jitter(attempt, &mut rng))
+                .sum()
+        };
+        assert!(expected > Duration::ZERO);
+
+        // リトライ可能なエラーは MAX_RETRIES 回試行し、試行の間だけ待機する
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        let start = tokio::time::Instant::now();
+        let err = retry_with_backoff::<(), _, _>(&mut StdRng::seed_from_u64(seed), || {
+            attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            async { Err(ScraperError::Grpc("unavailable".to_string())) }
+        })
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ScraperError::Grpc(_)));
+        assert_eq!(attempts.into_inner(), MAX_RETRIES);
+        assert_eq!(start.elapsed(), expected);
+
+        // リトライ対象外のエラーは1回で返し、待機しない
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        let start = tokio::time::Instant::now();
+        let err = retry_with_backoff::<(), _, _>(&mut StdRng::seed_from_u64(seed), || {
+            attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            async { Err(ScraperError::Config("invalid".to_string())) }
+        })
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ScraperError::Config(_)));
+        assert_eq!(attempts.into_inner(), 1);
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_user_data_dir_removed_on_drop() {
+        let base = std::env::temp_dir().join(format!("dtakolog_base_{}", std::process::id()));
+        let mut scraper = DtakologScraper::new(DtakologConfig {
+            user_data_base: Some(base.clone()),
+            ..Default::default()
+        });
+        let dir = scraper.new_user_data_dir();
+        assert_eq!(dir.parent(), Some(base.as_path()));
+
+        std::fs::create_dir_all(dir.join("Default")).unwrap();
+        scraper.user_data_dir = Some(dir.clone());
+        drop(scraper);
+        assert!(!dir.exists());
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_requested_download_dedup() {
+        let notification = DvrNotification {
+            vehicle_cd: 101,
+            vehicle_name: "1号車".to_string(),
+            serial_no: "SN001".to_string(),
+            file_name: "event-1.mp4".to_string(),
+            file_path: String::new(),
+            event_type: "急ブレーキ".to_string(),
+            dvr_datetime: "2024-04-01 10:00:00".to_string(),
+            driver_name: "山田".to_string(),
+        };
+
+        let scraper = DtakologScraper::new(DtakologConfig::default());
+        assert!(!scraper.recently_requested(&notification));
+        scraper.mark_requested(&notification);
+        assert!(scraper.recently_requested(&notification));
+
+        // 記録を共有すれば、リクエストごとに作成したスクレイパー間でも再リクエストしない
+        let requested = RequestedDownloads::default();
+        let config = DtakologConfig {
+            requested_downloads: requested.clone(),
+            ..Default::default()
+        };
+        DtakologScraper::new(config.clone()).mark_requested(&notification);
+        assert!(DtakologScraper::new(config).recently_requested(&notification));
+        assert!(DtakologScraper::new(DtakologConfig {
+            requested_downloads: requested,
+            ..Default::default()
+        })
+        .recently_requested(&notification));
+        assert!(!DtakologScraper::new(DtakologConfig::default()).recently_requested(&notification));
+
+        let scraper = DtakologScraper::new(DtakologConfig {
+            dvr_request_dedup_secs: 0,
+            ..Default::default()
+        });
+        scraper.mark_requested(&notification);
+        assert!(!scraper.recently_requested(&notification));
+    }
+
+    #[test]
+    fn test_backoff_with_jitter_bounds() {
+        for attempt in 0..10 {
+            let cap = (INITIAL_BACKOFF_MS * 2u64.pow(attempt)).min(MAX_BACKOFF_MS);
+            for _ in 0..100 {
+                let backoff = backoff_with_jitter(attempt, &mut rand::thread_rng());
+                assert!(backoff <= Duration::from_millis(cap));
+            }
+        }
+        // 極端に大きい試行回数でもオーバーフローせず上限に収まる
+        assert!(
+            backoff_with_jitter(u32::MAX, &mut rand::thread_rng())
+                <= Duration::from_millis(MAX_BACKOFF_MS)
+        );
+    }
+
+    #[test]
+    fn test_dedup_by_vehicle_cd() {
+        let mut seen = HashSet::new();
+        let first = dedup_by_vehicle_cd(
+            vec![
+                serde_json::json!({"VehicleCD": "1"}),
+                serde_json::json!({"VehicleCD": "2"}),
+            ],
+            &mut seen,
+        );
+        assert_eq!(first.len(), 2);
+
+        let second = dedup_by_vehicle_cd(
+            vec![
+                serde_json::json!({"VehicleCD": "2"}),
+                serde_json::json!({"VehicleCD": "3"}),
+                serde_json::json!({"VehicleName": "no cd"}),
+            ],
+            &mut seen,
+        );
+        assert_eq!(
+            second,
+            vec![
+                serde_json::json!({"VehicleCD": "3"}),
+                serde_json::json!({"VehicleName": "no cd"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_video_url() {
+        let base = DEFAULT_DVR_BASE_URL;
+        assert_eq!(
+            DtakologScraper::build_video_url(base, "2025/01/01", "EV001.vdf"),
+            "http://theearth-np.com/dvrData/2025/01/01/EV001-1.mp4"
+        );
+        // 途中の ".vdf" は変更しない
+        assert_eq!(
+            DtakologScraper::build_video_url(base, "p", "a.vdf.b.VDF"),
+            "http://theearth-np.com/dvrData/p/a.vdf.b-1.mp4"
+        );
+        // 拡張子なし・マルチバイト
+        assert_eq!(
+            DtakologScraper::build_video_url(base, "p", "映像"),
+            "http://theearth-np.com/dvrData/p/映像-1.mp4"
+        );
+        assert_eq!(
+            DtakologScraper::build_video_url("https://example.com/dvr/", "/p/", "x.vdf"),
+            "https://example.com/dvr/p/x-1.mp4"
+        );
+    }
+
+    #[test]
+    fn test_write_atomic_hides_partial_writes() {
+        let dir = std::env::temp_dir().join(format!("dtakolog_atomic_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("vehicles_20250101_000000.json");
+
+        // 書き込み途中でクラッシュした状態を再現（一時ファイルのみ残る）
+        std::fs::write(tmp_path_for(&target), b"[{\"VehicleCD\":").unwrap();
+        let json_files = |dir: &Path| {
+            std::fs::read_dir(dir)
+                .unwrap()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+                .count()
+        };
+        assert_eq!(json_files(&dir), 0);
+
+        // 書き込み中の可能性がある新しい一時ファイルは残す
+        cleanup_orphaned_tmp_files(&dir, ORPHANED_TMP_MIN_AGE);
+        assert!(tmp_path_for(&target).exists());
+
+        // 次回実行時に古い一時ファイルが削除される
+        std::fs::File::options()
+            .write(true)
+            .open(tmp_path_for(&target))
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() - ORPHANED_TMP_MIN_AGE * 2)
+            .unwrap();
+        cleanup_orphaned_tmp_files(&dir, ORPHANED_TMP_MIN_AGE);
+        assert!(!tmp_path_for(&target).exists());
+
+        write_atomic(&target, b"[]").unwrap();
+        assert_eq!(std::fs::read(&target).unwrap(), b"[]");
+        assert!(!tmp_path_for(&target).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 
     #[tokio::test]
     #[ignore] // 実環境テスト用: cargo test -p scraper-service test_dtakolog_scraper -- --ignored --nocapture
@@ -1264,6 +2717,7 @@ mod tests {
             user_pass,
             headless: true, // ヘッドレスモード
             debug: true,
+            save_raw_data: false,
             ..Default::default()
         };
 
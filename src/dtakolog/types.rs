@@ -1,7 +1,19 @@
 //! Dtakolog 関連の型定義
 
+use chrono::FixedOffset;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::config::LoginStrategy;
+use crate::error::ScraperError;
+
+/// ダウンロードリクエスト済みの動画 (SerialNo, FileName) とリクエスト時刻
+///
+/// リクエストごとに作成されるスクレイパー間で共有し、同じ動画の再リクエストを防ぐ。
+pub type RequestedDownloads = Arc<Mutex<HashMap<(String, String), Instant>>>;
 
 /// Vehicleデータ
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,13 +24,52 @@ pub struct VehicleData {
     pub vehicle_name: String,
     #[serde(rename = "Status")]
     pub status: String,
+    /// 上記以外のフィールド（キー順で保持し、シリアライズ結果を安定させる）
     #[serde(rename = "Metadata")]
-    pub metadata: HashMap<String, String>,
+    pub metadata: BTreeMap<String, String>,
+    /// 取得元のブランチID
+    #[serde(rename = "BranchID", default)]
+    pub branch_id: String,
 }
 
 /// 生データ (JSON形式で保持)
 pub type DtakologData = Vec<serde_json::Value>;
 
+/// 生データを1件ずつフラットなマップに変換（gRPC送信・ETL向けの共通の形）
+///
+/// ネストしたオブジェクトは `親.子` のキーに展開し、配列・スカラー・null はそのまま値とする。
+/// オブジェクト以外の要素は除外する。
+pub fn flatten_records(raw_data: &DtakologData) -> Vec<BTreeMap<String, serde_json::Value>> {
+    raw_data
+        .iter()
+        .filter_map(|item| {
+            let obj = item.as_object()?;
+            let mut record = BTreeMap::new();
+            flatten_into(&mut record, None, obj);
+            Some(record)
+        })
+        .collect()
+}
+
+fn flatten_into(
+    record: &mut BTreeMap<String, serde_json::Value>,
+    prefix: Option<&str>,
+    obj: &serde_json::Map<String, serde_json::Value>,
+) {
+    for (key, value) in obj {
+        let key = match prefix {
+            Some(prefix) => format!("{}.{}", prefix, key),
+            None => key.clone(),
+        };
+        match value {
+            serde_json::Value::Object(nested) => flatten_into(record, Some(&key), nested),
+            other => {
+                record.insert(key, other.clone());
+            }
+        }
+    }
+}
+
 /// gRPC送信結果
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GrpcResponse {
@@ -28,6 +79,31 @@ pub struct GrpcResponse {
     pub message: String,
 }
 
+/// gRPC送信内容のサマリー（送信元と送信先の件数突き合わせ用）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SendSummary {
+    /// 送信したVehicle件数
+    pub vehicles_sent: usize,
+    /// 生データのうちVehicleとして解釈できず除外した件数
+    pub vehicles_filtered: usize,
+    /// 送信に含めた映像通知件数
+    pub notifications_sent: usize,
+}
+
+impl SendSummary {
+    pub fn new(
+        raw_data: &DtakologData,
+        vehicles: &[VehicleData],
+        notifications: &[VideoNotificationResult],
+    ) -> Self {
+        Self {
+            vehicles_sent: vehicles.len(),
+            vehicles_filtered: raw_data.len().saturating_sub(vehicles.len()),
+            notifications_sent: notifications.len(),
+        }
+    }
+}
+
 /// Dtakolog スクレイプ結果
 #[derive(Debug, Clone)]
 pub struct DtakologResult {
@@ -35,12 +111,32 @@ pub struct DtakologResult {
     pub vehicles: Vec<VehicleData>,
     /// 生のJSONデータ
     pub raw_data: DtakologData,
-    /// 新規/更新されたセッションID
+    /// 認証済みセッションのID（`ASP.NET_SessionId` クッキーの値）
+    ///
+    /// クッキーが取得できなかった場合は `session_<UNIXタイムスタンプ>` 形式の合成値で、
+    /// セッション再利用には使えない。
     pub session_id: String,
     /// gRPC送信結果（送信した場合）
     pub grpc_response: Option<GrpcResponse>,
+    /// gRPC送信内容のサマリー（送信した場合）
+    pub send_summary: Option<SendSummary>,
     /// 映像通知結果（mp4 URL付き）
     pub video_notifications: Vec<VideoNotificationResult>,
+    /// ダウンロードをリクエストしたが未完了の動画（`poll_pending_downloads` で後から回収）
+    pub pending_downloads: Vec<PendingDownload>,
+    /// 生データの保存先（`./data/vehicles_*.json`、保存に失敗した場合は `None`）
+    pub raw_data_path: Option<PathBuf>,
+    /// 直近のログイン直後のページURL（保存済みセッションでログインを省略した場合は前回の値または `None`）
+    pub last_url: Option<String>,
+    /// ブラウザのコンソール出力（`capture_console` 有効時のみ）
+    pub console_logs: Vec<String>,
+    /// 取得に失敗したブランチ (branch_id, エラー)
+    ///
+    /// 複数ブランチのうち一部が失敗した場合、成功したブランチの車両のみで結果を返し、
+    /// 失敗分をここに記録する（全ブランチ失敗時は結果ではなくエラーを返す）。
+    /// 部分的なデータを受け入れるかは呼び出し側で判断する。
+    /// `DtakologResult` を `Clone` のまま保つため `Arc` で保持する。
+    pub branch_errors: Vec<(String, Arc<ScraperError>)>,
 }
 
 /// 映像通知結果（rust-logi送信用、mp4 URL付き）
@@ -54,6 +150,54 @@ pub struct VideoNotificationResult {
     pub dvr_datetime: String,
     pub driver_name: String,
     pub mp4_url: String,
+    /// `mp4_url` へのHEADリクエストで到達を確認できたか（`verify_video_urls` 無効時は常に false）
+    #[serde(default)]
+    pub verified: bool,
+}
+
+/// ダウンロードリクエスト済みで、DVRからのアップロード待ちの動画
+///
+/// 呼び出し側で永続化し、後で `DtakologScraper::poll_pending_downloads` に渡して回収する。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingDownload {
+    pub vehicle_cd: i64,
+    pub vehicle_name: String,
+    pub serial_no: String,
+    pub file_name: String,
+    pub event_type: String,
+    pub dvr_datetime: String,
+    pub driver_name: String,
+}
+
+impl PendingDownload {
+    /// 動画の準備完了時の結果に変換
+    pub fn into_result(self, mp4_url: String) -> VideoNotificationResult {
+        VideoNotificationResult {
+            vehicle_cd: self.vehicle_cd,
+            vehicle_name: self.vehicle_name,
+            serial_no: self.serial_no,
+            file_name: self.file_name,
+            event_type: self.event_type,
+            dvr_datetime: self.dvr_datetime,
+            driver_name: self.driver_name,
+            mp4_url,
+            verified: false,
+        }
+    }
+}
+
+impl From<&DvrNotification> for PendingDownload {
+    fn from(notification: &DvrNotification) -> Self {
+        Self {
+            vehicle_cd: notification.vehicle_cd,
+            vehicle_name: notification.vehicle_name.clone(),
+            serial_no: notification.serial_no.clone(),
+            file_name: notification.file_name.clone(),
+            event_type: notification.event_type.clone(),
+            dvr_datetime: notification.dvr_datetime.clone(),
+            driver_name: notification.driver_name.clone(),
+        }
+    }
 }
 
 /// 映像通知データ（Monitoring_DvrNotification2 の結果）
@@ -87,7 +231,7 @@ pub struct DvrFileInfo {
 }
 
 /// Dtakolog スクレイプ設定
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct DtakologConfig {
     /// 会社ID
     pub comp_id: String,
@@ -99,16 +243,119 @@ pub struct DtakologConfig {
     pub branch_id: String,
     /// フィルターID (デフォルト: "0")
     pub filter_id: String,
+    /// 取得対象の (ブランチID, フィルターID) の組み合わせ
+    ///
+    /// 空の場合は `branch_id` / `filter_id` の1件のみを対象とする。
+    pub branches: Vec<(String, String)>,
+    /// 複数ブランチ取得時に同一ブラウザ内で同時に開くページ数 (デフォルト: 1 = 逐次取得)
+    ///
+    /// 2以上の場合、ブランチごとに新しいページを開いて並列に取得する。
+    /// ページはブラウザのデフォルトコンテキストを共有するため、ログイン済みのセッションをそのまま利用する。
+    pub max_concurrent_pages: usize,
     /// ヘッドレスモード
     pub headless: bool,
     /// デバッグモード
     pub debug: bool,
     /// セッションTTL（秒）
     pub session_ttl_secs: u64,
+    /// VenusBridgeService の読み込み待機時間（秒）
+    pub service_wait_secs: u64,
+    /// VenusBridgeService の読み込み確認間隔（ミリ秒）
+    pub service_poll_interval_ms: u64,
+    /// 車両データ取得（VehicleStateTableForBranchEx）の応答待ちの上限（秒、デフォルト: 60）
+    ///
+    /// 車両数の多いブランチでは長めに、疎通確認のみの場合は短めに設定する。
+    pub vehicle_fetch_timeout_secs: u64,
+    /// ログインボタン押下後、ログイン完了を確認するまでの上限時間（秒）
+    pub login_timeout_secs: u64,
+    /// ブラウザのコンソール出力を収集して結果・エラーに含めるか (デフォルト: false)
+    pub capture_console: bool,
     /// gRPC URL (例: "http://localhost:50051")
     pub grpc_url: Option<String>,
     /// gRPC組織ID
     pub grpc_organization_id: Option<String>,
+    /// 動画ファイル確認（Request_DvrFileList）の同時実行数
+    pub dvr_concurrency: usize,
+    /// 同じ動画 (SerialNo, FileName) のダウンロードリクエストを再送しない期間（秒、0で無効）
+    ///
+    /// 繰り返し `scrape` する場合、動画の準備が完了するまで同じ通知が返るため、
+    /// この期間内に一度リクエストした動画は再リクエストせず待機中として扱う。
+    pub dvr_request_dedup_secs: u64,
+    /// `dvr_request_dedup_secs` の判定に使うリクエスト済み動画の記録
+    ///
+    /// 既定では設定ごとに新しい記録を作成する（クローンした設定間では共有される）。
+    /// スクレイパーを都度作成する場合は、共有の記録を渡す。
+    pub requested_downloads: RequestedDownloads,
+    /// 動画URLのベース (デフォルト: "http://theearth-np.com/dvrData")
+    pub dvr_base_url: Option<String>,
+    /// 映像通知（動画）処理を行うか (デフォルト: true)
+    pub process_videos: bool,
+    /// 返却前に動画URLへHEADリクエストを送り、到達可否を `verified` に記録するか (デフォルト: false)
+    pub verify_video_urls: bool,
+    /// 取得した生データを `./data/` に保存するか (デフォルト: true、テストでは false を指定する)
+    ///
+    /// false の場合はファイルを書き込まず、`DtakologResult::raw_data_path` は `None` になる。
+    pub save_raw_data: bool,
+    /// 生データ・デバッグ用スクリーンショットのファイル名に使う時刻のタイムゾーン（デフォルト: JST）
+    ///
+    /// UTC のファイル名にする場合は `FixedOffset::east_opt(0).unwrap()` を指定する。
+    pub timestamp_offset: FixedOffset,
+    /// ブラウザのロケール（`--lang` と Accept-Language に反映、デフォルト: "ja-JP"）
+    pub locale: String,
+    /// ブラウザのユーザーデータディレクトリを作成する親ディレクトリ（デフォルト: システムの一時ディレクトリ）
+    ///
+    /// 起動ごとに `dtakolog-<id>` を作成し、`close` / ドロップ時に削除する。
+    pub user_data_base: Option<PathBuf>,
+    /// 起動済みブラウザのCDPエンドポイント（例: `ws://browserless:3000`）
+    ///
+    /// 指定した場合はブラウザを起動せずに接続する。`headless` / `debug` と起動引数は無視される。
+    pub cdp_endpoint: Option<String>,
+    /// `scrape` に渡したセッションクッキーの使い方 (デフォルト: `ReuseElseLogin`)
+    ///
+    /// `scrape` の `force_login` が true の場合は `Always` として扱う。
+    pub login_strategy: LoginStrategy,
+    /// CDPハンドラー・コンソール収集のタスクを起動するランタイム（未指定時は呼び出し元のランタイム）
+    pub runtime: Option<tokio::runtime::Handle>,
+}
+
+impl std::fmt::Debug for DtakologConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DtakologConfig")
+            .field("comp_id", &self.comp_id)
+            .field("user_name", &self.user_name)
+            .field("user_pass", &"***")
+            .field("branch_id", &self.branch_id)
+            .field("filter_id", &self.filter_id)
+            .field("branches", &self.branches)
+            .field("max_concurrent_pages", &self.max_concurrent_pages)
+            .field("headless", &self.headless)
+            .field("debug", &self.debug)
+            .field("session_ttl_secs", &self.session_ttl_secs)
+            .field("service_wait_secs", &self.service_wait_secs)
+            .field("service_poll_interval_ms", &self.service_poll_interval_ms)
+            .field(
+                "vehicle_fetch_timeout_secs",
+                &self.vehicle_fetch_timeout_secs,
+            )
+            .field("login_timeout_secs", &self.login_timeout_secs)
+            .field("capture_console", &self.capture_console)
+            .field("grpc_url", &self.grpc_url)
+            .field("grpc_organization_id", &self.grpc_organization_id)
+            .field("dvr_concurrency", &self.dvr_concurrency)
+            .field("dvr_request_dedup_secs", &self.dvr_request_dedup_secs)
+            .field("requested_downloads", &self.requested_downloads)
+            .field("dvr_base_url", &self.dvr_base_url)
+            .field("process_videos", &self.process_videos)
+            .field("verify_video_urls", &self.verify_video_urls)
+            .field("save_raw_data", &self.save_raw_data)
+            .field("timestamp_offset", &self.timestamp_offset)
+            .field("locale", &self.locale)
+            .field("user_data_base", &self.user_data_base)
+            .field("cdp_endpoint", &self.cdp_endpoint)
+            .field("login_strategy", &self.login_strategy)
+            .field("runtime", &self.runtime)
+            .finish()
+    }
 }
 
 impl Default for DtakologConfig {
@@ -119,11 +366,323 @@ impl Default for DtakologConfig {
             user_pass: String::new(),
             branch_id: "00000000".to_string(),
             filter_id: "0".to_string(),
+            branches: Vec::new(),
+            max_concurrent_pages: 1,
             headless: true,
             debug: false,
             session_ttl_secs: 3600,
+            service_wait_secs: 30,
+            service_poll_interval_ms: 1000,
+            vehicle_fetch_timeout_secs: 60,
+            login_timeout_secs: 60,
+            capture_console: false,
             grpc_url: None,
             grpc_organization_id: None,
+            dvr_concurrency: 3,
+            dvr_request_dedup_secs: 600,
+            requested_downloads: RequestedDownloads::default(),
+            dvr_base_url: None,
+            process_videos: true,
+            verify_video_urls: false,
+            save_raw_data: true,
+            timestamp_offset: FixedOffset::east_opt(9 * 3600).expect("JST offset"),
+            locale: "ja-JP".to_string(),
+            user_data_base: None,
+            cdp_endpoint: None,
+            login_strategy: LoginStrategy::default(),
+            runtime: None,
+        }
+    }
+}
+
+impl DtakologConfig {
+    /// 設定値を検証（ブラウザ起動前の早期エラー検出用）
+    pub fn validate(&self) -> Result<(), ScraperError> {
+        let required = [
+            ("comp_id", &self.comp_id),
+            ("user_name", &self.user_name),
+            ("user_pass", &self.user_pass),
+            ("branch_id", &self.branch_id),
+            ("filter_id", &self.filter_id),
+        ];
+        for (name, value) in required {
+            if value.trim().is_empty() {
+                return Err(ScraperError::Config(format!("{} is empty", name)));
+            }
+        }
+
+        if self.max_concurrent_pages == 0 {
+            return Err(ScraperError::Config(
+                "max_concurrent_pages must be non-zero".to_string(),
+            ));
+        }
+
+        if self.service_poll_interval_ms == 0 {
+            return Err(ScraperError::Config(
+                "service_poll_interval_ms must be non-zero".to_string(),
+            ));
+        }
+
+        if self.vehicle_fetch_timeout_secs == 0 {
+            return Err(ScraperError::Config(
+                "vehicle_fetch_timeout_secs must be non-zero".to_string(),
+            ));
+        }
+
+        for (branch_id, filter_id) in &self.branches {
+            if branch_id.trim().is_empty() || filter_id.trim().is_empty() {
+                return Err(ScraperError::Config(format!(
+                    "branches contains an empty entry: ('{}', '{}')",
+                    branch_id, filter_id
+                )));
+            }
+        }
+
+        if let Some(ref url) = self.grpc_url {
+            validate_url(url).map_err(|reason| {
+                ScraperError::Config(format!("invalid grpc_url '{}': {}", url, reason))
+            })?;
+        }
+
+        if let Some(ref endpoint) = self.cdp_endpoint {
+            crate::browser::validate_cdp_endpoint(endpoint)?;
+        }
+
+        if let Some(ref url) = self.dvr_base_url {
+            validate_url(url).map_err(|reason| {
+                ScraperError::Config(format!("invalid dvr_base_url '{}': {}", url, reason))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// ブラウザ起動時にこの設定から追加される引数
+    ///
+    /// chromiumoxide が常に付与する既定の引数や `--user-data-dir` は含まない。
+    /// `cdp_endpoint` 指定時は使われない。
+    pub fn effective_browser_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--disable-blink-features=AutomationControlled".to_string(),
+            "--disable-dev-shm-usage".to_string(),
+            "--disable-gpu".to_string(),
+            "--disable-web-security".to_string(), // CORS制限を無効化
+            "--allow-running-insecure-content".to_string(),
+            format!("--lang={}", self.locale),
+        ];
+        if self.debug {
+            args.push("--enable-logging=stderr".to_string());
+            args.push("--v=1".to_string());
+        }
+        args
+    }
+
+    /// 取得対象の (ブランチID, フィルターID) 一覧
+    pub fn branch_filters(&self) -> Vec<(String, String)> {
+        if self.branches.is_empty() {
+            vec![(self.branch_id.clone(), self.filter_id.clone())]
+        } else {
+            self.branches.clone()
+        }
+    }
+}
+
+/// URLの形式を簡易チェック（scheme://host[:port][/path]）
+fn validate_url(url: &str) -> Result<(), &'static str> {
+    let (scheme, rest) = url.split_once("://").ok_or("missing scheme")?;
+    if !matches!(scheme, "http" | "https") {
+        return Err("scheme must be http or https");
+    }
+    let authority = rest.split('/').next().unwrap_or_default();
+    let host = match authority.rsplit_once(':') {
+        Some((host, port)) => {
+            port.parse::<u16>().map_err(|_| "invalid port")?;
+            host
+        }
+        None => authority,
+    };
+    if host.is_empty() {
+        return Err("missing host");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn valid_config() -> DtakologConfig {
+        DtakologConfig {
+            comp_id: "comp".to_string(),
+            user_name: "user".to_string(),
+            user_pass: "pass".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_send_summary() {
+        let raw_data: DtakologData = vec![
+            serde_json::json!({"VehicleCD": "1"}),
+            serde_json::json!({"VehicleCD": "2"}),
+            serde_json::json!("not an object"),
+        ];
+        let vehicles: Vec<VehicleData> = raw_data
+            .iter()
+            .filter(|v| v.is_object())
+            .map(|_| VehicleData {
+                vehicle_cd: String::new(),
+                vehicle_name: String::new(),
+                status: String::new(),
+                metadata: BTreeMap::new(),
+                branch_id: String::new(),
+            })
+            .collect();
+
+        let summary = SendSummary::new(&raw_data, &vehicles, &[]);
+        assert_eq!(summary.vehicles_sent, 2);
+        assert_eq!(summary.vehicles_filtered, 1);
+        assert_eq!(summary.notifications_sent, 0);
+    }
+
+    #[test]
+    fn test_flatten_records() {
+        let raw_data: DtakologData = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/dtakolog_vehicles.json"
+        )))
+        .unwrap();
+        let records = flatten_records(&raw_data);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["VehicleCD"], "101");
+        assert_eq!(records[0]["Speed"], 42);
+        assert_eq!(records[0]["Position.Lat"], 35.6812);
+        assert_eq!(records[0]["Position.Address.Pref"], "東京都");
+        assert_eq!(records[0]["Alerts"], serde_json::json!(["速度超過"]));
+        assert!(!records[0].contains_key("Position"));
+        assert!(records[1]["Position"].is_null());
+    }
+
+    #[test]
+    fn test_pending_download_roundtrip() {
+        let notification: DvrNotification = serde_json::from_value(serde_json::json!({
+            "VehicleCD": 101,
+            "VehicleName": "1号車",
+            "SerialNo": "SN001",
+            "FileName": "event.vdf",
+            "FilePath": "",
+            "EventType": "急ブレーキ",
+            "DvrDatetime": "2024-01-05 08:00:00",
+            "DriverName": "山田",
+        }))
+        .unwrap();
+
+        let pending = PendingDownload::from(&notification);
+        let json = serde_json::to_string(&pending).unwrap();
+        let restored: PendingDownload = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, pending);
+
+        let result = restored.into_result("http://example.com/event-1.mp4".to_string());
+        assert_eq!(result.vehicle_cd, 101);
+        assert_eq!(result.serial_no, "SN001");
+        assert_eq!(result.mp4_url, "http://example.com/event-1.mp4");
+    }
+
+    #[test]
+    fn test_validate_ok() {
+        let mut config = valid_config();
+        assert!(config.validate().is_ok());
+
+        config.grpc_url = Some("http://localhost:50051".to_string());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_debug_redacts_password() {
+        let debug = format!(
+            "{:?}",
+            DtakologConfig {
+                user_pass: "secret-pass".to_string(),
+                ..valid_config()
+            }
+        );
+        assert!(!debug.contains("secret-pass"));
+    }
+
+    #[test]
+    fn test_validate_missing_fields() {
+        let config = DtakologConfig {
+            user_pass: String::new(),
+            ..valid_config()
+        };
+        assert!(
+            matches!(config.validate(), Err(ScraperError::Config(msg)) if msg.contains("user_pass"))
+        );
+
+        let config = DtakologConfig {
+            branch_id: String::new(),
+            ..valid_config()
+        };
+        assert!(config.validate().is_err());
+
+        let config = DtakologConfig {
+            service_poll_interval_ms: 0,
+            ..valid_config()
+        };
+        assert!(config.validate().is_err());
+
+        let config = DtakologConfig {
+            max_concurrent_pages: 0,
+            ..valid_config()
+        };
+        assert!(config.validate().is_err());
+
+        let config = DtakologConfig {
+            vehicle_fetch_timeout_secs: 0,
+            ..valid_config()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_branch_filters() {
+        let config = valid_config();
+        assert_eq!(
+            config.branch_filters(),
+            vec![("00000000".to_string(), "0".to_string())]
+        );
+
+        let config = DtakologConfig {
+            branches: vec![
+                ("00000001".to_string(), "0".to_string()),
+                ("00000002".to_string(), "1".to_string()),
+            ],
+            ..valid_config()
+        };
+        assert_eq!(config.branch_filters().len(), 2);
+        assert!(config.validate().is_ok());
+
+        let config = DtakologConfig {
+            branches: vec![("00000001".to_string(), String::new())],
+            ..valid_config()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_grpc_url() {
+        for url in [
+            "localhost:50051",
+            "ftp://host",
+            "http://",
+            "http://host:port",
+        ] {
+            let config = DtakologConfig {
+                grpc_url: Some(url.to_string()),
+                ..valid_config()
+            };
+            assert!(config.validate().is_err(), "{} should be rejected", url);
         }
     }
 }
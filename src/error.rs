@@ -1,7 +1,16 @@
+use std::time::Duration;
+
+use serde::Serialize;
 use thiserror::Error;
 
+/// アクセス制限ページに待ち時間の記載がない場合の待ち時間
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(15 * 60);
+
 #[derive(Error, Debug)]
 pub enum ScraperError {
+    #[error("設定エラー: {0}")]
+    Config(String),
+
     #[error("ブラウザ初期化エラー: {0}")]
     BrowserInit(String),
 
@@ -23,9 +32,35 @@ pub enum ScraperError {
     #[error("ファイル操作エラー: {0}")]
     FileIO(#[from] std::io::Error),
 
+    /// スクリーンショットの取得失敗
+    #[error("スクリーンショットエラー: {0}")]
+    Screenshot(String),
+
+    #[error("ダウンロードディレクトリに書き込めません（download_path is not writable）: {}: {source}", path.display())]
+    DownloadPathNotWritable {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+
     #[error("明細データなし: {0}")]
     NoUsageData(String),
 
+    /// 明細CSVの列構成が想定と異なる（サイト側の仕様変更）
+    #[error("CSV列構成エラー: {0}")]
+    CsvSchema(String),
+
+    /// サイトがメンテナンス中（短時間での再試行は無意味なためリトライ対象外）
+    #[error("メンテナンス中: {0}")]
+    Maintenance(String),
+
+    /// 短時間のログイン繰り返しによるアクセス制限（`retry_after` 経過後に再実行する）
+    #[error("アクセス制限中（再実行までの待ち時間: {retry_after:?}）")]
+    RateLimited { retry_after: Option<Duration> },
+
+    /// パスワードの有効期限切れ（サイト上でのパスワード変更が必要なためリトライ対象外）
+    #[error("パスワード有効期限切れ: {0}")]
+    PasswordExpired(String),
+
     // Dtakolog 固有のエラー
     #[error("データ抽出エラー: {0}")]
     Extraction(String),
@@ -36,6 +71,13 @@ pub enum ScraperError {
     #[error("セッションエラー: {0}")]
     Session(String),
 
+    /// 同一アカウントが別の場所でログインしたことによる強制ログアウト
+    ///
+    /// 複数のワーカーが同じアカウントを取り合っている可能性がある。再ログインすると相手側が
+    /// ログアウトされるため、自動では再ログインしない。
+    #[error("別のログインによりセッションが無効化されました（session superseded by another login）: {0}")]
+    SessionSuperseded(String),
+
     #[error("gRPCエラー: {0}")]
     Grpc(String),
 
@@ -44,17 +86,155 @@ pub enum ScraperError {
 
     #[error("JSONシリアライズエラー: {0}")]
     Json(String),
+
+    #[error("未対応の操作: {0}")]
+    Unsupported(String),
+
+    #[error("同一リクエストを実行中: {0}")]
+    DuplicateInProgress(String),
+
+    /// 結果の後処理（`ResultProcessor`）の失敗
+    #[error("後処理 {name} でエラー: {source}")]
+    Processor {
+        name: String,
+        source: Box<ScraperError>,
+    },
+}
+
+/// エラーの分類（サービス層でHTTPステータス等に対応付ける用）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum ErrorCategory {
+    /// 認証の失敗・セッション切れ（再認証が必要）
+    Auth,
+    /// 一時的な障害（時間をおいて再実行すれば成功しうる）
+    Transient,
+    /// サイト側の画面・データ構成の変更が疑われる
+    SiteChange,
+    /// 設定・リクエスト内容の誤り
+    Config,
+    /// ローカルのファイル操作の失敗
+    Io,
+    /// 対象期間に明細データがない
+    NoData,
+}
+
+impl ErrorCategory {
+    /// 対応するHTTPステータスコードの目安
+    ///
+    /// Auth=401, Transient=503, SiteChange=502, Config=400, Io=500, NoData=404
+    pub fn http_status(self) -> u16 {
+        match self {
+            ErrorCategory::Auth => 401,
+            ErrorCategory::Transient => 503,
+            ErrorCategory::SiteChange => 502,
+            ErrorCategory::Config => 400,
+            ErrorCategory::Io => 500,
+            ErrorCategory::NoData => 404,
+        }
+    }
 }
 
 impl ScraperError {
-    /// リトライ可能なエラーかどうか
+    /// エラーの分類
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            ScraperError::Processor { source, .. } => source.category(),
+            ScraperError::Login(_)
+            | ScraperError::Session(_)
+            | ScraperError::SessionSuperseded(_)
+            | ScraperError::PasswordExpired(_) => ErrorCategory::Auth,
+            ScraperError::BrowserInit(_)
+            | ScraperError::Navigation(_)
+            | ScraperError::Timeout(_)
+            | ScraperError::Download(_)
+            | ScraperError::Maintenance(_)
+            | ScraperError::RateLimited { .. }
+            | ScraperError::Grpc(_)
+            | ScraperError::GrpcConnectionFailed { .. }
+            | ScraperError::DuplicateInProgress(_) => ErrorCategory::Transient,
+            ScraperError::ElementNotFound(_)
+            | ScraperError::CsvSchema(_)
+            | ScraperError::Extraction(_)
+            | ScraperError::JavaScript(_)
+            | ScraperError::Json(_) => ErrorCategory::SiteChange,
+            ScraperError::Config(_) | ScraperError::Unsupported(_) => ErrorCategory::Config,
+            ScraperError::FileIO(_)
+            | ScraperError::DownloadPathNotWritable { .. }
+            | ScraperError::Screenshot(_) => ErrorCategory::Io,
+            ScraperError::NoUsageData(_) => ErrorCategory::NoData,
+        }
+    }
+
+    /// 時間をおけば再実行できるエラーの待ち時間
+    ///
+    /// `RateLimited` の場合のみ `Some`（ページに待ち時間の記載がなければ控えめに15分）。
+    /// 即座のリトライは制限を延ばすため、[`Self::is_retryable`] は `false` を返す。
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ScraperError::Processor { source, .. } => source.retry_after(),
+            ScraperError::RateLimited { retry_after } => {
+                Some(retry_after.unwrap_or(DEFAULT_RETRY_AFTER))
+            }
+            _ => None,
+        }
+    }
+
+    /// リトライ可能なエラーかどうか（`Processor` は元のエラーで判定）
     pub fn is_retryable(&self) -> bool {
-        matches!(
-            self,
-            ScraperError::Navigation(_)
-                | ScraperError::Timeout(_)
-                | ScraperError::Grpc(_)
-                | ScraperError::BrowserInit(_)
-        )
+        match self {
+            ScraperError::Processor { source, .. } => source.is_retryable(),
+            _ => matches!(
+                self,
+                ScraperError::Navigation(_)
+                    | ScraperError::Timeout(_)
+                    | ScraperError::Grpc(_)
+                    | ScraperError::BrowserInit(_)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category() {
+        let auth = ScraperError::Session("Redirected to login page".to_string());
+        assert_eq!(auth.category(), ErrorCategory::Auth);
+        assert_eq!(auth.category().http_status(), 401);
+        assert_eq!(
+            ScraperError::Maintenance(String::new()).category(),
+            ErrorCategory::Transient
+        );
+        assert_eq!(
+            ScraperError::CsvSchema(String::new()).category(),
+            ErrorCategory::SiteChange
+        );
+        assert_eq!(
+            ScraperError::Config(String::new()).category().http_status(),
+            400
+        );
+    }
+
+    #[test]
+    fn test_processor_delegates_to_source() {
+        let processor = |source| ScraperError::Processor {
+            name: "upload".to_string(),
+            source: Box::new(source),
+        };
+        let timeout = processor(ScraperError::Timeout(String::new()));
+        assert!(timeout.is_retryable());
+        assert_eq!(timeout.category(), ErrorCategory::Transient);
+
+        let config = processor(ScraperError::Config(String::new()));
+        assert!(!config.is_retryable());
+        assert_eq!(config.category(), ErrorCategory::Config);
+
+        let rate_limited = processor(ScraperError::RateLimited {
+            retry_after: Some(Duration::from_secs(60)),
+        });
+        assert!(!rate_limited.is_retryable());
+        assert_eq!(rate_limited.retry_after(), Some(Duration::from_secs(60)));
     }
 }
@@ -0,0 +1,197 @@
+//! 複数アカウントの認証情報ファイル
+//!
+//! `{user_id, password, download_path?}` のリストを JSON / TOML から読み込み、
+//! アカウントごとの `ScraperConfig` / `EtcScraper` を生成する。
+//!
+//! - JSON: アカウントの配列（`ETC_ACCOUNTS` 環境変数と同じ形式）
+//! - TOML: `[[accounts]]` テーブルの配列（`toml` feature 有効時のみ）
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::config::ScraperConfig;
+use crate::error::ScraperError;
+
+use super::EtcScraper;
+
+/// 1アカウント分の認証情報
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+pub struct Account {
+    pub user_id: String,
+    pub password: String,
+    /// アカウント固有のダウンロード先（未指定時はベース設定の `download_path`）
+    #[serde(default)]
+    pub download_path: Option<PathBuf>,
+}
+
+impl std::fmt::Debug for Account {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Account")
+            .field("user_id", &self.user_id)
+            .field("password", &"***")
+            .field("download_path", &self.download_path)
+            .finish()
+    }
+}
+
+impl Account {
+    /// ベース設定に認証情報（とダウンロード先）を上書きした設定
+    pub fn to_config(&self, base: &ScraperConfig) -> ScraperConfig {
+        let mut config = base.clone();
+        config.user_id = self.user_id.clone();
+        config.password = self.password.clone();
+        if let Some(ref path) = self.download_path {
+            config.download_path = path.clone();
+        }
+        config
+    }
+}
+
+/// TOML の `[[accounts]]` 形式
+#[cfg(feature = "toml")]
+#[derive(Deserialize)]
+struct TomlAccounts {
+    accounts: Vec<Account>,
+}
+
+/// 検証済みのアカウント一覧（`user_id` の重複なし）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountStore {
+    accounts: Vec<Account>,
+}
+
+impl AccountStore {
+    /// アカウント一覧を検証して作成
+    ///
+    /// 空の `user_id` / `password`、`user_id` の重複は `Config` エラー。
+    pub fn new(accounts: Vec<Account>) -> Result<Self, ScraperError> {
+        let mut seen = HashSet::new();
+        for (i, account) in accounts.iter().enumerate() {
+            if account.user_id.trim().is_empty() {
+                return Err(ScraperError::Config(format!(
+                    "accounts[{}]: user_id is empty",
+                    i
+                )));
+            }
+            if account.password.is_empty() {
+                return Err(ScraperError::Config(format!(
+                    "accounts[{}] ({}): password is empty",
+                    i, account.user_id
+                )));
+            }
+            if !seen.insert(account.user_id.as_str()) {
+                return Err(ScraperError::Config(format!(
+                    "accounts[{}]: duplicate user_id {}",
+                    i, account.user_id
+                )));
+            }
+        }
+        Ok(Self { accounts })
+    }
+
+    /// JSON（アカウントの配列）から読み込み
+    pub fn from_json(json: &str) -> Result<Self, ScraperError> {
+        let accounts = serde_json::from_str(json)
+            .map_err(|e| ScraperError::Config(format!("アカウントJSONの解析エラー: {}", e)))?;
+        Self::new(accounts)
+    }
+
+    /// TOML（`[[accounts]]` テーブルの配列）から読み込み
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self, ScraperError> {
+        let parsed: TomlAccounts = toml::from_str(toml)
+            .map_err(|e| ScraperError::Config(format!("アカウントTOMLの解析エラー: {}", e)))?;
+        Self::new(parsed.accounts)
+    }
+
+    /// ファイルから読み込み（拡張子 `.toml` は TOML、それ以外は JSON として解析）
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ScraperError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        let is_toml = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+        if !is_toml {
+            return Self::from_json(&content);
+        }
+        #[cfg(feature = "toml")]
+        {
+            Self::from_toml(&content)
+        }
+        #[cfg(not(feature = "toml"))]
+        {
+            Err(ScraperError::Unsupported(format!(
+                "TOMLのアカウントファイルには toml feature が必要です: {}",
+                path.display()
+            )))
+        }
+    }
+
+    /// 環境変数の JSON から読み込み（例: `AccountStore::from_env("ETC_ACCOUNTS")`）
+    pub fn from_env(var: &str) -> Result<Self, ScraperError> {
+        let json = std::env::var(var).map_err(|e| {
+            ScraperError::Config(format!("環境変数 {} を取得できません: {}", var, e))
+        })?;
+        Self::from_json(&json)
+    }
+
+    pub fn accounts(&self) -> &[Account] {
+        &self.accounts
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// アカウントごとの設定（ヘッドレス・タイムアウト等はベース設定を引き継ぐ）
+    pub fn configs<'a>(
+        &'a self,
+        base: &'a ScraperConfig,
+    ) -> impl Iterator<Item = ScraperConfig> + 'a {
+        self.accounts
+            .iter()
+            .map(move |account| account.to_config(base))
+    }
+
+    /// アカウントごとの `EtcScraper`（未初期化）
+    pub fn scrapers<'a>(
+        &'a self,
+        base: &'a ScraperConfig,
+    ) -> impl Iterator<Item = EtcScraper> + 'a {
+        self.configs(base).map(EtcScraper::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_store_from_json() {
+        let store = AccountStore::from_json(
+            r#"[{"user_id":"user1","password":"pass1"},
+                {"user_id":"user2","password":"pass2","download_path":"./downloads/user2"}]"#,
+        )
+        .unwrap();
+        assert_eq!(store.len(), 2);
+
+        let base = ScraperConfig::default().with_download_path("./downloads");
+        let configs: Vec<ScraperConfig> = store.configs(&base).collect();
+        assert_eq!(configs[0].user_id, "user1");
+        assert_eq!(configs[0].download_path, PathBuf::from("./downloads"));
+        assert_eq!(configs[1].password, "pass2");
+        assert_eq!(configs[1].download_path, PathBuf::from("./downloads/user2"));
+        assert!(!format!("{:?}", store).contains("pass1"));
+
+        let duplicate = AccountStore::from_json(
+            r#"[{"user_id":"user1","password":"a"},{"user_id":"user1","password":"b"}]"#,
+        );
+        assert!(matches!(duplicate, Err(ScraperError::Config(_))));
+    }
+}
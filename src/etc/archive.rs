@@ -0,0 +1,276 @@
+//! 圧縮形式（zip / gzip）でダウンロードされたCSVの展開
+//!
+//! `compressed` feature 有効時のみ `.zip` / `.gz` をダウンロード対象として扱い、
+//! 展開したCSVのパスを返す。feature 無効時はアーカイブを対象としない。
+
+use std::path::{Path, PathBuf};
+
+use crate::error::ScraperError;
+
+/// 展開対象のアーカイブかどうか
+#[cfg(feature = "compressed")]
+pub(crate) fn is_archive(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip") || ext.eq_ignore_ascii_case("gz"))
+}
+
+#[cfg(not(feature = "compressed"))]
+pub(crate) fn is_archive(_path: &Path) -> bool {
+    false
+}
+
+/// zipファイルの先頭バイト（ローカルファイルヘッダー）
+#[cfg(feature = "compressed")]
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+/// gzipファイルの先頭バイト
+#[cfg(feature = "compressed")]
+const GZIP_MAGIC: &[u8] = b"\x1f\x8b";
+
+/// アーカイブであればCSVを展開してそのパスを返す（アーカイブでなければそのまま返す）
+///
+/// 形式は拡張子ではなく先頭バイトで判定する（拡張子が `.zip` のエラーページ等を展開しない）。
+/// 展開後のサイズが `max_size` を超える場合はエラーにする（zip bomb 対策）。
+/// 展開に成功したアーカイブは削除する。
+#[cfg(feature = "compressed")]
+pub(crate) fn extract_csv_if_archive(
+    path: PathBuf,
+    max_size: Option<u64>,
+) -> Result<PathBuf, ScraperError> {
+    use std::io::Read;
+
+    let mut head = Vec::with_capacity(ZIP_MAGIC.len());
+    std::fs::File::open(&path)?
+        .take(ZIP_MAGIC.len() as u64)
+        .read_to_end(&mut head)?;
+
+    let extracted = if head.starts_with(ZIP_MAGIC) {
+        extract_zip(&path, max_size)?
+    } else if head.starts_with(GZIP_MAGIC) {
+        extract_gz(&path, max_size)?
+    } else {
+        return Ok(path);
+    };
+
+    tracing::info!(archive = ?path, path = ?extracted, "圧縮ファイルを展開");
+    if extracted != path {
+        if let Err(e) = std::fs::remove_file(&path) {
+            tracing::warn!(archive = ?path, error = %e, "展開済みの圧縮ファイルを削除できません");
+        }
+    }
+    Ok(extracted)
+}
+
+#[cfg(not(feature = "compressed"))]
+pub(crate) fn extract_csv_if_archive(
+    path: PathBuf,
+    _max_size: Option<u64>,
+) -> Result<PathBuf, ScraperError> {
+    Ok(path)
+}
+
+/// 展開した内容を一時ファイルに書き込み、完了後に `out_path` へリネーム
+///
+/// `max_size` を超えた時点で書き込みを中止し、一時ファイルを削除してエラーにする。
+#[cfg(feature = "compressed")]
+fn write_extracted(
+    reader: &mut impl std::io::Read,
+    out_path: &Path,
+    max_size: Option<u64>,
+) -> Result<(), ScraperError> {
+    use std::io::Read;
+
+    let mut tmp_name = out_path.as_os_str().to_owned();
+    tmp_name.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_name);
+
+    let result = (|| {
+        let mut out = std::fs::File::create(&tmp_path)?;
+        let limit = max_size.map_or(u64::MAX, |max| max.saturating_add(1));
+        let written = std::io::copy(&mut reader.by_ref().take(limit), &mut out)?;
+        if let Some(max) = max_size.filter(|&max| written > max) {
+            return Err(ScraperError::Download(format!(
+                "extracted file exceeds max size: {:?} (> {} bytes)",
+                out_path, max
+            )));
+        }
+        out.sync_all()?;
+        std::fs::rename(&tmp_path, out_path)?;
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// zip内のCSVを展開（CSVが複数ある場合は先頭を使用）
+#[cfg(feature = "compressed")]
+fn extract_zip(path: &Path, max_size: Option<u64>) -> Result<PathBuf, ScraperError> {
+    let file = std::fs::File::open(path)?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| ScraperError::Download(format!("zipファイルを開けません: {}", e)))?;
+
+    let csv_names: Vec<String> = archive
+        .file_names()
+        .filter(|name| {
+            Path::new(name)
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+        })
+        .map(String::from)
+        .collect();
+
+    let name = csv_names.first().ok_or_else(|| {
+        ScraperError::Download(format!(
+            "zipファイル内にCSVがありません: {}",
+            path.display()
+        ))
+    })?;
+    if csv_names.len() > 1 {
+        tracing::warn!(candidates = ?csv_names, "zip内に複数のCSVがあります。先頭のファイルを使用します");
+    }
+
+    // zip内のディレクトリ構成は使わず、ファイル名のみで展開（パストラバーサル対策）
+    let file_name = Path::new(name)
+        .file_name()
+        .ok_or_else(|| ScraperError::Download(format!("不正なファイル名: {}", name)))?;
+    let out_path = path.with_file_name(file_name);
+
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|e| ScraperError::Download(format!("zipの展開に失敗しました: {}", e)))?;
+    write_extracted(&mut entry, &out_path, max_size)?;
+
+    Ok(out_path)
+}
+
+/// gzipを展開（`xxx.csv.gz` → `xxx.csv`、拡張子がない場合は `xxx.csv`）
+#[cfg(feature = "compressed")]
+fn extract_gz(path: &Path, max_size: Option<u64>) -> Result<PathBuf, ScraperError> {
+    let mut out_path = if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"))
+    {
+        path.with_extension("")
+    } else {
+        path.to_path_buf()
+    };
+    if !out_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+    {
+        out_path.set_extension("csv");
+    }
+
+    let mut decoder = flate2::read::GzDecoder::new(std::fs::File::open(path)?);
+    write_extracted(&mut decoder, &out_path, max_size)?;
+
+    Ok(out_path)
+}
+
+#[cfg(all(test, feature = "compressed"))]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("etc_archive_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_extract_gz() {
+        let dir = temp_dir("gz");
+        let gz_path = dir.join("meisai.csv.gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&gz_path).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder.write_all(b"a,b,c").unwrap();
+        encoder.finish().unwrap();
+
+        let extracted = extract_csv_if_archive(gz_path.clone(), None).unwrap();
+        let content = std::fs::read(&extracted).unwrap();
+        let archive_removed = !gz_path.exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(extracted, dir.join("meisai.csv"));
+        assert_eq!(content, b"a,b,c");
+        assert!(archive_removed);
+    }
+
+    #[test]
+    fn test_extract_zip() {
+        let dir = temp_dir("zip");
+        let zip_path = dir.join("meisai.zip");
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("readme.txt", options).unwrap();
+        writer.write_all(b"readme").unwrap();
+        writer.start_file("sub/meisai.csv", options).unwrap();
+        writer.write_all(b"a,b,c").unwrap();
+        writer.finish().unwrap();
+
+        // 拡張子がない（GUID形式の）ファイルも先頭バイトで判定して展開する
+        let guid_path = dir.join("3f2504e0-4f89-11d3-9a0c-0305e82c3301");
+        std::fs::copy(&zip_path, &guid_path).unwrap();
+        let from_guid = extract_csv_if_archive(guid_path.clone(), Some(5)).unwrap();
+        let guid_removed = !guid_path.exists();
+
+        let extracted = extract_csv_if_archive(zip_path.clone(), None).unwrap();
+        let content = std::fs::read(&extracted).unwrap();
+        let archive_removed = !zip_path.exists();
+
+        // 拡張子が .zip でも中身がzipでなければ展開しない
+        let html_path = dir.join("error.zip");
+        std::fs::write(&html_path, b"<!DOCTYPE html><html></html>").unwrap();
+        let not_zip = extract_csv_if_archive(html_path.clone(), None).unwrap();
+
+        // CSVを含まないzipはエラー
+        let empty_path = dir.join("empty.zip");
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&empty_path).unwrap());
+        writer.start_file("readme.txt", options).unwrap();
+        writer.finish().unwrap();
+        let err = extract_csv_if_archive(empty_path.clone(), None).unwrap_err();
+        let empty_kept = empty_path.exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(from_guid, dir.join("meisai.csv"));
+        assert!(guid_removed);
+        assert_eq!(extracted, dir.join("meisai.csv"));
+        assert_eq!(content, b"a,b,c");
+        assert!(archive_removed);
+        assert_eq!(not_zip, html_path);
+        assert!(matches!(err, ScraperError::Download(_)));
+        assert!(empty_kept);
+    }
+
+    #[test]
+    fn test_extract_zip_max_size() {
+        let dir = temp_dir("zip_max");
+        let zip_path = dir.join("meisai.zip");
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        writer
+            .start_file("meisai.csv", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(&[b'a'; 4096]).unwrap();
+        writer.finish().unwrap();
+
+        let err = extract_csv_if_archive(zip_path.clone(), Some(1024)).unwrap_err();
+        let leftovers: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name())
+            .collect();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(
+            matches!(err, ScraperError::Download(ref msg) if msg.contains("exceeds max size")),
+            "{:?}",
+            err
+        );
+        // 展開途中のファイルは残さず、アーカイブは削除しない
+        assert_eq!(leftovers, ["meisai.zip"]);
+    }
+}
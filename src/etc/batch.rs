@@ -0,0 +1,175 @@
+//! 複数アカウントの逐次実行
+//!
+//! 短時間に連続してログインするとサイト側でアクセス制限されるため、
+//! アカウント間に待機時間（±10% のジッター付き）を挟んで1件ずつ実行する。
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use rand::Rng;
+use tracing::{info, warn};
+
+use crate::config::ScraperConfig;
+use crate::error::ScraperError;
+use crate::traits::Scraper;
+
+use super::AccountStore;
+
+/// アカウント間の待機時間に加えるジッターの割合
+const INTER_ACCOUNT_JITTER_RATIO: f64 = 0.1;
+
+/// 待機時間に ±`INTER_ACCOUNT_JITTER_RATIO` のジッターを加える
+fn jittered(delay: Duration) -> Duration {
+    if delay.is_zero() {
+        return delay;
+    }
+    let factor = rand::thread_rng()
+        .gen_range(1.0 - INTER_ACCOUNT_JITTER_RATIO..=1.0 + INTER_ACCOUNT_JITTER_RATIO);
+    delay.mul_f64(factor)
+}
+
+/// アカウントごとに `EtcScraper` を逐次実行するバッチ
+#[derive(Debug, Clone)]
+pub struct BatchRunner {
+    /// 各アカウントの設定のベース（認証情報・ダウンロード先はアカウントで上書き）
+    pub config: ScraperConfig,
+    /// アカウント間の待機時間（実際の待機は ±10% のジッター付き、最後のアカウントの後は待機しない）
+    ///
+    /// アクセス制限（`RateLimited`）で失敗した場合は、次のアカウントまで少なくとも
+    /// [`ScraperError::retry_after`] の時間を待つ。
+    pub inter_account_delay: Duration,
+}
+
+impl BatchRunner {
+    pub fn new(config: ScraperConfig) -> Self {
+        Self {
+            config,
+            inter_account_delay: Duration::ZERO,
+        }
+    }
+
+    pub fn with_inter_account_delay(mut self, delay: Duration) -> Self {
+        self.inter_account_delay = delay;
+        self
+    }
+
+    /// 全アカウントを順に実行し、(user_id, 結果) を実行順に返す
+    ///
+    /// 失敗したアカウントがあっても残りのアカウントは実行する。
+    /// アクセス制限で失敗した場合は、制限が解除されるまで次のアカウントを待機させる。
+    pub async fn run(
+        &self,
+        accounts: &AccountStore,
+    ) -> Vec<(String, Result<PathBuf, ScraperError>)> {
+        let mut results = Vec::with_capacity(accounts.len());
+        let mut retry_after = Duration::ZERO;
+        for (i, (account, mut scraper)) in accounts
+            .accounts()
+            .iter()
+            .zip(accounts.scrapers(&self.config))
+            .enumerate()
+        {
+            if i > 0 {
+                let delay = jittered(self.inter_account_delay).max(retry_after);
+                if !delay.is_zero() {
+                    info!(delay = ?delay, "次のアカウントまで待機");
+                    tokio::time::sleep(delay).await;
+                }
+            }
+
+            let result = scraper.execute().await;
+            retry_after = Duration::ZERO;
+            if let Err(ref e) = result {
+                warn!(user_id = %account.user_id, error = %e, "アカウントの実行に失敗");
+                retry_after = e.retry_after().unwrap_or_default();
+            }
+            results.push((account.user_id.clone(), result));
+        }
+        results
+    }
+}
+
+/// 全アカウントをアカウント間に `delay` を挟んで順に実行（[`BatchRunner`] の簡易版）
+pub async fn run_batch(
+    accounts: &AccountStore,
+    config: &ScraperConfig,
+    delay: Duration,
+) -> Vec<(String, Result<PathBuf, ScraperError>)> {
+    BatchRunner::new(config.clone())
+        .with_inter_account_delay(delay)
+        .run(accounts)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_run_batch() {
+        let dir = std::env::temp_dir().join(format!("etc_batch_{}", std::process::id()));
+        let fixtures = dir.join("fixtures");
+        std::fs::create_dir_all(&fixtures).unwrap();
+        std::fs::write(fixtures.join("user1.csv"), "利用年月日（自）,車両番号\n").unwrap();
+
+        let accounts = AccountStore::from_json(
+            r#"[{"user_id":"user1","password":"pass1"},{"user_id":"user2","password":"pass2"}]"#,
+        )
+        .unwrap();
+        let config = ScraperConfig::default()
+            .with_download_path(dir.join("downloads"))
+            .with_fixture_mode(&fixtures);
+        let start = tokio::time::Instant::now();
+        let results = run_batch(&accounts, &config, Duration::from_secs(10)).await;
+        let elapsed = start.elapsed();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, "user1");
+        assert!(results[0].1.is_ok());
+        assert!(results[1].1.is_err());
+        assert!(elapsed >= Duration::from_secs(9) && elapsed <= Duration::from_secs(11));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_run_waits_for_rate_limit() {
+        let dir = std::env::temp_dir().join(format!("etc_batch_limit_{}", std::process::id()));
+        let fixtures = dir.join("fixtures");
+        std::fs::create_dir_all(&fixtures).unwrap();
+        std::fs::write(
+            fixtures.join("user1.html"),
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/etc_rate_limited.html"
+            )),
+        )
+        .unwrap();
+        std::fs::write(fixtures.join("user2.csv"), "利用年月日（自）,車両番号\n").unwrap();
+
+        let accounts = AccountStore::from_json(
+            r#"[{"user_id":"user1","password":"pass1"},{"user_id":"user2","password":"pass2"}]"#,
+        )
+        .unwrap();
+        let config = ScraperConfig::default()
+            .with_download_path(dir.join("downloads"))
+            .with_fixture_mode(&fixtures);
+        let start = tokio::time::Instant::now();
+        let results = BatchRunner::new(config)
+            .with_inter_account_delay(Duration::from_secs(10))
+            .run(&accounts)
+            .await;
+        let elapsed = start.elapsed();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // 制限ページの「３０分程度」待ってから次のアカウントを実行する
+        assert!(matches!(
+            results[0].1,
+            Err(ScraperError::RateLimited {
+                retry_after: Some(wait)
+            }) if wait == Duration::from_secs(30 * 60)
+        ));
+        assert!(results[1].1.is_ok());
+        assert!(elapsed >= Duration::from_secs(30 * 60));
+        assert!(elapsed < Duration::from_secs(30 * 60 + 11));
+    }
+}
@@ -0,0 +1,126 @@
+//! ブラウザのセッションを引き継いだ reqwest クライアント
+//!
+//! `cookies` feature 有効時のみ。ブラウザでのログイン（JavaScript・CSRF対策を含む）後の
+//! クッキーを reqwest のクッキージャーに移し、軽量なエンドポイントのポーリングに使えるようにする。
+
+use std::sync::Arc;
+
+use chromiumoxide::cdp::browser_protocol::network::Cookie;
+use reqwest::cookie::Jar;
+use reqwest::Url;
+
+use crate::error::ScraperError;
+
+/// CDP のクッキーを Set-Cookie ヘッダーの値と、それを受け取ったとみなすURLに変換
+///
+/// ドメインが `.` 始まりのクッキーは `Domain` 属性付き（サブドメインにも送信）、
+/// それ以外はそのホストのみに送信するクッキーとして扱う。
+fn to_set_cookie(cookie: &Cookie) -> Option<(Url, String)> {
+    let host = cookie.domain.trim_start_matches('.');
+    let path = if cookie.path.is_empty() {
+        "/"
+    } else {
+        cookie.path.as_str()
+    };
+    let url = Url::parse(&format!("https://{}{}", host, path)).ok()?;
+
+    let mut set_cookie = format!("{}={}; Path={}", cookie.name, cookie.value, path);
+    if cookie.domain.starts_with('.') {
+        set_cookie.push_str(&format!("; Domain={}", host));
+    }
+    if cookie.secure {
+        set_cookie.push_str("; Secure");
+    }
+    if cookie.http_only {
+        set_cookie.push_str("; HttpOnly");
+    }
+    // セッションクッキーは expires が -1
+    if !cookie.session && cookie.expires > 0.0 {
+        if let Some(expires) = chrono::DateTime::from_timestamp(cookie.expires as i64, 0) {
+            set_cookie.push_str(&format!(
+                "; Expires={}",
+                expires.format("%a, %d %b %Y %H:%M:%S GMT")
+            ));
+        }
+    }
+    Some((url, set_cookie))
+}
+
+/// クッキーを引き継いだ reqwest クライアントを作成
+///
+/// `user_agent` にはブラウザと同じ値を指定する（User-Agent でセッションを検証するサイト向け）。
+pub(crate) fn client_with_cookies(
+    cookies: &[Cookie],
+    user_agent: Option<&str>,
+) -> Result<reqwest::Client, ScraperError> {
+    let jar = Arc::new(Jar::default());
+    for cookie in cookies {
+        match to_set_cookie(cookie) {
+            Some((url, set_cookie)) => jar.add_cookie_str(&set_cookie, &url),
+            None => tracing::warn!(
+                name = %cookie.name,
+                domain = %cookie.domain,
+                "クッキーのドメインを解釈できないため引き継ぎません"
+            ),
+        }
+    }
+
+    let mut builder = reqwest::Client::builder().cookie_provider(jar);
+    if let Some(user_agent) = user_agent {
+        builder = builder.user_agent(user_agent);
+    }
+    builder
+        .build()
+        .map_err(|e| ScraperError::Session(format!("HTTPクライアント作成エラー: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::cookie::CookieStore;
+
+    fn cookie(name: &str, domain: &str, path: &str) -> Cookie {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "value": "v",
+            "domain": domain,
+            "path": path,
+            "expires": -1.0,
+            "size": 2,
+            "httpOnly": true,
+            "secure": true,
+            "session": true,
+            "priority": "Medium",
+            "sourceScheme": "Secure",
+            "sourcePort": 443
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cookie_domain_and_path() {
+        let jar = Jar::default();
+        for c in [
+            cookie("HOST", "www.etc-meisai.jp", "/"),
+            cookie("DOMAIN", ".etc-meisai.jp", "/"),
+            cookie("SEARCH", "www.etc-meisai.jp", "/etc/R"),
+        ] {
+            let (url, set_cookie) = to_set_cookie(&c).unwrap();
+            jar.add_cookie_str(&set_cookie, &url);
+        }
+
+        let header = |url: &str| {
+            jar.cookies(&Url::parse(url).unwrap())
+                .map(|v| v.to_str().unwrap().to_string())
+                .unwrap_or_default()
+        };
+        let top = header("https://www.etc-meisai.jp/");
+        assert!(top.contains("HOST=v") && top.contains("DOMAIN=v") && !top.contains("SEARCH"));
+        assert!(header("https://www.etc-meisai.jp/etc/R/search").contains("SEARCH=v"));
+
+        let sub = header("https://api.etc-meisai.jp/");
+        assert!(sub.contains("DOMAIN=v") && !sub.contains("HOST"));
+        // Secure クッキーは http には送らない
+        assert!(header("http://www.etc-meisai.jp/").is_empty());
+    }
+}
@@ -1,3 +1,31 @@
+mod accounts;
+mod archive;
+mod batch;
+#[cfg(feature = "cookies")]
+mod http_client;
+#[cfg(feature = "parquet")]
+mod parquet_export;
+mod schema;
 mod scraper;
+mod summary;
+mod types;
+mod usage;
+mod vehicles;
 
-pub use scraper::EtcScraper;
+pub use accounts::{Account, AccountStore};
+pub use batch::{run_batch, BatchRunner};
+#[cfg(feature = "parquet")]
+pub use parquet_export::etc_csv_to_parquet;
+pub use schema::{
+    csv_to_utf8_with_bom, detect_csv_encoding, validate_csv_header, CsvEncoding,
+    EXPECTED_CSV_COLUMNS,
+};
+pub(crate) use scraper::ensure_within_max_size;
+pub use scraper::{check_browser_available, EtcScraper};
+pub use types::{
+    DownloadKind, DownloadOption, PageLink, RegisteredVehicle, ReportType, ResultSummary,
+};
+pub use usage::{
+    compare_records_hash, filter_new_records, parse_usage_csv, parse_yen_amount, records_hash,
+    EtcUsageRecord, EtcUsageSummary, RecordsChange,
+};
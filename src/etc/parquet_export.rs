@@ -0,0 +1,124 @@
+//! 利用明細の Parquet 出力
+//!
+//! `parquet` feature 有効時のみ。分析基盤で型変換をせずに取り込めるよう、
+//! 利用日は date32、料金・割引額・マイレージは int64、IC・車両番号・カード番号は文字列の列として書き出す。
+
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, Date32Array, Int64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use chrono::NaiveDate;
+use parquet::arrow::ArrowWriter;
+
+use crate::error::ScraperError;
+
+use super::usage::EtcUsageRecord;
+
+/// Parquet のスキーマ（列名は `EtcUsageRecord` のフィールド名）
+fn usage_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("date", DataType::Date32, false),
+        Field::new("entry_ic", DataType::Utf8, false),
+        Field::new("exit_ic", DataType::Utf8, false),
+        Field::new("amount", DataType::Int64, false),
+        Field::new("discount_amount", DataType::Int64, false),
+        Field::new("mileage_points", DataType::Int64, true),
+        Field::new("vehicle_number", DataType::Utf8, false),
+        Field::new("card_number", DataType::Utf8, false),
+    ])
+}
+
+/// 利用日を date32（1970-01-01 からの日数）に変換
+fn to_date32(date: NaiveDate) -> i32 {
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid epoch");
+    (date - epoch).num_days() as i32
+}
+
+fn parquet_error(e: impl std::fmt::Display) -> ScraperError {
+    ScraperError::FileIO(std::io::Error::other(format!("Parquet出力エラー: {}", e)))
+}
+
+/// 利用明細を Parquet ファイルに出力
+///
+/// `out` は上書きされる。レコードが空の場合もスキーマのみのファイルを作成する。
+pub fn etc_csv_to_parquet(records: &[EtcUsageRecord], out: &Path) -> Result<(), ScraperError> {
+    let schema = Arc::new(usage_schema());
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(Date32Array::from_iter_values(
+            records.iter().map(|r| to_date32(r.date)),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            records.iter().map(|r| r.entry_ic.as_str()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            records.iter().map(|r| r.exit_ic.as_str()),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+            records.iter().map(|r| r.amount),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+            records.iter().map(|r| r.discount_amount),
+        )),
+        Arc::new(Int64Array::from(
+            records.iter().map(|r| r.mileage_points).collect::<Vec<_>>(),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            records.iter().map(|r| r.vehicle_number.as_str()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            records.iter().map(|r| r.card_number.as_str()),
+        )),
+    ];
+    let batch = RecordBatch::try_new(schema.clone(), columns).map_err(parquet_error)?;
+
+    let file = std::fs::File::create(out)?;
+    let mut writer = ArrowWriter::try_new(file, schema, None).map_err(parquet_error)?;
+    writer.write(&batch).map_err(parquet_error)?;
+    writer.close().map_err(parquet_error)?;
+
+    tracing::info!(path = ?out, rows = records.len(), "Parquetを出力");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::Array;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    #[test]
+    fn test_etc_csv_to_parquet_schema() {
+        let records = vec![EtcUsageRecord {
+            date: NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(),
+            entry_ic: "東京".to_string(),
+            exit_ic: "横浜".to_string(),
+            amount: 1320,
+            discount_amount: 330,
+            mileage_points: None,
+            vehicle_number: "品川100あ1234".to_string(),
+            card_number: "1234-5678-9012-3456".to_string(),
+        }];
+        let out = std::env::temp_dir().join(format!("etc_usage_{}.parquet", std::process::id()));
+        etc_csv_to_parquet(&records, &out).unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&out).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|b| b.unwrap()).collect();
+        std::fs::remove_file(&out).ok();
+
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 1);
+        assert_eq!(batch.schema().field(0).data_type(), &DataType::Date32);
+        assert_eq!(batch.schema().field(3).data_type(), &DataType::Int64);
+        let dates = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Date32Array>()
+            .unwrap();
+        assert_eq!(dates.value(0), to_date32(records[0].date));
+    }
+}
@@ -0,0 +1,208 @@
+//! ETC 明細CSVのヘッダー検証と文字コード変換
+//!
+//! サイト側で列構成が変わると下流のパーサーが列をずらして読み込んでしまうため、
+//! ダウンロード直後にヘッダー行を期待値と照合して早期にエラーにする。
+//! また、Excelで文字化けしないよう Shift_JIS のCSVを BOM 付き UTF-8 に変換する。
+//! 文字コードはアカウントによって UTF-8 / Shift_JIS のいずれもあるため自動判定する。
+
+use encoding_rs::{Encoding, SHIFT_JIS, UTF_16BE, UTF_16LE, UTF_8};
+use serde::{Deserialize, Serialize};
+
+use crate::error::ScraperError;
+
+/// 明細CSVの文字コード（BOM、UTF-8 としての妥当性の順に判定）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CsvEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    ShiftJis,
+}
+
+impl CsvEncoding {
+    fn encoding(self) -> &'static Encoding {
+        match self {
+            CsvEncoding::Utf8 => UTF_8,
+            CsvEncoding::Utf16Le => UTF_16LE,
+            CsvEncoding::Utf16Be => UTF_16BE,
+            CsvEncoding::ShiftJis => SHIFT_JIS,
+        }
+    }
+}
+
+/// 明細CSVの文字コードを判定
+///
+/// BOM があればそれに従い、なければ UTF-8 として厳密に検証して、不正なら Shift_JIS とみなす。
+pub fn detect_csv_encoding(bytes: &[u8]) -> CsvEncoding {
+    detect(bytes).0
+}
+
+/// 文字コードと BOM の長さ
+fn detect(bytes: &[u8]) -> (CsvEncoding, usize) {
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        let detected = if encoding == UTF_16LE {
+            CsvEncoding::Utf16Le
+        } else if encoding == UTF_16BE {
+            CsvEncoding::Utf16Be
+        } else {
+            CsvEncoding::Utf8
+        };
+        return (detected, bom_len);
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(_) => (CsvEncoding::Utf8, 0),
+        Err(_) => (CsvEncoding::ShiftJis, 0),
+    }
+}
+
+/// 明細CSVの期待するヘッダー列（順序も含めて一致を要求）
+pub const EXPECTED_CSV_COLUMNS: [&str; 13] = [
+    "利用年月日（自）",
+    "時分（自）",
+    "利用年月日（至）",
+    "時分（至）",
+    "利用ＩＣ（自）",
+    "利用ＩＣ（至）",
+    "割引前料金",
+    "ＥＴＣ割引額",
+    "通行料金",
+    "車種",
+    "車両番号",
+    "ＥＴＣカード番号",
+    "備考",
+];
+
+/// 明細CSVのヘッダー行が期待する列構成と一致するか検証
+///
+/// 文字コードは [`detect_csv_encoding`] で判定する。
+/// 一致しない場合は期待値と実際の列を列挙した `ScraperError::CsvSchema` を返す。
+pub fn validate_csv_header(bytes: &[u8]) -> Result<(), ScraperError> {
+    let text = decode(bytes);
+    let header = text.lines().next().unwrap_or_default();
+    let actual: Vec<String> = split_fields(header)
+        .into_iter()
+        .filter(|column| !column.is_empty())
+        .collect();
+
+    if actual.iter().map(String::as_str).eq(EXPECTED_CSV_COLUMNS) {
+        return Ok(());
+    }
+
+    Err(ScraperError::CsvSchema(format!(
+        "ヘッダー行が想定と異なります（expected: [{}], actual: [{}]）",
+        EXPECTED_CSV_COLUMNS.join(", "),
+        actual.join(", ")
+    )))
+}
+
+/// CSVを BOM 付き UTF-8 に変換（Excel で日本語を正しく表示させる用）
+///
+/// UTF-8 以外の場合はデコードし、既に UTF-8 の場合は BOM の有無に関わらず BOM を1つだけ付与する。
+pub fn csv_to_utf8_with_bom(bytes: &[u8]) -> Vec<u8> {
+    const BOM: &[u8] = b"\xEF\xBB\xBF";
+    let text = decode(bytes);
+    let mut out = Vec::with_capacity(BOM.len() + text.len());
+    out.extend_from_slice(BOM);
+    out.extend_from_slice(text.as_bytes());
+    out
+}
+
+/// CSVの1行を列に分割（前後の空白と引用符を除去）
+///
+/// 引用符で囲まれた列内のカンマ（`"1,320"` 等）は区切りとして扱わない。
+pub(super) fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+        .into_iter()
+        .map(|field| field.trim().to_string())
+        .collect()
+}
+
+/// 文字コードを判定してデコード（BOM は除去する）
+pub(super) fn decode(bytes: &[u8]) -> std::borrow::Cow<'_, str> {
+    let (encoding, bom_len) = detect(bytes);
+    encoding
+        .encoding()
+        .decode_without_bom_handling(&bytes[bom_len..])
+        .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expected_header() -> String {
+        EXPECTED_CSV_COLUMNS.join(",")
+    }
+
+    #[test]
+    fn test_valid_header() {
+        let csv = format!(
+            "{}\n2024/01/05,08:00,2024/01/05,09:10,東京,横浜,1000,100,900,普通車,品川500あ1234,1234********5678,\n",
+            expected_header()
+        );
+        assert!(validate_csv_header(csv.as_bytes()).is_ok());
+
+        let with_bom = [b"\xEF\xBB\xBF".as_slice(), csv.as_bytes()].concat();
+        assert!(validate_csv_header(&with_bom).is_ok());
+
+        let (sjis, _, _) = encoding_rs::SHIFT_JIS.encode(&csv);
+        assert!(validate_csv_header(&sjis).is_ok());
+    }
+
+    #[test]
+    fn test_csv_to_utf8_with_bom() {
+        let csv = "利用年月日（自）,車両番号\n2024/01/05,品川500あ1234\n";
+        let (sjis, _, _) = encoding_rs::SHIFT_JIS.encode(csv);
+
+        let converted = csv_to_utf8_with_bom(&sjis);
+        assert!(converted.starts_with(b"\xEF\xBB\xBF"));
+        assert_eq!(&converted[3..], csv.as_bytes());
+
+        // 変換済みのものを再変換してもBOMは重複しない
+        assert_eq!(csv_to_utf8_with_bom(&converted), converted);
+    }
+
+    #[test]
+    fn test_detect_csv_encoding() {
+        let csv = "利用年月日（自）,車両番号\n";
+        assert_eq!(detect_csv_encoding(csv.as_bytes()), CsvEncoding::Utf8);
+        assert_eq!(
+            detect_csv_encoding(&[b"\xEF\xBB\xBF".as_slice(), csv.as_bytes()].concat()),
+            CsvEncoding::Utf8
+        );
+
+        let (sjis, _, _) = SHIFT_JIS.encode(csv);
+        assert_eq!(detect_csv_encoding(&sjis), CsvEncoding::ShiftJis);
+        assert_eq!(decode(&sjis), csv);
+
+        let utf16: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(csv.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        assert_eq!(detect_csv_encoding(&utf16), CsvEncoding::Utf16Le);
+        assert_eq!(decode(&utf16), csv);
+    }
+
+    #[test]
+    fn test_unexpected_columns() {
+        let csv = expected_header().replace("車種,", "");
+        let err = validate_csv_header(csv.as_bytes()).unwrap_err();
+        assert!(matches!(err, ScraperError::CsvSchema(_)));
+        let message = err.to_string();
+        assert!(message.contains("expected: [利用年月日（自）"));
+        assert!(message.contains("actual: [利用年月日（自）"));
+
+        assert!(validate_csv_header(b"").is_err());
+    }
+}
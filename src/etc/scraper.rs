@@ -1,22 +1,55 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
 use chromiumoxide::browser::{Browser, BrowserConfig};
-use chromiumoxide::cdp::browser_protocol::browser::SetDownloadBehaviorBehavior;
+use chromiumoxide::cdp::browser_protocol::network::CookieParam;
 use chromiumoxide::cdp::browser_protocol::page::{EventJavascriptDialogOpening, HandleJavaScriptDialogParams};
 use chromiumoxide::Page;
+use chrono::{Datelike, NaiveDate};
 use futures::StreamExt;
 use tracing::{debug, info, warn};
 
-use crate::config::ScraperConfig;
+use crate::browser::{wait_for_selector, ConnectionMonitor};
+use crate::config::{HeadlessMode, LoginStrategy, ScraperConfig};
 use crate::error::ScraperError;
+use crate::har::HarRecorder;
+use crate::telemetry;
 use crate::traits::Scraper;
 
+use super::archive;
+use super::schema;
+use super::summary;
+use super::types::{
+    DownloadKind, DownloadOption, PageLink, RegisteredVehicle, ReportType, ResultSummary,
+};
+use super::vehicles;
+
 const ETC_MEISAI_URL: &str = "https://www.etc-meisai.jp/";
 const LOGIN_FUNC_CODE: &str = "funccode=1013000000";
 const DOWNLOAD_WAIT_SECS: u64 = 120;
+/// 検索結果ページの明細表
+const RESULT_TABLE_SELECTOR: &str = "table.meisai";
+/// 明細表の行選択チェックボックス（見出し行の「全選択」は含まない）
+const RESULT_ROW_CHECKBOX_SELECTOR: &str = "td input[type='checkbox']";
+/// 利用証明書（PDF）発行ボタンの候補（明細表と同じフォーム内のボタンのみ）
+const RECEIPT_BUTTON_SELECTOR: &str = "input[type='button'], input[type='submit'], button";
+/// 利用証明書（PDF）発行ボタンの文言
+const RECEIPT_BUTTON_TEXT: &str = "利用証明書";
+/// カード情報（登録車両一覧）ページへのメニューのリンクテキスト（先に一致したものを使用）
+const CARD_LIST_LINK_TEXTS: [&str; 3] = ["カード情報", "登録カード", "車両情報"];
+/// カード一覧表の判定に使うヘッダー文言
+const CARD_TABLE_HEADER_TEXT: &str = "カード番号";
+/// 検索結果ページの集計欄（見つからない場合はページ全体のテキストから読み取る）
+const RESULT_SUMMARY_SELECTORS: [&str; 2] = ["table.summary", "#contents"];
+/// 検索条件ページの利用年月日「期間指定」ラジオボタン（`value='0'` は「全て」）
+const SEARCH_PERIOD_MODE_SELECTOR: &str = "input[name='sokoKbn'][value='1']";
+/// 検索条件ページの期間指定欄（開始年・月・日、終了年・月・日）
+const SEARCH_PERIOD_FIELDS: [&str; 6] = ["fromYYYY", "fromMM", "fromDD", "toYYYY", "toMM", "toDD"];
+/// 二段階認証のOTP入力欄
+const OTP_INPUT_SELECTOR: &str =
+    "input[autocomplete='one-time-code'], input[name*='otp' i], input[name*='onetime' i]";
 
 /// アカウント種別
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -26,11 +59,366 @@ enum AccountType {
     Unknown,
 }
 
+/// 起動ごとの専用プロファイルディレクトリのパス（リトライ・並行起動時の競合回避）
+fn new_user_data_dir() -> PathBuf {
+    let unique_id = format!(
+        "{}-{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+    );
+    std::env::temp_dir().join(format!("etc-scraper-{}", unique_id))
+}
+
+/// 起動時に作成したプロファイルディレクトリを削除
+fn remove_user_data_dir(dir: &Path) {
+    match std::fs::remove_dir_all(dir) {
+        Ok(()) => debug!(path = %dir.display(), "プロファイルディレクトリを削除"),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => {
+            warn!(path = %dir.display(), error = %e, "プロファイルディレクトリを削除できません")
+        }
+    }
+}
+
+/// ScraperConfig からブラウザ設定を構築
+fn build_browser_config(
+    config: &ScraperConfig,
+    download_dir: Option<&str>,
+    user_data_dir: &Path,
+) -> Result<BrowserConfig, ScraperError> {
+    let args = config.browser_args(download_dir);
+    debug!(args = ?args, "ブラウザ起動引数");
+
+    let mut builder = BrowserConfig::builder()
+        .user_data_dir(user_data_dir)
+        .args(args);
+
+    // Chrome実行ファイルのパスを設定
+    if let Some(ref chrome_path) = config.chrome_path {
+        info!("Chrome実行ファイル: {:?}", chrome_path);
+        builder = builder.chrome_executable(chrome_path);
+    }
+
+    builder = match config.effective_headless_mode() {
+        HeadlessMode::New => builder.new_headless_mode(),
+        HeadlessMode::Old => builder.headless_mode(chromiumoxide::browser::HeadlessMode::True),
+        // headlessモードを無効化
+        HeadlessMode::Off => builder.with_head(),
+    };
+
+    let browser_config = builder
+        .build()
+        .map_err(|e| ScraperError::BrowserInit(format!("ブラウザ設定エラー: {}", e)))?;
+    debug!(config = ?browser_config, "ブラウザ設定");
+    Ok(browser_config)
+}
+
+/// メンテナンス告知ページかどうか（URLのパスまたは本文の現在メンテナンス中を示す文言で判定）
+///
+/// 通常のログインページにも事前告知（「システムメンテナンスのお知らせ」等）が載るため、
+/// ログインフォームがあるページはメンテナンス中とみなさない。
+fn is_maintenance_page(url: &str, text: &str, has_login_form: bool) -> bool {
+    const MARKERS: [&str; 2] = ["ただいまメンテナンス中", "現在メンテナンス中"];
+    if has_login_form {
+        return false;
+    }
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    path.to_ascii_lowercase().contains("/maintenance")
+        || MARKERS.iter().any(|marker| text.contains(marker))
+}
+
+/// パスワード有効期限切れ（変更要求）ページかどうか
+fn is_password_expired_page(text: &str) -> bool {
+    const MARKERS: [&str; 3] = [
+        "パスワードの有効期限が切れています",
+        "パスワード有効期限切れ",
+        "パスワードの有効期限が過ぎています",
+    ];
+    MARKERS.iter().any(|marker| text.contains(marker))
+}
+
+/// ログイン試行回数超過などのアクセス制限ページなら、記載された待ち時間を返す
+///
+/// 制限ページでなければ `None`、待ち時間の記載がなければ `Some(None)`。
+/// 「しばらく時間をおいて」は一般的なシステムエラーページにも載るため、
+/// 制限を示す文言がある場合のみ待ち時間の記載として扱う。
+fn rate_limit_wait(text: &str) -> Option<Option<Duration>> {
+    const MARKERS: [&str; 2] = ["ご利用を制限しています", "試行回数が上限"];
+    const WAIT_MARKER: &str = "しばらく時間をおいて";
+    if !MARKERS.iter().any(|marker| text.contains(marker)) {
+        return None;
+    }
+    Some(
+        text.lines()
+            .filter(|line| {
+                line.contains(WAIT_MARKER) || MARKERS.iter().any(|marker| line.contains(marker))
+            })
+            .find_map(parse_wait_duration),
+    )
+}
+
+/// 「30分」「1時間」「１時間３０分」等の待ち時間の記載をパース
+fn parse_wait_duration(text: &str) -> Option<Duration> {
+    // 「時」単体（時刻）を待ち時間と誤認しないよう「時間」のみを単位とする
+    const UNITS: [(&str, u64); 3] = [("時間", 3600), ("分", 60), ("秒", 1)];
+    let mut total = 0;
+    let mut digits = String::new();
+    for (i, c) in text.char_indices() {
+        match c {
+            '0'..='9' => digits.push(c),
+            '０'..='９' => digits.extend(char::from_digit(c as u32 - '０' as u32, 10)),
+            _ => {
+                digits.clear();
+                continue;
+            }
+        }
+        let rest = &text[i + c.len_utf8()..];
+        if let Some((_, secs)) = UNITS.iter().find(|(unit, _)| rest.starts_with(unit)) {
+            total += digits.parse::<u64>().unwrap_or(0) * secs;
+        }
+    }
+    (total > 0).then(|| Duration::from_secs(total))
+}
+
+/// 検索条件ページで明細の種類（利用明細・請求明細）のラジオボタンを選択するJavaScript
+///
+/// ラベルの文言が `ReportType::label` を含むラジオボタンを選ぶ。
+/// 戻り値は選択できたかどうか。
+fn report_type_script(report_type: ReportType) -> String {
+    format!(
+        r#"
+        (function() {{
+            var text = {};
+            var labels = document.querySelectorAll('form label');
+            for (var i = 0; i < labels.length; i++) {{
+                var radio = labels[i].querySelector("input[type='radio']");
+                if (radio && labels[i].textContent.indexOf(text) >= 0) {{
+                    radio.click();
+                    return radio.checked;
+                }}
+            }}
+            return false;
+        }})()
+        "#,
+        serde_json::Value::String(report_type.label().to_string())
+    )
+}
+
+/// 利用年月日を「期間指定」に切り替え、期間欄を設定するJavaScript
+///
+/// セレクトボックスは数値として一致する選択肢を選ぶ（`"04"` と `4` は同じ）。
+/// 戻り値は見つからなかった欄の名前（すべて設定できた場合は空文字列）。
+fn period_search_script(from: NaiveDate, to: NaiveDate) -> String {
+    let values = [
+        from.year() as u32,
+        from.month(),
+        from.day(),
+        to.year() as u32,
+        to.month(),
+        to.day(),
+    ];
+    let fields: serde_json::Value = SEARCH_PERIOD_FIELDS
+        .iter()
+        .zip(values)
+        .map(|(name, value)| (name.to_string(), serde_json::Value::from(value)))
+        .collect::<serde_json::Map<_, _>>()
+        .into();
+    format!(
+        r#"
+        (function() {{
+            var mode = document.querySelector("{mode}");
+            if (!mode) {{
+                return 'sokoKbn';
+            }}
+            mode.click();
+            var fields = {fields};
+            for (var name in fields) {{
+                var el = document.querySelector('[name="' + name + '"]');
+                if (!el) {{
+                    return name;
+                }}
+                var value = fields[name];
+                if (el.tagName === 'SELECT') {{
+                    for (var i = 0; i < el.options.length; i++) {{
+                        if (parseInt(el.options[i].value, 10) === value) {{
+                            el.selectedIndex = i;
+                            break;
+                        }}
+                    }}
+                }} else {{
+                    el.value = String(value);
+                }}
+                el.dispatchEvent(new Event('change', {{ bubbles: true }}));
+            }}
+            return '';
+        }})()
+        "#,
+        mode = SEARCH_PERIOD_MODE_SELECTOR,
+        fields = fields
+    )
+}
+
+/// 拡張子がいずれかに一致するか（大文字小文字は区別しない）
+fn has_extension(path: &Path, extensions: &[impl AsRef<str>]) -> bool {
+    path.extension().is_some_and(|ext| {
+        extensions
+            .iter()
+            .any(|accepted| ext.eq_ignore_ascii_case(accepted.as_ref()))
+    })
+}
+
+/// ダウンロードファイルのサイズが上限以内か確認（上限超過時は読み込まずにエラー）
+pub(crate) fn ensure_within_max_size(
+    path: &Path,
+    max_size: Option<u64>,
+) -> Result<(), ScraperError> {
+    let Some(max_size) = max_size else {
+        return Ok(());
+    };
+    let size = std::fs::metadata(path)?.len();
+    if size > max_size {
+        return Err(ScraperError::Download(format!(
+            "file exceeds max size: {:?} ({} bytes > {} bytes)",
+            path, size, max_size
+        )));
+    }
+    Ok(())
+}
+
+/// CSVの代わりにエラーページのHTMLが保存されていないか確認
+fn ensure_not_html(path: &Path) -> Result<(), ScraperError> {
+    use std::io::Read;
+
+    let mut head = Vec::with_capacity(512);
+    std::fs::File::open(path)?
+        .take(512)
+        .read_to_end(&mut head)?;
+    let head = head.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(&head);
+    let start = head
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(head.len());
+    let head = head[start..].to_ascii_lowercase();
+
+    if head.starts_with(b"<!doctype html") || head.starts_with(b"<html") {
+        return Err(ScraperError::Download(format!(
+            "ダウンロードファイルがCSVではなくHTMLです: {:?}",
+            path
+        )));
+    }
+    Ok(())
+}
+
+/// リネーム後のCSVが存在し、空でなくテキストであることを確認
+///
+/// UTF-16 以外で NUL バイトを含む場合はバイナリとみなす。
+fn ensure_valid_csv(path: &Path) -> Result<(), ScraperError> {
+    use std::io::Read;
+
+    let invalid = |reason: &str| {
+        ScraperError::Download(format!("empty or invalid CSV（{}）: {:?}", reason, path))
+    };
+    if !path.is_file() {
+        return Err(invalid("ファイルがありません"));
+    }
+    let mut head = Vec::with_capacity(512);
+    std::fs::File::open(path)?
+        .take(512)
+        .read_to_end(&mut head)?;
+    if head.iter().all(|b| b.is_ascii_whitespace()) {
+        return Err(invalid("空のファイル"));
+    }
+    let utf16 = matches!(
+        schema::detect_csv_encoding(&head),
+        schema::CsvEncoding::Utf16Le | schema::CsvEncoding::Utf16Be
+    );
+    if !utf16 && head.contains(&0) {
+        return Err(invalid("テキストではありません"));
+    }
+    Ok(())
+}
+
+/// 新規ファイル候補から最新のものを選択
+///
+/// ダウンロード前から残っていた古いファイルを誤って返さないよう、
+/// 候補が複数ある場合は警告を出したうえで更新日時が最新のものを選ぶ
+/// （同時刻の場合はパス名順で決定的に選ぶ）。
+fn select_newest(mut candidates: Vec<(PathBuf, std::time::SystemTime)>) -> Option<PathBuf> {
+    if candidates.len() > 1 {
+        let paths: Vec<_> = candidates.iter().map(|(path, _)| path).collect();
+        warn!(candidates = ?paths, "新規ファイルが複数検出されました。最新のファイルを選択します");
+    }
+    candidates.sort_by(|(a_path, a_time), (b_path, b_time)| {
+        a_time.cmp(b_time).then_with(|| a_path.cmp(b_path))
+    });
+    candidates.pop().map(|(path, _)| path)
+}
+
+/// ブラウザが起動可能か確認（起動前チェック用）
+///
+/// ブラウザを起動して `navigator.userAgent` を取得し、終了する。
+/// 成功時はUser-Agent文字列を返す。
+pub async fn check_browser_available(config: &ScraperConfig) -> Result<String, ScraperError> {
+    let user_data_dir = new_user_data_dir();
+    let browser_config = build_browser_config(config, None, &user_data_dir)?;
+    let (mut browser, mut handler) = match Browser::launch(browser_config).await {
+        Ok(launched) => launched,
+        Err(e) => {
+            remove_user_data_dir(&user_data_dir);
+            return Err(ScraperError::BrowserInit(format!(
+                "ブラウザを起動できません: {}",
+                e
+            )));
+        }
+    };
+
+    let handler_task = crate::browser::spawn_on(config.runtime.as_ref(), async move {
+        while let Some(event) = handler.next().await {
+            debug!("Browser event: {:?}", event);
+        }
+    });
+
+    let result = async {
+        let page = browser
+            .new_page("about:blank")
+            .await
+            .map_err(|e| ScraperError::BrowserInit(format!("ページを作成できません: {}", e)))?;
+        page.evaluate("navigator.userAgent")
+            .await
+            .map_err(|e| ScraperError::JavaScript(e.to_string()))?
+            .into_value::<String>()
+            .map_err(|e| ScraperError::JavaScript(e.to_string()))
+    }
+    .await;
+
+    let exited =
+        crate::browser::close_browser(&mut browser, crate::browser::BROWSER_CLOSE_TIMEOUT).await;
+    handler_task.abort();
+    if exited {
+        remove_user_data_dir(&user_data_dir);
+    } else {
+        warn!(path = %user_data_dir.display(), "ブラウザが終了しないため、プロファイルディレクトリを残します");
+    }
+
+    if let Ok(ref user_agent) = result {
+        info!(user_agent = %user_agent, "ブラウザ起動確認完了");
+    }
+    result
+}
+
 pub struct EtcScraper {
     config: ScraperConfig,
     browser: Option<Browser>,
     page: Option<Arc<Page>>,
     account_type: AccountType,
+    connection: Option<ConnectionMonitor>,
+    har: Option<HarRecorder>,
+    last_url: Option<String>,
+    /// 起動したブラウザのプロファイルディレクトリ（終了後に削除する）
+    user_data_dir: Option<PathBuf>,
 }
 
 impl EtcScraper {
@@ -40,18 +428,101 @@ impl EtcScraper {
             browser: None,
             page: None,
             account_type: AccountType::Unknown,
+            connection: None,
+            har: None,
+            last_url: None,
+            user_data_dir: None,
         }
     }
 
+    /// ログイン直後に表示されていたページのURL（個人／法人の判定やエラー報告用）
+    ///
+    /// 保存済みセッションで再開した場合は、遷移先のURLを返す。ログイン前は `None`。
+    pub fn last_url(&self) -> Option<&str> {
+        self.last_url.as_deref()
+    }
+
     fn get_page(&self) -> Result<&Arc<Page>, ScraperError> {
+        self.check_connection()?;
         self.page
             .as_ref()
             .ok_or_else(|| ScraperError::BrowserInit("ブラウザが初期化されていません".into()))
     }
 
+    /// HARキャプチャ有効時、これまでのネットワークログを書き出す
+    fn save_har(&self) {
+        if let (Some(ref har), Some(ref path)) = (&self.har, &self.config.capture_har) {
+            match har.write(path) {
+                Ok(()) => info!(path = ?path, "HARファイルを保存"),
+                Err(e) => warn!(error = %e, "HARファイルの保存に失敗しました"),
+            }
+        }
+    }
+
+    /// CDPハンドラータスクのハンドルを取り出す（`initialize` 後の1回のみ `Some`）
+    ///
+    /// タスクはブラウザとの接続が切れると終了する。呼び出し側で終了を監視・join する用途向け。
+    pub fn take_handler_task(&mut self) -> Option<tokio::task::JoinHandle<()>> {
+        self.connection
+            .as_ref()
+            .and_then(ConnectionMonitor::take_task)
+    }
+
+    /// ブラウザとの接続が切れていればエラー
+    fn check_connection(&self) -> Result<(), ScraperError> {
+        match self.connection {
+            Some(ref connection) => connection.check(),
+            None => Ok(()),
+        }
+    }
+
+    /// ダウンロードディレクトリを作成し、書き込み可能か確認
+    fn ensure_download_dir_writable(&self) -> Result<(), ScraperError> {
+        let download_dir = &self.config.download_dir();
+        let not_writable = |source| ScraperError::DownloadPathNotWritable {
+            path: download_dir.clone(),
+            source,
+        };
+
+        std::fs::create_dir_all(download_dir).map_err(not_writable)?;
+
+        // 読み取り専用ボリューム対策: 実際にファイルを書き込んで確認
+        let probe = download_dir.join(format!(".write_test_{}", std::process::id()));
+        std::fs::write(&probe, b"").map_err(not_writable)?;
+        let _ = std::fs::remove_file(&probe);
+
+        Ok(())
+    }
+
+    /// ダウンロードディレクトリ直下のCSV・ダウンロード途中ファイルを削除
+    ///
+    /// 対象は `.csv` / `.crdownload` のみで、サブディレクトリや他の形式のファイルは削除しない。
+    fn clean_download_dir(&self) -> Result<(), ScraperError> {
+        let download_dir = self.config.download_dir();
+        let mut removed = Vec::new();
+
+        for entry in std::fs::read_dir(&download_dir)?.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            let is_artifact = path.is_file()
+                && path.extension().is_some_and(|ext| {
+                    ext.eq_ignore_ascii_case("csv") || ext.eq_ignore_ascii_case("crdownload")
+                });
+            if !is_artifact {
+                continue;
+            }
+
+            std::fs::remove_file(&path)?;
+            debug!("削除: {:?}", path);
+            removed.push(path);
+        }
+
+        info!(count = removed.len(), files = ?removed, "ダウンロードディレクトリの既存ファイルを削除");
+        Ok(())
+    }
+
     /// ダウンロードディレクトリの全ファイルを取得
     fn get_existing_files(&self) -> std::collections::HashSet<PathBuf> {
-        let download_dir = &self.config.download_path;
+        let download_dir = &self.config.download_dir();
         if !download_dir.exists() {
             return std::collections::HashSet::new();
         }
@@ -68,19 +539,29 @@ impl EtcScraper {
     }
 
     /// ダウンロード完了を待機（既存ファイルを除外）
+    ///
+    /// ネットワークマウント上で書き込み途中のファイルを返さないよう、
+    /// 連続する2回のポーリングでサイズが変わらないことを完了条件とする。
     async fn wait_for_download(
         &self,
         existing_files: &std::collections::HashSet<PathBuf>,
+        kind: DownloadKind,
     ) -> Result<PathBuf, ScraperError> {
         let timeout = Duration::from_secs(DOWNLOAD_WAIT_SECS);
-        let poll_interval = Duration::from_millis(500);
-        let start = std::time::Instant::now();
-        let download_dir = &self.config.download_path;
+        let poll_interval = self.config.download_poll_interval;
+        // tokio の時計を使う（テストで tokio::time::pause() により時間を進められるように）
+        let start = tokio::time::Instant::now();
+        let download_dir = &self.config.download_dir();
+        let mut last_sizes: std::collections::HashMap<PathBuf, u64> =
+            std::collections::HashMap::new();
 
         debug!("ダウンロード待機開始... (既存ファイル数: {})", existing_files.len());
 
         loop {
             if let Ok(entries) = std::fs::read_dir(download_dir) {
+                // 今回のポーリングで確定した対象拡張子のファイル（複数あり得る）
+                let mut candidates: Vec<(PathBuf, std::time::SystemTime)> = Vec::new();
+
                 for entry in entries.filter_map(|e| e.ok()) {
                     let path = entry.path();
 
@@ -97,30 +578,58 @@ impl EtcScraper {
                         continue;
                     }
 
-                    // CSVファイルを検出
-                    if let Some(ext) = path.extension() {
-                        if ext.to_ascii_lowercase() == "csv" {
-                            info!("CSVファイル検出: {:?}", path);
-                            return Ok(path);
-                        }
+                    // 対象拡張子のファイル、または拡張子がないファイル（GUID形式）が対象
+                    let is_target = if kind.extension() == "csv" {
+                        has_extension(&path, &self.config.accepted_download_extensions)
+                            || archive::is_archive(&path)
+                    } else {
+                        has_extension(&path, &[kind.extension()])
+                    };
+                    let is_guid = path.extension().is_none();
+                    if !is_target && !is_guid {
+                        continue;
                     }
 
-                    // 拡張子がないファイル（GUID形式）で十分なサイズがあれば完了
-                    if path.extension().is_none() {
-                        if let Ok(metadata) = std::fs::metadata(&path) {
-                            if metadata.len() > 100 {
-                                // CSVにリネーム
-                                let csv_path = path.with_extension("csv");
-                                if std::fs::rename(&path, &csv_path).is_ok() {
-                                    info!("GUIDファイルをリネーム: {:?}", csv_path);
-                                    return Ok(csv_path);
-                                }
-                            }
+                    let (size, modified) = match std::fs::metadata(&path) {
+                        Ok(metadata) => (
+                            metadata.len(),
+                            metadata.modified().unwrap_or(std::time::UNIX_EPOCH),
+                        ),
+                        Err(_) => continue,
+                    };
+
+                    // 前回ポーリング時とサイズが同じになるまで待機
+                    if last_sizes.insert(path.clone(), size) != Some(size) {
+                        debug!("サイズ安定待ち: {} ({}bytes)", filename, size);
+                        continue;
+                    }
+
+                    // 対象ファイルを検出（read_dir の順序に依存しないよう、まとめてから選択）
+                    if is_target {
+                        candidates.push((path, modified));
+                        continue;
+                    }
+
+                    // GUID形式で十分なサイズがあれば完了
+                    if size > 100 {
+                        // 対象拡張子にリネーム
+                        let renamed = path.with_extension(kind.extension());
+                        if std::fs::rename(&path, &renamed).is_ok() {
+                            info!(path = ?renamed, "GUIDファイルをリネーム");
+                            return Ok(renamed);
                         }
                     }
                 }
+
+                if let Some(path) = select_newest(candidates) {
+                    info!(path = ?path, "ダウンロードファイル検出");
+                    return Ok(path);
+                }
             }
 
+            // ブラウザが落ちていればタイムアウトまで待たずに終了
+            self.check_connection()?;
+
             if start.elapsed() > timeout {
                 // タイムアウト時にディレクトリ内のファイルをデバッグ出力
                 let files: Vec<_> = std::fs::read_dir(download_dir)
@@ -139,8 +648,8 @@ impl EtcScraper {
         }
     }
 
-    /// CSVファイルをリネーム（user_id付与）
-    fn rename_csv(&self, original_path: PathBuf) -> Result<PathBuf, ScraperError> {
+    /// ダウンロードファイルをリネーム（user_id付与）
+    fn rename_download(&self, original_path: PathBuf) -> Result<PathBuf, ScraperError> {
         let filename = original_path
             .file_name()
             .ok_or_else(|| ScraperError::Download("ファイル名が取得できません".into()))?
@@ -149,26 +658,54 @@ impl EtcScraper {
         let new_filename = format!("{}_{}", self.config.user_id, filename);
         let new_path = original_path.with_file_name(new_filename);
 
-        std::fs::rename(&original_path, &new_path)?;
-        info!("CSVファイルをリネーム: {:?} -> {:?}", original_path, new_path);
+        std::fs::rename(&original_path, &new_path).map_err(|source| {
+            ScraperError::DownloadPathNotWritable {
+                path: new_path.clone(),
+                source,
+            }
+        })?;
+        info!(from = ?original_path, to = ?new_path, "ダウンロードファイルをリネーム");
 
         Ok(new_path)
     }
 }
 
+impl Drop for EtcScraper {
+    fn drop(&mut self) {
+        // ブラウザプロセスの終了を確認してからディレクトリを削除（close 済みなら何もしない）
+        self.page = None;
+        let exited = self.browser.take().is_none_or(|mut browser| {
+            crate::browser::kill_browser_blocking(
+                &mut browser,
+                crate::browser::BROWSER_KILL_TIMEOUT,
+            )
+        });
+        if let Some(dir) = self.user_data_dir.take() {
+            if exited {
+                remove_user_data_dir(&dir);
+            } else {
+                warn!(path = %dir.display(), "ブラウザが終了しないため、プロファイルディレクトリを残します");
+            }
+        }
+    }
+}
+
 #[async_trait]
 impl Scraper for EtcScraper {
     async fn initialize(&mut self) -> Result<(), ScraperError> {
         info!("ブラウザを初期化中...");
+        self.config.validate()?;
 
-        // ダウンロードディレクトリを作成
-        std::fs::create_dir_all(&self.config.download_path)?;
+        // ダウンロードディレクトリを作成（ブラウザ起動前に書き込み可否を確認）
+        self.ensure_download_dir_writable()?;
 
-        let download_path = self
-            .config
-            .download_path
-            .canonicalize()
-            .unwrap_or_else(|_| self.config.download_path.clone());
+        // 前回の残りファイルを誤って返さないよう事前に削除
+        if self.config.clean_download_dir {
+            self.clean_download_dir()?;
+        }
+
+        let download_dir = self.config.download_dir();
+        let download_path = download_dir.canonicalize().unwrap_or(download_dir);
 
         // Windowsネイティブパスに変換（MSYS2のパスはChromeで認識されない）
         #[cfg(windows)]
@@ -182,43 +719,26 @@ impl Scraper for EtcScraper {
 
         info!("ダウンロードパス: {}", download_path_str);
 
-        // ブラウザ設定
-        let mut builder = BrowserConfig::builder()
-            .window_size(1280, 800)
-            .arg(format!(
-                "--download.default_directory={}",
-                download_path_str
-            ));
-
-        // Chrome実行ファイルのパスを設定
-        if let Some(ref chrome_path) = self.config.chrome_path {
-            info!("Chrome実行ファイル: {:?}", chrome_path);
-            builder = builder.chrome_executable(chrome_path);
-            // headless-shell使用時はsandbox無効化が必要
-            builder = builder.arg("--no-sandbox");
-        }
-
-        if self.config.headless {
-            builder = builder.arg("--headless=new");
-        } else {
-            // headlessモードを無効化
-            builder = builder.with_head();
-        }
-
-        let config = builder.build().map_err(|e| {
-            ScraperError::BrowserInit(format!("ブラウザ設定エラー: {}", e))
-        })?;
-
-        let (browser, mut handler) = Browser::launch(config)
-            .await
-            .map_err(|e| ScraperError::BrowserInit(e.to_string()))?;
-
-        // ブラウザイベントハンドラをバックグラウンドで実行
-        tokio::spawn(async move {
-            while let Some(event) = handler.next().await {
-                debug!("Browser event: {:?}", event);
+        // 起動済みブラウザへの接続、または新規起動
+        let (browser, handler) = match self.config.cdp_endpoint {
+            Some(ref endpoint) => crate::browser::connect(endpoint, self.config.timeout).await?,
+            None => {
+                // 起動に失敗しても作成済みのディレクトリを削除できるよう先に記録する
+                let user_data_dir = new_user_data_dir();
+                self.user_data_dir = Some(user_data_dir.clone());
+                let config =
+                    build_browser_config(&self.config, Some(&download_path_str), &user_data_dir)?;
+                Browser::launch(config)
+                    .await
+                    .map_err(|e| ScraperError::BrowserInit(e.to_string()))?
             }
-        });
+        };
+
+        // ブラウザイベントハンドラをバックグラウンドで実行（接続断を監視）
+        self.connection = Some(ConnectionMonitor::spawn(
+            handler,
+            self.config.runtime.as_ref(),
+        ));
 
         // 新しいページを作成
         let page = browser
@@ -232,7 +752,7 @@ impl Scraper for EtcScraper {
             .map_err(|e| ScraperError::BrowserInit(format!("ダイアログリスナー設定エラー: {}", e)))?;
 
         let page_for_dialog = page.clone();
-        tokio::spawn(async move {
+        crate::browser::spawn_on(self.config.runtime.as_ref(), async move {
             while let Some(event) = dialog_events.next().await {
                 info!("ダイアログ検出: type={:?}, message={}", event.r#type, event.message);
                 let params = HandleJavaScriptDialogParams::builder()
@@ -248,17 +768,15 @@ impl Scraper for EtcScraper {
         });
 
         // ダウンロード先を設定
-        let download_params =
-            chromiumoxide::cdp::browser_protocol::browser::SetDownloadBehaviorParams::builder()
-                .behavior(SetDownloadBehaviorBehavior::AllowAndName)
-                .download_path(download_path_str)
-                .events_enabled(true)
-                .build()
-                .map_err(|e| ScraperError::BrowserInit(format!("ダウンロード設定エラー: {}", e)))?;
+        crate::browser::set_download_dir(&page, &download_path_str).await?;
 
-        page.execute(download_params)
-            .await
-            .map_err(|e| ScraperError::BrowserInit(format!("ダウンロード設定エラー: {}", e)))?;
+        // ロケールを設定（日本語UIのテキストに依存するセレクタのため）
+        crate::browser::set_locale_override(&page, &self.config.locale).await?;
+        crate::browser::set_extra_headers(&page, &self.config.extra_headers).await?;
+
+        if self.config.capture_har.is_some() {
+            self.har = Some(HarRecorder::start(&page, self.config.runtime.as_ref()).await?);
+        }
 
         self.browser = Some(browser);
         self.page = Some(Arc::new(page));
@@ -269,6 +787,26 @@ impl Scraper for EtcScraper {
 
     async fn login(&mut self) -> Result<(), ScraperError> {
         let page = self.get_page()?.clone();
+
+        // 保存済みセッションがあればログイン操作を省略して直接遷移
+        let strategy = self.config.login_strategy;
+        if strategy != LoginStrategy::Always && self.try_resume_session(&page).await {
+            self.run_on_navigate(&page).await?;
+            return Ok(());
+        }
+        if strategy == LoginStrategy::ReuseOnly {
+            return Err(ScraperError::Session(
+                "保存済みセッションが無効です（login_strategy が ReuseOnly のためログインしません）"
+                    .into(),
+            ));
+        }
+        if self.config.password.is_empty() {
+            return Err(ScraperError::Session(
+                "保存済みセッションが無効で、パスワードが指定されていないためログインできません"
+                    .into(),
+            ));
+        }
+
         info!("ログイン処理開始...");
 
         // ETCメイセイトップページにアクセス
@@ -279,21 +817,15 @@ impl Scraper for EtcScraper {
         // ページ読み込み完了を待機
         tokio::time::sleep(Duration::from_secs(3)).await;
         debug!("トップページにアクセス完了");
+        self.run_on_navigate(&page).await?;
+
+        // メンテナンス中はログインリンクが表示されないため先に判定
+        self.check_maintenance(&page).await?;
 
         // ログインリンクが表示されるまで待機してクリック
         let login_link_selector = format!("a[href*='{}']", LOGIN_FUNC_CODE);
-        for i in 0..10 {
-            let exists: bool = page
-                .evaluate(format!(r#"document.querySelector("{}") !== null"#, login_link_selector))
-                .await
-                .map(|v| v.into_value().unwrap_or(false))
-                .unwrap_or(false);
-            if exists {
-                debug!("ログインリンク検出");
-                break;
-            }
-            debug!("ログインリンク待機中... ({}/10)", i + 1);
-            tokio::time::sleep(Duration::from_millis(500)).await;
+        if wait_for_selector(&page, &login_link_selector, Duration::from_secs(5)).await? {
+            debug!("ログインリンク検出");
         }
 
         // クリックしてナビゲーションを待機
@@ -308,6 +840,8 @@ impl Scraper for EtcScraper {
         // ページ読み込みを待機（wait_for_navigationはタイミングが難しいので固定待機）
         tokio::time::sleep(Duration::from_secs(5)).await;
         debug!("ログインページに遷移完了");
+        self.check_maintenance(&page).await?;
+        self.run_on_navigate(&page).await?;
 
         // 現在のURLをデバッグ出力
         let url: String = page
@@ -318,27 +852,12 @@ impl Scraper for EtcScraper {
         debug!("現在のURL: {}", url);
 
         // 入力欄が表示されるまで待機
-        for i in 0..10 {
-            let exists: bool = page
-                .evaluate(r#"document.querySelector("input[name='risLoginId']") !== null"#)
-                .await
-                .map(|v| v.into_value().unwrap_or(false))
-                .unwrap_or(false);
-            if exists {
-                debug!("ログインフォーム検出");
-                break;
-            }
-            debug!("ログインフォーム待機中... ({}/10)", i + 1);
-            tokio::time::sleep(Duration::from_millis(500)).await;
-        }
-
-        // それでも見つからない場合、ページの内容をデバッグ出力
-        let form_exists: bool = page
-            .evaluate(r#"document.querySelector("input[name='risLoginId']") !== null"#)
-            .await
-            .map(|v| v.into_value().unwrap_or(false))
-            .unwrap_or(false);
-        if !form_exists {
+        let form_exists =
+            wait_for_selector(&page, "input[name='risLoginId']", Duration::from_secs(5)).await?;
+        if form_exists {
+            debug!("ログインフォーム検出");
+        } else {
+            // 見つからない場合、ページの内容をデバッグ出力
             let html: String = page
                 .evaluate("document.body.innerHTML.substring(0, 1500)")
                 .await
@@ -377,6 +896,16 @@ impl Scraper for EtcScraper {
 
         tokio::time::sleep(Duration::from_secs(3)).await;
 
+        // ログインの繰り返しで制限されている場合、リトライすると制限が延びるため即座に中断
+        self.check_rate_limited(&page).await?;
+
+        // 二段階認証（OTP入力欄が表示された場合のみ）
+        self.submit_otp_if_required(&page).await?;
+        self.run_on_navigate(&page).await?;
+
+        // パスワード変更を求められた場合は以降の画面に進めない
+        self.check_password_expired(&page).await?;
+
         // ログイン後のURLを確認してアカウント種別を判定
         let current_url: String = page
             .evaluate("window.location.href")
@@ -385,20 +914,7 @@ impl Scraper for EtcScraper {
             .unwrap_or_default();
         debug!("ログイン後のURL: {}", current_url);
 
-        // URL判定でアカウント種別を検出
-        // 個人: /etc_user_meisai/ を含む
-        // 法人: /etc_corp_meisai/ を含む
-        if current_url.contains("/etc_corp_meisai/") {
-            info!("法人アカウントを検出しました");
-            self.account_type = AccountType::Corporate;
-        } else if current_url.contains("/etc_user_meisai/") {
-            info!("個人アカウントを検出しました");
-            self.account_type = AccountType::Personal;
-        } else {
-            warn!("アカウント種別を判定できません: {}", current_url);
-            // デフォルトは個人として扱う
-            self.account_type = AccountType::Personal;
-        }
+        self.record_landing_url(current_url);
 
         info!("ログイン完了");
         Ok(())
@@ -406,7 +922,7 @@ impl Scraper for EtcScraper {
 
     async fn download(&mut self) -> Result<PathBuf, ScraperError> {
         let page = self.get_page()?.clone();
-        info!("CSVダウンロード処理開始... (アカウント種別: {:?})", self.account_type);
+        info!(account_type = ?self.account_type, "CSVダウンロード処理開始...");
 
         // 現在のページ上のリンクをデバッグ出力
         let links_debug: String = page
@@ -428,18 +944,110 @@ impl Scraper for EtcScraper {
         debug!("ログイン後のリンク一覧: {}", links_debug);
 
         // アカウント種別によってフロー分岐
-        match self.account_type {
-            AccountType::Corporate => self.download_corporate(&page).await,
-            AccountType::Personal | AccountType::Unknown => self.download_personal(&page).await,
+        let result = self.download_by_account_type(&page).await;
+
+        // 途中でセッションが切れてログインページに戻された場合は1回だけ再ログインしてリトライ
+        // 別の場所でのログインによる強制ログアウトの場合は、再ログインで相手側を追い出さないようエラーにする
+        let result = match result {
+            Err(e) if !matches!(e, ScraperError::NoUsageData(_)) => {
+                crate::browser::check_session_superseded(&page).await?;
+                if self.is_on_login_page(&page).await {
+                    warn!(error = %e, "セッション切れを検出しました。再ログインしてリトライします");
+                    self.login().await?;
+                    let page = self.get_page()?.clone();
+                    self.download_by_account_type(&page).await
+                } else {
+                    Err(e)
+                }
+            }
+            other => other,
+        };
+
+        let path = result?;
+        if self.validates_schema() {
+            schema::validate_csv_header(&std::fs::read(&path)?)?;
+            debug!("CSVヘッダー検証OK");
+        }
+        Ok(path)
+    }
+
+    #[tracing::instrument(skip(self), fields(scraper = "etc", user_id = %self.config.user_id))]
+    async fn execute(&mut self) -> Result<PathBuf, ScraperError> {
+        if let Some(dir) = self.config.fixture_dir.clone() {
+            return self.execute_fixture(&dir);
+        }
+
+        let start = tokio::time::Instant::now();
+        let result = async {
+            let policy = self.config.init_retry.clone();
+            self.initialize_with_retry(&policy).await?;
+            self.login().await?;
+            let path = self.download().await?;
+            self.close().await?;
+            Ok(path)
+        }
+        .await;
+
+        if result.is_err() {
+            // 失敗時の調査用に、closeされていなくても記録を書き出す
+            self.save_har();
+        }
+
+        telemetry::record_scrape("etc", result.is_ok(), start.elapsed());
+        if let Ok(ref path) = result {
+            telemetry::record_download("etc", path);
         }
+        result
+    }
+
+    async fn evaluate(&self, script: &str) -> Result<serde_json::Value, ScraperError> {
+        let page = self.get_page()?;
+        page.evaluate(script)
+            .await
+            .map_err(|e| ScraperError::JavaScript(e.to_string()))?
+            .into_value()
+            .map_err(|e| ScraperError::Json(e.to_string()))
+    }
+
+    async fn screenshot(&self, path: &Path) -> Result<(), ScraperError> {
+        let page = self.get_page()?;
+        crate::browser::save_full_page_screenshot(page, path).await?;
+        info!(path = ?path, "スクリーンショットを保存");
+        Ok(())
     }
 
     async fn close(&mut self) -> Result<(), ScraperError> {
         info!("ブラウザを終了中...");
 
+        self.save_har();
+        self.har = None;
+
+        // 接続先のブラウザは終了しないため、作成したタブだけを閉じる
+        let mut exited = true;
+        if self.config.cdp_endpoint.is_some() {
+            if let Some(page) = self.page.take() {
+                if let Err(e) = Page::clone(&page).close().await {
+                    debug!("ページを閉じられませんでした: {}", e);
+                }
+            }
+        } else if let Some(mut browser) = self.browser.take() {
+            // 起動したブラウザは明示的に終了し、子プロセスを残さない
+            exited =
+                crate::browser::close_browser(&mut browser, crate::browser::BROWSER_CLOSE_TIMEOUT)
+                    .await;
+        }
+
         // ページとブラウザの参照を解放
         self.page = None;
         self.browser = None;
+        self.connection = None;
+        if let Some(dir) = self.user_data_dir.take() {
+            if exited {
+                remove_user_data_dir(&dir);
+            } else {
+                warn!(path = %dir.display(), "ブラウザが終了しないため、プロファイルディレクトリを残します");
+            }
+        }
 
         info!("ブラウザ終了完了");
         Ok(())
@@ -447,62 +1055,360 @@ impl Scraper for EtcScraper {
 }
 
 impl EtcScraper {
-    /// 個人向けダウンロード処理
-    async fn download_personal(&self, page: &Arc<Page>) -> Result<PathBuf, ScraperError> {
-        info!("個人向けダウンロード処理を開始...");
+    /// ページ遷移後に `on_navigate` フックを実行（未設定なら何もしない）
+    async fn run_on_navigate(&self, page: &Page) -> Result<(), ScraperError> {
+        match self.config.on_navigate {
+            Some(ref hook) => hook.run(page).await,
+            None => Ok(()),
+        }
+    }
 
-        // JavaScriptで「検索条件の指定」リンクをクリック
-        let clicked: bool = page
-            .evaluate(
-                r#"
-                (function() {
-                    var links = document.querySelectorAll('a');
-                    for (var i = 0; i < links.length; i++) {
-                        if (links[i].textContent.indexOf('検索条件の指定') >= 0) {
-                            links[i].click();
-                            return true;
-                        }
-                    }
-                    return false;
-                })()
-                "#,
-            )
+    /// ダウンロードした明細CSVのヘッダーを検証するか（利用明細の明細CSVのみ対象）
+    fn validates_schema(&self) -> bool {
+        self.config.validate_schema
+            && self.config.download_kind == DownloadKind::DetailCsv
+            && self.config.report_type == ReportType::Usage
+    }
+
+    /// 検索条件ページで明細の種類を選択
+    ///
+    /// 「検索条件の指定」への遷移で選択がリセットされるため、遷移後に呼び出す。
+    /// 利用明細は選択欄がなければ既定のまま進め、請求明細は選択欄がなければエラーとする。
+    async fn select_report_type(&self, page: &Arc<Page>) -> Result<(), ScraperError> {
+        let report_type = self.config.report_type;
+        let selected: bool = page
+            .evaluate(report_type_script(report_type))
             .await
-            .map(|v| v.into_value().unwrap_or(false))
+            .map_err(|e| ScraperError::JavaScript(e.to_string()))?
+            .into_value()
             .unwrap_or(false);
-        debug!("検索条件リンククリック: {}", clicked);
 
-        tokio::time::sleep(Duration::from_secs(3)).await;
+        if !selected {
+            if report_type == ReportType::Usage {
+                debug!("明細の種類の選択欄がないため既定（利用明細）のまま検索");
+                return Ok(());
+            }
+            return Err(ScraperError::ElementNotFound(format!(
+                "明細の種類「{}」の選択欄が見つかりません",
+                report_type.label()
+            )));
+        }
+        info!(report_type = report_type.id(), "明細の種類を選択");
 
-        // 「全て」オプションを選択（JavaScriptで）
-        let _ = page
-            .evaluate(
-                r#"
-                (function() {
-                    var radio = document.querySelector("input[name='sokoKbn'][value='0']");
-                    if (radio) {
-                        radio.click();
-                        return true;
-                    }
-                    return false;
-                })()
-                "#,
-            )
-            .await;
-        debug!("「全て」オプション選択完了");
         tokio::time::sleep(Duration::from_secs(1)).await;
+        Ok(())
+    }
 
-        // 「全選択」リンクをクリック（JavaScriptで）
-        let _ = page
+    /// アカウント種別に応じたダウンロード処理
+    async fn download_by_account_type(&self, page: &Arc<Page>) -> Result<PathBuf, ScraperError> {
+        match self.account_type {
+            AccountType::Corporate => self.download_corporate(page).await,
+            AccountType::Personal | AccountType::Unknown => self.download_personal(page).await,
+        }
+    }
+
+    /// メンテナンス告知ページが表示されていれば `Maintenance` エラーを返す
+    async fn check_maintenance(&self, page: &Arc<Page>) -> Result<(), ScraperError> {
+        let (url, text, has_login_form): (String, String, bool) = page
             .evaluate(
-                r#"
-                (function() {
-                    var links = document.querySelectorAll('a');
-                    for (var i = 0; i < links.length; i++) {
-                        if (links[i].textContent.indexOf('全選択') >= 0) {
-                            links[i].click();
-                            return true;
-                        }
+                r#"[
+                    window.location.href,
+                    document.body ? document.body.innerText : '',
+                    document.querySelector("input[name='risLoginId']") !== null
+                ]"#,
+            )
+            .await
+            .map(|v| v.into_value().unwrap_or_default())
+            .unwrap_or_default();
+
+        if is_maintenance_page(&url, &text, has_login_form) {
+            warn!(url = %url, "ETC利用照会サービスはメンテナンス中です");
+            return Err(ScraperError::Maintenance(format!(
+                "ETC利用照会サービスはメンテナンス中です: {}",
+                url
+            )));
+        }
+        Ok(())
+    }
+
+    /// アクセス制限ページが表示されていれば `RateLimited` エラーを返す
+    async fn check_rate_limited(&self, page: &Arc<Page>) -> Result<(), ScraperError> {
+        let text: String = page
+            .evaluate("document.body ? document.body.innerText : ''")
+            .await
+            .map(|v| v.into_value().unwrap_or_default())
+            .unwrap_or_default();
+
+        if let Some(retry_after) = rate_limit_wait(&text) {
+            warn!(user_id = %self.config.user_id, retry_after = ?retry_after, "ログインが一時的に制限されています");
+            return Err(ScraperError::RateLimited { retry_after });
+        }
+        Ok(())
+    }
+
+    /// ログイン後にパスワード有効期限切れページが表示されていればエラー
+    async fn check_password_expired(&self, page: &Arc<Page>) -> Result<(), ScraperError> {
+        let text: String = page
+            .evaluate("document.body ? document.body.innerText : ''")
+            .await
+            .map(|v| v.into_value().unwrap_or_default())
+            .unwrap_or_default();
+
+        if is_password_expired_page(&text) {
+            warn!(user_id = %self.config.user_id, "パスワードの有効期限が切れています");
+            return Err(ScraperError::PasswordExpired(format!(
+                "ユーザー {} のパスワードを変更してください",
+                self.config.user_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// ログイン後のURLを記録し、アカウント種別を判定
+    fn record_landing_url(&mut self, url: String) {
+        self.detect_account_type(&url);
+        self.last_url = Some(url);
+    }
+
+    /// URL判定でアカウント種別を検出
+    ///
+    /// 個人: /etc_user_meisai/ を含む
+    /// 法人: /etc_corp_meisai/ を含む
+    fn detect_account_type(&mut self, current_url: &str) {
+        if current_url.contains("/etc_corp_meisai/") {
+            info!(account_type = "corporate", "法人アカウントを検出しました");
+            self.account_type = AccountType::Corporate;
+        } else if current_url.contains("/etc_user_meisai/") {
+            info!(account_type = "personal", "個人アカウントを検出しました");
+            self.account_type = AccountType::Personal;
+        } else {
+            warn!(url = %current_url, "アカウント種別を判定できません");
+            // デフォルトは個人として扱う
+            self.account_type = AccountType::Personal;
+        }
+    }
+
+    /// 保存済みセッションのクッキーを復元してログイン後ページへ直接遷移
+    ///
+    /// ログインページにリダイレクトされた場合は `false` を返し、通常のログインにフォールバックする。
+    async fn try_resume_session(&mut self, page: &Arc<Page>) -> bool {
+        let Some(session_url) = self.config.session_url.clone() else {
+            return false;
+        };
+        if self.config.session_cookies.is_empty() {
+            return false;
+        }
+
+        info!("保存済みセッションで検索画面への直接遷移を試行...");
+        for (name, value, domain, path) in &self.config.session_cookies {
+            let cookie_param = CookieParam::builder()
+                .name(name)
+                .value(value)
+                .domain(domain)
+                .path(path)
+                .build();
+            if let Ok(param) = cookie_param {
+                if let Err(e) = page.set_cookie(param).await {
+                    debug!("クッキー設定エラー: {}", e);
+                }
+            }
+        }
+
+        if let Err(e) = page.goto(session_url.as_str()).await {
+            warn!(error = %e, "セッション復元時の遷移に失敗しました。通常ログインを行います");
+            return false;
+        }
+        tokio::time::sleep(Duration::from_secs(3)).await;
+
+        if self.is_on_login_page(page).await {
+            info!("セッションが無効です。通常ログインを行います");
+            return false;
+        }
+
+        let current_url: String = page
+            .evaluate("window.location.href")
+            .await
+            .map(|v| v.into_value().unwrap_or_default())
+            .unwrap_or_default();
+        self.record_landing_url(current_url);
+
+        info!("保存済みセッションでログイン済み");
+        true
+    }
+
+    /// 現在のセッションのクッキーを取得（次回実行時の `with_session` 用）
+    pub async fn session_cookies(
+        &self,
+    ) -> Result<Vec<(String, String, String, String)>, ScraperError> {
+        let page = self.get_page()?;
+        let cookies = page
+            .get_cookies()
+            .await
+            .map_err(|e| ScraperError::Session(format!("クッキー取得エラー: {}", e)))?;
+        Ok(cookies
+            .into_iter()
+            .map(|c| (c.name, c.value, c.domain, c.path))
+            .collect())
+    }
+
+    /// 現在のセッションのクッキーを引き継いだ reqwest クライアントを作成
+    ///
+    /// ブラウザでログインした後、軽量なエンドポイントを HTTP だけでポーリングする用。
+    /// クッキーはドメイン・パス・Secure 属性を保ったままクッキージャーに登録し、
+    /// User-Agent もブラウザと揃える。以降のクッキーの更新はブラウザ側と同期しない。
+    #[cfg(feature = "cookies")]
+    pub async fn export_reqwest_client(&self) -> Result<reqwest::Client, ScraperError> {
+        let page = self.get_page()?;
+        let cookies = page
+            .get_cookies()
+            .await
+            .map_err(|e| ScraperError::Session(format!("クッキー取得エラー: {}", e)))?;
+        let user_agent: Option<String> = page
+            .evaluate("navigator.userAgent")
+            .await
+            .ok()
+            .and_then(|v| v.into_value().ok());
+
+        let client = super::http_client::client_with_cookies(&cookies, user_agent.as_deref())?;
+        info!(
+            cookie_count = cookies.len(),
+            "セッションを引き継いだHTTPクライアントを作成"
+        );
+        Ok(client)
+    }
+
+    /// ログインページにリダイレクトされているか（セッション切れ検出）
+    async fn is_on_login_page(&self, page: &Arc<Page>) -> bool {
+        page.evaluate(format!(
+            r#"window.location.href.indexOf("{}") >= 0 || document.querySelector("input[name='risLoginId']") !== null"#,
+            LOGIN_FUNC_CODE
+        ))
+        .await
+        .map(|v| v.into_value().unwrap_or(false))
+        .unwrap_or(false)
+    }
+
+    /// OTP入力欄が表示されていればコードを取得して送信
+    async fn submit_otp_if_required(&self, page: &Arc<Page>) -> Result<(), ScraperError> {
+        let otp_required: bool = page
+            .evaluate(format!(
+                r#"document.querySelector("{}") !== null"#,
+                OTP_INPUT_SELECTOR
+            ))
+            .await
+            .map(|v| v.into_value().unwrap_or(false))
+            .unwrap_or(false);
+        if !otp_required {
+            return Ok(());
+        }
+
+        info!("ワンタイムパスワード入力欄を検出しました");
+        let provider = self.config.otp_provider.as_ref().ok_or_else(|| {
+            ScraperError::Login(
+                "ワンタイムパスワードが要求されましたが otp_provider が未設定です".into(),
+            )
+        })?;
+        let code = provider.fetch().await?;
+
+        // コードを入力して送信ボタンをクリック
+        let code_literal =
+            serde_json::to_string(code.trim()).map_err(|e| ScraperError::Json(e.to_string()))?;
+        let submitted: bool = page
+            .evaluate(format!(
+                r#"
+                (function() {{
+                    var input = document.querySelector("{}");
+                    input.value = {};
+                    var buttons = document.querySelectorAll("input[type='button'], input[type='submit'], button");
+                    for (var i = 0; i < buttons.length; i++) {{
+                        var text = buttons[i].value || buttons[i].textContent;
+                        if (text.indexOf('認証') >= 0 || text.indexOf('送信') >= 0 || text.indexOf('ログイン') >= 0) {{
+                            buttons[i].click();
+                            return true;
+                        }}
+                    }}
+                    if (input.form) {{
+                        input.form.submit();
+                        return true;
+                    }}
+                    return false;
+                }})()
+                "#,
+                OTP_INPUT_SELECTOR, code_literal
+            ))
+            .await
+            .map(|v| v.into_value().unwrap_or(false))
+            .map_err(|e| ScraperError::Login(format!("ワンタイムパスワード入力: {}", e)))?;
+
+        if !submitted {
+            return Err(ScraperError::ElementNotFound(
+                "ワンタイムパスワード送信ボタンが見つかりません".into(),
+            ));
+        }
+        debug!("ワンタイムパスワード送信完了");
+
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        Ok(())
+    }
+
+    /// 個人向けダウンロード処理
+    async fn download_personal(&self, page: &Arc<Page>) -> Result<PathBuf, ScraperError> {
+        info!("個人向けダウンロード処理を開始...");
+
+        // JavaScriptで「検索条件の指定」リンクをクリック
+        let clicked: bool = page
+            .evaluate(
+                r#"
+                (function() {
+                    var links = document.querySelectorAll('a');
+                    for (var i = 0; i < links.length; i++) {
+                        if (links[i].textContent.indexOf('検索条件の指定') >= 0) {
+                            links[i].click();
+                            return true;
+                        }
+                    }
+                    return false;
+                })()
+                "#,
+            )
+            .await
+            .map(|v| v.into_value().unwrap_or(false))
+            .unwrap_or(false);
+        debug!("検索条件リンククリック: {}", clicked);
+
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        self.run_on_navigate(page).await?;
+        self.select_report_type(page).await?;
+
+        // 「全て」オプションを選択（JavaScriptで）
+        let _ = page
+            .evaluate(
+                r#"
+                (function() {
+                    var radio = document.querySelector("input[name='sokoKbn'][value='0']");
+                    if (radio) {
+                        radio.click();
+                        return true;
+                    }
+                    return false;
+                })()
+                "#,
+            )
+            .await;
+        debug!("「全て」オプション選択完了");
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        // 「全選択」リンクをクリック（JavaScriptで）
+        let _ = page
+            .evaluate(
+                r#"
+                (function() {
+                    var links = document.querySelectorAll('a');
+                    for (var i = 0; i < links.length; i++) {
+                        if (links[i].textContent.indexOf('全選択') >= 0) {
+                            links[i].click();
+                            return true;
+                        }
                     }
                     return false;
                 })()
@@ -562,6 +1468,7 @@ impl EtcScraper {
         debug!("検索ボタンクリック完了");
 
         tokio::time::sleep(Duration::from_secs(3)).await;
+        self.run_on_navigate(page).await?;
 
         // CSVダウンロード処理を実行
         self.download_csv(page).await
@@ -592,7 +1499,8 @@ impl EtcScraper {
             .map(|v| v.into_value().unwrap_or(false))
             .unwrap_or(false);
 
-        if has_csv_link {
+        // トップページの明細リストは利用明細のため、請求明細は検索条件ページから取得する
+        if has_csv_link && self.config.report_type == ReportType::Usage {
             debug!("現在のページにCSVリンクが見つかりました");
         } else {
             // 検索条件ページへ移動が必要な場合
@@ -621,6 +1529,8 @@ impl EtcScraper {
             debug!("検索リンククリック: {}", clicked);
 
             tokio::time::sleep(Duration::from_secs(3)).await;
+            self.run_on_navigate(page).await?;
+            self.select_report_type(page).await?;
 
             // 「全て」オプションを選択（JavaScriptで）
             let _ = page
@@ -750,12 +1660,450 @@ impl EtcScraper {
             debug!("検索ボタンクリック完了");
 
             tokio::time::sleep(Duration::from_secs(3)).await;
+            self.run_on_navigate(page).await?;
         }
 
         // CSVダウンロード処理を実行
         self.download_csv(page).await
     }
 
+    /// 複数期間の明細CSVを1回のログインでまとめてダウンロード
+    ///
+    /// `login` 後に呼び出す。期間ごとに検索条件ページへ戻って期間を指定・検索し、
+    /// CSVをダウンロードする。戻り値は `periods` と同じ順序の期間ごとの結果で、
+    /// 途中の期間が失敗しても残りの期間は続けて処理する（取得済みのCSVは失われない）。
+    pub async fn download_range(
+        &self,
+        periods: Vec<(NaiveDate, NaiveDate)>,
+    ) -> Result<Vec<Result<PathBuf, ScraperError>>, ScraperError> {
+        if let Some((from, to)) = periods.iter().find(|(from, to)| from > to) {
+            return Err(ScraperError::Config(format!(
+                "期間の開始日が終了日より後です: {} > {}",
+                from, to
+            )));
+        }
+
+        let page = self.get_page()?;
+        let mut results = Vec::with_capacity(periods.len());
+        for (i, (from, to)) in periods.into_iter().enumerate() {
+            info!(period = i + 1, %from, %to, "期間指定ダウンロード");
+            let result = self.download_period(page, from, to).await;
+            if let Err(ref e) = result {
+                warn!(period = i + 1, %from, %to, "期間指定ダウンロード失敗: {}", e);
+            }
+            results.push(result);
+        }
+
+        info!(
+            count = results.iter().filter(|r| r.is_ok()).count(),
+            total = results.len(),
+            "期間指定ダウンロード完了"
+        );
+        Ok(results)
+    }
+
+    /// 1期間分の検索とCSVダウンロード
+    async fn download_period(
+        &self,
+        page: &Arc<Page>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<PathBuf, ScraperError> {
+        self.check_connection()?;
+        self.search_period(page, from, to).await?;
+
+        let path = self.download_csv(page).await?;
+        if self.validates_schema() {
+            schema::validate_csv_header(&std::fs::read(&path)?)?;
+        }
+        Ok(path)
+    }
+
+    /// 検索条件ページへ移動し、期間を指定して検索
+    async fn search_period(
+        &self,
+        page: &Arc<Page>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<(), ScraperError> {
+        // 「検索条件の指定」（法人向けは「利用明細検索」）リンクをクリック
+        let clicked: bool = page
+            .evaluate(
+                r#"
+                (function() {
+                    var links = document.querySelectorAll('a');
+                    for (var i = 0; i < links.length; i++) {
+                        var text = links[i].textContent;
+                        if (text.indexOf('検索条件') >= 0 || text.indexOf('利用明細検索') >= 0) {
+                            links[i].click();
+                            return true;
+                        }
+                    }
+                    return false;
+                })()
+                "#,
+            )
+            .await
+            .map(|v| v.into_value().unwrap_or(false))
+            .unwrap_or(false);
+        debug!("検索条件リンククリック: {}", clicked);
+
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        self.run_on_navigate(page).await?;
+        self.select_report_type(page).await?;
+
+        // 「期間指定」に切り替えて期間を設定
+        let missing: String = page
+            .evaluate(period_search_script(from, to))
+            .await
+            .map_err(|e| ScraperError::JavaScript(e.to_string()))?
+            .into_value()
+            .unwrap_or_default();
+        if !missing.is_empty() {
+            return Err(ScraperError::ElementNotFound(format!(
+                "期間指定欄 ({}) が見つかりません",
+                missing
+            )));
+        }
+        debug!(%from, %to, "期間指定完了");
+
+        // 「全選択」リンクをクリック（全カードを対象にする）
+        let _ = page
+            .evaluate(
+                r#"
+                (function() {
+                    var links = document.querySelectorAll('a');
+                    for (var i = 0; i < links.length; i++) {
+                        if (links[i].textContent.indexOf('全選択') >= 0) {
+                            links[i].click();
+                            return true;
+                        }
+                    }
+                    return false;
+                })()
+                "#,
+            )
+            .await;
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        // 検索ボタンをクリック
+        let search_clicked: bool = page
+            .evaluate(
+                r#"
+                (function() {
+                    var btn = document.querySelector("input[name='focusTarget']");
+                    if (btn) {
+                        btn.click();
+                        return true;
+                    }
+                    var inputs = document.querySelectorAll("input[type='button'], input[type='submit']");
+                    for (var i = 0; i < inputs.length; i++) {
+                        if (inputs[i].value.indexOf('検索') >= 0) {
+                            inputs[i].click();
+                            return true;
+                        }
+                    }
+                    return false;
+                })()
+                "#,
+            )
+            .await
+            .map(|v| v.into_value().unwrap_or(false))
+            .unwrap_or(false);
+        if !search_clicked {
+            return Err(ScraperError::ElementNotFound(
+                "検索ボタンが見つかりません".into(),
+            ));
+        }
+        debug!("検索ボタンクリック完了");
+
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        self.run_on_navigate(page).await?;
+        Ok(())
+    }
+
+    /// 検索結果ページで利用可能なダウンロードリンク一覧を取得
+    ///
+    /// `login` と検索条件の指定が完了した後のページを対象とする。
+    pub async fn list_download_options(&self) -> Result<Vec<DownloadOption>, ScraperError> {
+        let page = self.get_page()?;
+        Self::find_download_options(page).await
+    }
+
+    /// アカウントに登録されたETCカード・車両の一覧を取得
+    ///
+    /// `login` 後に呼び出す。メニューからカード情報ページへ移動し、
+    /// 「カード番号」列を含む表を読み取る。明細のダウンロードとは別ページのため、
+    /// 続けてダウンロードする場合は `download` の検索条件の指定から再度行われる。
+    pub async fn list_vehicles(&self) -> Result<Vec<RegisteredVehicle>, ScraperError> {
+        let page = self.get_page()?;
+        info!("登録車両一覧の取得開始...");
+
+        let clicked: bool = page
+            .evaluate(format!(
+                r#"
+                (function() {{
+                    var texts = {};
+                    var links = document.querySelectorAll('a');
+                    for (var t = 0; t < texts.length; t++) {{
+                        for (var i = 0; i < links.length; i++) {{
+                            if (links[i].textContent.indexOf(texts[t]) >= 0) {{
+                                links[i].click();
+                                return true;
+                            }}
+                        }}
+                    }}
+                    return false;
+                }})()
+                "#,
+                serde_json::json!(CARD_LIST_LINK_TEXTS)
+            ))
+            .await
+            .map_err(|e| ScraperError::JavaScript(e.to_string()))?
+            .into_value()
+            .unwrap_or(false);
+        if !clicked {
+            return Err(ScraperError::ElementNotFound(format!(
+                "カード情報のメニューリンク（{}）が見つかりません",
+                CARD_LIST_LINK_TEXTS.join(" / ")
+            )));
+        }
+
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        self.check_maintenance(page).await?;
+        self.run_on_navigate(page).await?;
+
+        // 「カード番号」列を含む表の全行をセル文字列の配列として取得
+        let json: String = page
+            .evaluate(format!(
+                r#"
+                (function() {{
+                    var header = {};
+                    var tables = document.querySelectorAll('table');
+                    for (var t = 0; t < tables.length; t++) {{
+                        if (tables[t].textContent.indexOf(header) < 0) {{
+                            continue;
+                        }}
+                        var rows = [];
+                        var trs = tables[t].querySelectorAll('tr');
+                        for (var i = 0; i < trs.length; i++) {{
+                            var cells = trs[i].querySelectorAll('th, td');
+                            var row = [];
+                            for (var j = 0; j < cells.length; j++) {{
+                                row.push(cells[j].textContent.trim());
+                            }}
+                            rows.push(row);
+                        }}
+                        return JSON.stringify(rows);
+                    }}
+                    return '[]';
+                }})()
+                "#,
+                serde_json::Value::String(CARD_TABLE_HEADER_TEXT.to_string())
+            ))
+            .await
+            .map_err(|e| ScraperError::JavaScript(e.to_string()))?
+            .into_value()
+            .unwrap_or_else(|_| "[]".to_string());
+
+        let rows: Vec<Vec<String>> =
+            serde_json::from_str(&json).map_err(|e| ScraperError::Json(e.to_string()))?;
+        let vehicles = vehicles::parse_registered_vehicles(&rows)?;
+
+        info!(count = vehicles.len(), "登録車両一覧の取得完了");
+        Ok(vehicles)
+    }
+
+    /// 検索結果ページの件数・合計金額を取得
+    ///
+    /// 検索条件の指定が完了した後の検索結果ページで呼び出す。
+    /// ダウンロードしたCSVの件数・合計と照合し、一部の明細しか取得できていないことを検出する用途向け。
+    pub async fn read_result_summary(&self) -> Result<ResultSummary, ScraperError> {
+        let page = self.get_page()?;
+        let text: String = page
+            .evaluate(format!(
+                r#"
+                (function() {{
+                    var selectors = {};
+                    for (var i = 0; i < selectors.length; i++) {{
+                        var el = document.querySelector(selectors[i]);
+                        if (el) {{
+                            return el.innerText;
+                        }}
+                    }}
+                    return document.body ? document.body.innerText : '';
+                }})()
+                "#,
+                serde_json::json!(RESULT_SUMMARY_SELECTORS)
+            ))
+            .await
+            .map_err(|e| ScraperError::JavaScript(e.to_string()))?
+            .into_value()
+            .unwrap_or_default();
+
+        let summary = summary::parse_result_summary(&text)?;
+        info!(
+            record_count = summary.record_count,
+            total_amount = summary.total_amount,
+            "検索結果の集計を取得"
+        );
+        Ok(summary)
+    }
+
+    /// 検索結果の利用証明書（PDF）をダウンロード
+    ///
+    /// `login` と検索条件の指定が完了した後の検索結果ページで、明細表の全行を選択してから
+    /// 同じフォームの利用証明書の発行ボタンを押し、`.pdf` ファイルのダウンロードを待つ。
+    pub async fn download_receipt_pdf(&self) -> Result<PathBuf, ScraperError> {
+        let page = self.get_page()?;
+        info!("利用証明書PDFのダウンロード開始...");
+
+        let existing_files = self.get_existing_files();
+
+        // 明細表の行選択チェックボックスを全て選択（利用証明書は選択した明細が対象）
+        let selected: u32 = page
+            .evaluate(format!(
+                r#"
+                (function() {{
+                    var table = document.querySelector("{}");
+                    if (!table) {{
+                        return 0;
+                    }}
+                    var boxes = table.querySelectorAll("{}");
+                    var count = 0;
+                    for (var i = 0; i < boxes.length; i++) {{
+                        if (!boxes[i].disabled && !boxes[i].checked) {{
+                            boxes[i].click();
+                        }}
+                        if (boxes[i].checked) {{
+                            count++;
+                        }}
+                    }}
+                    return count;
+                }})()
+                "#,
+                RESULT_TABLE_SELECTOR, RESULT_ROW_CHECKBOX_SELECTOR
+            ))
+            .await
+            .map_err(|e| ScraperError::JavaScript(e.to_string()))?
+            .into_value()
+            .unwrap_or(0);
+        debug!(selected, "明細を選択");
+
+        let clicked: bool = page
+            .evaluate(format!(
+                r#"
+                (function() {{
+                    var text = {};
+                    var table = document.querySelector("{}");
+                    var form = table ? table.closest('form') : null;
+                    if (!form) {{
+                        return false;
+                    }}
+                    var elements = form.querySelectorAll("{}");
+                    for (var i = 0; i < elements.length; i++) {{
+                        var label = (elements[i].textContent || elements[i].value || '').trim();
+                        if (label.indexOf(text) >= 0) {{
+                            elements[i].click();
+                            return true;
+                        }}
+                    }}
+                    return false;
+                }})()
+                "#,
+                serde_json::Value::String(RECEIPT_BUTTON_TEXT.to_string()),
+                RESULT_TABLE_SELECTOR,
+                RECEIPT_BUTTON_SELECTOR
+            ))
+            .await
+            .map_err(|e| ScraperError::JavaScript(e.to_string()))?
+            .into_value()
+            .unwrap_or(false);
+
+        if !clicked {
+            return Err(ScraperError::ElementNotFound(format!(
+                "{}の発行ボタンが見つかりません",
+                RECEIPT_BUTTON_TEXT
+            )));
+        }
+
+        let pdf_path = self
+            .wait_for_download(&existing_files, DownloadKind::Pdf)
+            .await?;
+        ensure_within_max_size(&pdf_path, self.config.max_download_size)?;
+        let renamed_path = self.rename_download(pdf_path)?;
+
+        info!(path = ?renamed_path, "利用証明書PDFダウンロード完了");
+        Ok(renamed_path)
+    }
+
+    /// ページ内の全 `<a>` 要素を収集（`link_matcher` 用）
+    async fn page_links(page: &Arc<Page>) -> Result<Vec<PageLink>, ScraperError> {
+        let json: String = page
+            .evaluate(
+                r#"
+                (function() {
+                    var links = document.querySelectorAll('a');
+                    var result = [];
+                    for (var i = 0; i < links.length; i++) {
+                        result.push({
+                            index: i,
+                            text: links[i].textContent.trim(),
+                            href: links[i].getAttribute('href') || ''
+                        });
+                    }
+                    return JSON.stringify(result);
+                })()
+                "#,
+            )
+            .await
+            .map_err(|e| ScraperError::JavaScript(e.to_string()))?
+            .into_value()
+            .unwrap_or_else(|_| "[]".to_string());
+
+        serde_json::from_str(&json).map_err(|e| ScraperError::Json(e.to_string()))
+    }
+
+    /// ページ内のCSV/PDFリンクを収集
+    async fn find_download_options(page: &Arc<Page>) -> Result<Vec<DownloadOption>, ScraperError> {
+        #[derive(serde::Deserialize)]
+        struct RawLink {
+            index: usize,
+            text: String,
+        }
+
+        let json: String = page
+            .evaluate(
+                r#"
+                (function() {
+                    var links = document.querySelectorAll('a');
+                    var result = [];
+                    for (var i = 0; i < links.length; i++) {
+                        var text = links[i].textContent.trim();
+                        if (/CSV|ＣＳＶ|PDF|ＰＤＦ/.test(text)) {
+                            result.push({ index: i, text: text });
+                        }
+                    }
+                    return JSON.stringify(result);
+                })()
+                "#,
+            )
+            .await
+            .map_err(|e| ScraperError::JavaScript(e.to_string()))?
+            .into_value()
+            .unwrap_or_else(|_| "[]".to_string());
+
+        let links: Vec<RawLink> =
+            serde_json::from_str(&json).map_err(|e| ScraperError::Json(e.to_string()))?;
+        let options: Vec<DownloadOption> = links
+            .into_iter()
+            .map(|l| DownloadOption::new(l.index, l.text))
+            .collect();
+        debug!("ダウンロードリンク一覧: {:?}", options);
+
+        Ok(options)
+    }
+
     /// CSVダウンロード共通処理
     async fn download_csv(&self, page: &Arc<Page>) -> Result<PathBuf, ScraperError> {
         // JavaScriptが完全に読み込まれるまで待機
@@ -775,7 +2123,7 @@ impl EtcScraper {
             tokio::time::sleep(Duration::from_secs(1)).await;
         }
 
-        // 検索結果ページのリンク一覧をデバッグ出力
+        // 検索結果ページのリンク一覧（デバッグ出力と、リンクが見つからない場合のエラー用）
         let result_links: String = page
             .evaluate(
                 r#"
@@ -822,45 +2170,140 @@ impl EtcScraper {
         // 既存ファイルを記録（新しいファイルを検出するため）
         let existing_files = self.get_existing_files();
 
-        // CSVダウンロードリンクをクリック（JavaScriptで）
-        let csv_clicked: bool = page
-            .evaluate(
+        // 指定された種類のダウンロードリンクをクリック（JavaScriptで）
+        let kind = self.config.download_kind;
+        let (link_index, link_text) = match self.config.link_matcher {
+            Some(ref matcher) => {
+                let links = Self::page_links(page).await?;
+                let link = matcher
+                    .select(&links)
+                    .and_then(|i| links.get(i))
+                    .ok_or_else(|| {
+                        ScraperError::ElementNotFound(format!(
+                            "link_matcher に一致するダウンロードリンクがありません（ページ内のリンク: {}）",
+                            result_links
+                        ))
+                    })?;
+                (link.index, link.text.clone())
+            }
+            None => {
+                let option = Self::find_download_options(page)
+                    .await?
+                    .into_iter()
+                    .find(|o| o.kind == Some(kind))
+                    .ok_or_else(|| {
+                        ScraperError::ElementNotFound(format!(
+                            "ダウンロードリンクが見つかりません: {}（ページ内のリンク: {}）",
+                            kind.id(),
+                            result_links
+                        ))
+                    })?;
+                (option.link_index, option.text)
+            }
+        };
+
+        let clicked: bool = page
+            .evaluate(format!(
                 r#"
-                (function() {
-                    var links = document.querySelectorAll('a');
-                    for (var i = 0; i < links.length; i++) {
-                        var text = links[i].textContent;
-                        if (text.indexOf('明細') >= 0 && (text.indexOf('CSV') >= 0 || text.indexOf('ＣＳＶ') >= 0)) {
-                            console.log('Found CSV link: ' + text);
-                            links[i].click();
-                            return true;
-                        }
-                    }
+                (function() {{
+                    var link = document.querySelectorAll('a')[{}];
+                    if (link) {{
+                        link.click();
+                        return true;
+                    }}
                     return false;
-                })()
+                }})()
                 "#,
-            )
+                link_index
+            ))
             .await
             .map(|v| v.into_value().unwrap_or(false))
             .unwrap_or(false);
 
-        info!("CSVリンククリック: {}", csv_clicked);
+        info!(clicked, kind = kind.id(), text = %link_text, "ダウンロードリンククリック");
 
-        if !csv_clicked {
-            return Err(ScraperError::ElementNotFound(
-                "CSVダウンロードリンクが見つかりません".into(),
-            ));
+        if !clicked {
+            return Err(ScraperError::ElementNotFound(format!(
+                "ダウンロードリンクをクリックできません: {}（ページ内のリンク: {}）",
+                link_text, result_links
+            )));
         }
 
         // ダウンロード完了を待機
-        let csv_path = self.wait_for_download(&existing_files).await?;
+        let csv_path = self.wait_for_download(&existing_files, kind).await?;
+        let renamed_path = self.finish_download(csv_path, kind)?;
+
+        info!(path = ?renamed_path, "CSVダウンロード完了");
+        Ok(renamed_path)
+    }
+
+    /// ダウンロード済みファイルの後処理（サイズ確認・展開・HTML判定・リネーム・BOM変換）
+    fn finish_download(
+        &self,
+        csv_path: PathBuf,
+        kind: DownloadKind,
+    ) -> Result<PathBuf, ScraperError> {
+        ensure_within_max_size(&csv_path, self.config.max_download_size)?;
+        let csv_path = archive::extract_csv_if_archive(csv_path, self.config.max_download_size)?;
+        if kind.extension() == "csv" {
+            ensure_within_max_size(&csv_path, self.config.max_download_size)?;
+            ensure_not_html(&csv_path)?;
+        }
 
         // ファイルをリネーム
-        let renamed_path = self.rename_csv(csv_path)?;
+        let renamed_path = self.rename_download(csv_path)?;
+        if self.config.verify_csv && kind.extension() == "csv" {
+            ensure_valid_csv(&renamed_path)?;
+        }
+
+        // Excel向けに BOM 付き UTF-8 で保存し直す
+        if self.config.csv_utf8_bom && kind.extension() == "csv" {
+            let bytes = std::fs::read(&renamed_path)?;
+            std::fs::write(&renamed_path, schema::csv_to_utf8_with_bom(&bytes))?;
+            debug!("CSVを BOM 付き UTF-8 に変換");
+        }
 
-        info!("CSVダウンロード完了: {:?}", renamed_path);
         Ok(renamed_path)
     }
+
+    /// フィクスチャモード: `dir/{user_id}.{拡張子}` をダウンロードディレクトリにコピーして後処理する
+    fn execute_fixture(&self, dir: &Path) -> Result<PathBuf, ScraperError> {
+        warn!(dir = ?dir, "フィクスチャモードで実行します（テスト専用）");
+        self.ensure_download_dir_writable()?;
+
+        // ログイン後のページを模したHTMLがあれば、アクセス制限ページかどうかを判定する
+        let page = dir.join(format!("{}.html", self.config.user_id));
+        if page.is_file() {
+            if let Some(retry_after) = rate_limit_wait(&std::fs::read_to_string(&page)?) {
+                return Err(ScraperError::RateLimited { retry_after });
+            }
+        }
+
+        let source = self
+            .config
+            .accepted_download_extensions
+            .iter()
+            .map(|ext| dir.join(format!("{}.{}", self.config.user_id, ext)))
+            .find(|path| path.is_file())
+            .ok_or_else(|| {
+                ScraperError::Download(format!(
+                    "フィクスチャが見つかりません: {:?}/{}.*",
+                    dir, self.config.user_id
+                ))
+            })?;
+        let file_name = source
+            .file_name()
+            .ok_or_else(|| ScraperError::Download("ファイル名が取得できません".into()))?;
+        let csv_path = self.config.download_dir().join(file_name);
+        std::fs::copy(&source, &csv_path)?;
+
+        let path = self.finish_download(csv_path, self.config.download_kind)?;
+        if self.validates_schema() {
+            schema::validate_csv_header(&std::fs::read(&path)?)?;
+        }
+        info!(path = ?path, "フィクスチャ読み込み完了");
+        Ok(path)
+    }
 }
 
 #[cfg(test)]
@@ -873,6 +2316,24 @@ mod tests {
         let scraper = EtcScraper::new(config);
         assert!(scraper.browser.is_none());
         assert!(scraper.page.is_none());
+        assert!(scraper.last_url().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_user_data_dir_removed() {
+        let mut scraper = EtcScraper::new(ScraperConfig::new("user", "pass"));
+        let dir = new_user_data_dir();
+        std::fs::create_dir_all(dir.join("Default")).unwrap();
+        scraper.user_data_dir = Some(dir.clone());
+        scraper.close().await.unwrap();
+        assert!(!dir.exists());
+
+        let mut scraper = EtcScraper::new(ScraperConfig::new("user", "pass"));
+        let dir = new_user_data_dir();
+        std::fs::create_dir_all(dir.join("Default")).unwrap();
+        scraper.user_data_dir = Some(dir.clone());
+        drop(scraper);
+        assert!(!dir.exists());
     }
 
     #[test]
@@ -888,4 +2349,525 @@ mod tests {
         assert_eq!(config.download_path, PathBuf::from("/tmp/downloads"));
         assert_eq!(config.timeout, Duration::from_secs(120));
     }
+
+    #[test]
+    fn test_debug_redacts_password() {
+        let config = ScraperConfig::new("user", "secret-pass")
+            .with_extra_header("X-Api-Key", "secret-header")
+            .with_session(
+                Vec::new(),
+                "https://www.etc-meisai.jp/etc/R?token=secret-token",
+            );
+        let debug = format!("{:?}", config);
+        assert!(debug.contains("user"));
+        assert!(debug.contains("X-Api-Key"));
+        assert!(!debug.contains("secret-pass"));
+        assert!(!debug.contains("secret-header"));
+        assert!(!debug.contains("secret-token"));
+    }
+
+    #[test]
+    fn test_config_validate() {
+        assert!(ScraperConfig::new("user", "pass").validate().is_ok());
+        assert!(matches!(
+            ScraperConfig::new("", "pass").validate(),
+            Err(ScraperError::Config(_))
+        ));
+        assert!(ScraperConfig::new("user", "").validate().is_err());
+        assert!(ScraperConfig::new("user", "pass")
+            .with_window_size(0, 800)
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_effective_browser_args() {
+        let mut config = ScraperConfig::new("user", "pass")
+            .with_download_path("/tmp/downloads")
+            .with_window_size(1600, 900)
+            .with_locale("ja-JP");
+        config.chrome_path = None;
+        assert_eq!(
+            config.effective_browser_args(),
+            vec![
+                "--window-size=1600,900",
+                "--lang=ja-JP",
+                "--download.default_directory=/tmp/downloads",
+                "--headless=new",
+            ]
+        );
+
+        let args = config
+            .clone()
+            .with_headless_mode(HeadlessMode::Old)
+            .effective_browser_args();
+        assert!(args.contains(&"--headless".to_string()));
+        assert!(!args.contains(&"--headless=new".to_string()));
+
+        config.chrome_path = Some(PathBuf::from("/usr/bin/chromium"));
+        let args = config.with_headless(false).effective_browser_args();
+        assert!(args.contains(&"--no-sandbox".to_string()));
+        assert!(!args.contains(&"--headless=new".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_otp_provider() {
+        let config = ScraperConfig::new("user", "pass").with_otp_provider(
+            crate::config::OtpProvider::new(|| async { Ok("123456".to_string()) }),
+        );
+
+        let provider = config.otp_provider.clone().unwrap();
+        assert_eq!(provider.fetch().await.unwrap(), "123456");
+        assert_eq!(format!("{:?}", provider), "OtpProvider(..)");
+    }
+
+    #[test]
+    fn test_link_matcher() {
+        let matcher = crate::config::LinkMatcher::new(|links| {
+            links.iter().position(|l| l.href.contains("goOutput"))
+        });
+        let link = |index: usize, text: &str, href: &str| PageLink {
+            index,
+            text: text.to_string(),
+            href: href.to_string(),
+        };
+        let links = vec![
+            link(0, "検索条件の指定", "#"),
+            link(1, "明細データ出力", "javascript:goOutput('1')"),
+        ];
+        assert_eq!(matcher.select(&links), Some(1));
+        assert_eq!(matcher.select(&links[..1]), None);
+    }
+
+    #[test]
+    fn test_navigate_hook() {
+        // ページを借用するクロージャをそのまま渡せること
+        let hook = crate::config::NavigateHook::new(|page| {
+            Box::pin(async move {
+                page.evaluate("document.querySelector('.modal-close')?.click()")
+                    .await
+                    .map_err(|e| ScraperError::JavaScript(e.to_string()))?;
+                Ok(())
+            })
+        });
+        let config = ScraperConfig::new("user", "pass").with_on_navigate(hook);
+        assert_eq!(
+            format!("{:?}", config.on_navigate.unwrap()),
+            "NavigateHook(..)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_download_requires_stable_size() {
+        let dir = std::env::temp_dir().join(format!("etc_wait_dl_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let csv = dir.join("meisai.csv");
+        std::fs::write(&csv, b"a,b,c").unwrap();
+
+        let config = ScraperConfig::new("user", "pass")
+            .with_download_path(&dir)
+            .with_download_poll_interval(Duration::from_millis(10));
+        let scraper = EtcScraper::new(config);
+        let start = std::time::Instant::now();
+        let found = scraper
+            .wait_for_download(&std::collections::HashSet::new(), DownloadKind::DetailCsv)
+            .await
+            .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(found, csv);
+        // 1回目のポーリングでは確定せず、少なくとも1間隔待つ
+        assert!(start.elapsed() >= Duration::from_millis(10));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_download_accepted_extensions() {
+        let dir = std::env::temp_dir().join(format!("etc_wait_tsv_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let tsv = dir.join("meisai.TSV");
+        std::fs::write(&tsv, b"a\tb\tc").unwrap();
+        // 受け付ける拡張子でも書き込み途中のファイルは対象外
+        std::fs::write(dir.join("meisai.tsv.crdownload"), b"a\tb").unwrap();
+
+        let config = ScraperConfig::new("user", "pass")
+            .with_download_path(&dir)
+            .with_download_poll_interval(Duration::from_millis(10))
+            .with_accepted_download_extensions([".csv", "tsv"]);
+        assert_eq!(config.accepted_download_extensions, vec!["csv", "tsv"]);
+        let found = EtcScraper::new(config)
+            .wait_for_download(&std::collections::HashSet::new(), DownloadKind::DetailCsv)
+            .await
+            .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(found, tsv);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_download_timeout() {
+        let dir = std::env::temp_dir().join(format!("etc_wait_timeout_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let config = ScraperConfig::new("user", "pass").with_download_path(&dir);
+        let start = tokio::time::Instant::now();
+        let result = EtcScraper::new(config)
+            .wait_for_download(&std::collections::HashSet::new(), DownloadKind::DetailCsv)
+            .await;
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(result, Err(ScraperError::Timeout(_))));
+        assert!(start.elapsed() >= Duration::from_secs(DOWNLOAD_WAIT_SECS));
+    }
+
+    #[tokio::test]
+    async fn test_download_range_rejects_inverted_period() {
+        let scraper = EtcScraper::new(ScraperConfig::new("user", "pass"));
+        let date = |day| NaiveDate::from_ymd_opt(2024, 4, day).unwrap();
+        let result = scraper.download_range(vec![(date(30), date(1))]).await;
+        assert!(matches!(result, Err(ScraperError::Config(_))));
+
+        // 期間の指定が正しければブラウザ未初期化のエラーになる
+        let result = scraper.download_range(vec![(date(1), date(30))]).await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_period_search_script_matches_search_page() {
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/etc_search_condition.html"
+        ));
+        // 「期間指定」ラジオボタンと期間欄がページに存在すること
+        assert!(fixture.contains(r#"<input type="radio" name="sokoKbn" value="1">期間指定"#));
+        assert!(SEARCH_PERIOD_MODE_SELECTOR.contains("name='sokoKbn'][value='1'"));
+        let date = |day| NaiveDate::from_ymd_opt(2024, 4, day).unwrap();
+        let values = [2024, 4, 1, 2024, 4, 30];
+        for (name, value) in SEARCH_PERIOD_FIELDS.iter().zip(values) {
+            let select = fixture
+                .split(&format!(r#"<select name="{}">"#, name))
+                .nth(1)
+                .and_then(|rest| rest.split("</select>").next())
+                .unwrap_or_else(|| panic!("{} not found in search page", name));
+            // セレクトボックスは数値として一致する選択肢を選ぶ（"04" と 4）
+            assert!(
+                select
+                    .split(r#"<option value=""#)
+                    .skip(1)
+                    .filter_map(|option| option.split('"').next()?.parse::<u32>().ok())
+                    .any(|option| option == value),
+                "{} has no option {}",
+                name,
+                value
+            );
+        }
+
+        let script = period_search_script(date(1), date(30));
+        assert!(script.contains(SEARCH_PERIOD_MODE_SELECTOR));
+        assert!(script.contains(r#""fromDD":1"#));
+        assert!(script.contains(r#""toDD":30"#));
+        assert!(script.contains(r#""toYYYY":2024"#));
+    }
+
+    #[test]
+    fn test_report_type_script_matches_search_page() {
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/etc_search_condition.html"
+        ));
+        let form = fixture
+            .split("<form")
+            .nth(1)
+            .and_then(|rest| rest.split("</form>").next())
+            .expect("search form not found");
+        // 明細の種類は検索条件ページのフォーム内のラベル付きラジオボタンで選ぶ
+        for report_type in [ReportType::Usage, ReportType::Billing] {
+            let labels: Vec<&str> = form
+                .split("<label>")
+                .skip(1)
+                .filter_map(|label| label.split("</label>").next())
+                .filter(|label| label.contains(report_type.label()))
+                .collect();
+            assert_eq!(labels.len(), 1, "{}", report_type.id());
+            assert!(labels[0].contains(r#"<input type="radio""#));
+
+            let script = report_type_script(report_type);
+            assert!(script.contains(&format!(r#"var text = "{}";"#, report_type.label())));
+            assert!(script.contains("form label"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fixture_mode_validates_like_download() {
+        let dir = std::env::temp_dir().join(format!("etc_fixture_schema_{}", std::process::id()));
+        let fixtures = dir.join("fixtures");
+        std::fs::create_dir_all(&fixtures).unwrap();
+        std::fs::write(fixtures.join("user.csv"), "請求年月,請求金額\n").unwrap();
+
+        let config = |report_type| {
+            ScraperConfig::new("user", "pass")
+                .with_download_path(dir.join("downloads"))
+                .with_fixture_mode(&fixtures)
+                .with_validate_schema(true)
+                .with_report_type(report_type)
+        };
+        // 利用明細はヘッダーを検証し、請求明細は実際のダウンロードと同じく検証しない
+        let err = EtcScraper::new(config(ReportType::Usage))
+            .execute()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ScraperError::CsvSchema(_)), "{:?}", err);
+        assert!(EtcScraper::new(config(ReportType::Billing))
+            .execute()
+            .await
+            .is_ok());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_receipt_selectors_match_result_page() {
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/etc_search_result.html"
+        ));
+        let section = |html: &'static str, start: &str, end: &str| -> &'static str {
+            html.split(start)
+                .nth(1)
+                .and_then(|rest| rest.split(end).next())
+                .unwrap_or_else(|| panic!("{} not found", start))
+        };
+        assert_eq!(RESULT_TABLE_SELECTOR, "table.meisai");
+        let form = section(fixture, "<form", "</form>");
+        let table = section(form, r#"<table class="meisai">"#, "</table>");
+
+        // 行選択は明細表の `td` 内のチェックボックスのみ（見出しの全選択・案内の設定は対象外）
+        assert!(RESULT_ROW_CHECKBOX_SELECTOR.starts_with("td "));
+        let row_boxes = table.matches(r#"<td><input type="checkbox""#).count();
+        let all_boxes = fixture.matches(r#"type="checkbox""#).count();
+        assert_eq!(row_boxes, 2);
+        assert_eq!(all_boxes, 4);
+
+        // 発行ボタンは明細表と同じフォーム内のボタンのみ（案内のリンクは対象外）
+        assert!(!RECEIPT_BUTTON_SELECTOR.split(", ").any(|s| s == "a"));
+        let buttons: Vec<&str> = form
+            .split(r#"<input type="button""#)
+            .skip(1)
+            .filter(|button| section(button, r#"value=""#, "\"").contains(RECEIPT_BUTTON_TEXT))
+            .collect();
+        assert_eq!(buttons.len(), 1);
+        assert!(buttons[0].contains("focusTarget_Receipt"));
+        assert!(fixture.contains("利用証明書について</a>"));
+        assert!(!form.contains("利用証明書について"));
+    }
+
+    #[test]
+    fn test_download_file_guards() {
+        let dir = std::env::temp_dir().join(format!("etc_dl_guard_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let csv = dir.join("meisai.csv");
+        let html = dir.join("error.csv");
+        std::fs::write(&csv, "利用年月日（自）,時分（自）\n".as_bytes()).unwrap();
+        std::fs::write(
+            &html,
+            b"\r\n  <!DOCTYPE HTML><html><body>Error</body></html>",
+        )
+        .unwrap();
+
+        let size_checks = (
+            ensure_within_max_size(&csv, None).is_ok(),
+            ensure_within_max_size(&csv, Some(1024)).is_ok(),
+            ensure_within_max_size(&csv, Some(4)),
+        );
+        let html_checks = (ensure_not_html(&csv).is_ok(), ensure_not_html(&html));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(size_checks.0 && size_checks.1);
+        assert!(
+            matches!(size_checks.2, Err(ScraperError::Download(msg)) if msg.contains("exceeds max size"))
+        );
+        assert!(html_checks.0);
+        assert!(matches!(html_checks.1, Err(ScraperError::Download(_))));
+    }
+
+    #[test]
+    fn test_ensure_valid_csv() {
+        let empty = Path::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/etc_empty.csv"
+        ));
+        assert!(
+            matches!(ensure_valid_csv(empty), Err(ScraperError::Download(msg)) if msg.contains("empty or invalid CSV"))
+        );
+        assert!(ensure_valid_csv(&empty.with_file_name("missing.csv")).is_err());
+
+        let dir = std::env::temp_dir().join(format!("etc_valid_csv_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let csv = dir.join("meisai.csv");
+        let binary = dir.join("binary.csv");
+        std::fs::write(&csv, "利用年月日（自）,時分（自）\n".as_bytes()).unwrap();
+        std::fs::write(&binary, b"PK\x03\x04\x00\x00").unwrap();
+        let checks = (
+            ensure_valid_csv(&csv).is_ok(),
+            ensure_valid_csv(&binary).is_err(),
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(checks.0 && checks.1);
+    }
+
+    #[test]
+    fn test_is_maintenance_page() {
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/etc_maintenance.html"
+        ));
+        assert!(is_maintenance_page(ETC_MEISAI_URL, fixture, false));
+        assert!(is_maintenance_page(
+            "https://www.etc-meisai.jp/maintenance.html",
+            "",
+            false
+        ));
+        assert!(!is_maintenance_page(
+            ETC_MEISAI_URL,
+            "ログイン ETC利用照会サービス",
+            false
+        ));
+
+        // 事前告知が載っている通常のログインページはメンテナンス中ではない
+        let notice = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/etc_login_maintenance_notice.html"
+        ));
+        assert!(!is_maintenance_page(ETC_MEISAI_URL, notice, false));
+        assert!(!is_maintenance_page(
+            ETC_MEISAI_URL,
+            notice,
+            notice.contains("name=\"risLoginId\"")
+        ));
+        assert!(!is_maintenance_page(
+            "https://www.etc-meisai.jp/etc/R?funccode=1013000000&info=maintenance",
+            "",
+            false
+        ));
+
+        assert!(!ScraperError::Maintenance(String::new()).is_retryable());
+    }
+
+    #[test]
+    fn test_is_password_expired_page() {
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/etc_password_expired.html"
+        ));
+        assert!(is_password_expired_page(fixture));
+        assert!(!is_password_expired_page("ログイン ETC利用照会サービス"));
+
+        assert!(!ScraperError::PasswordExpired(String::new()).is_retryable());
+    }
+
+    #[test]
+    fn test_rate_limit_wait() {
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/etc_rate_limited.html"
+        ));
+        // 受付時間の「0時00分」は待ち時間として扱わない
+        assert_eq!(
+            rate_limit_wait(fixture),
+            Some(Some(Duration::from_secs(30 * 60)))
+        );
+        assert_eq!(
+            rate_limit_wait("ご利用を制限しています。しばらく時間をおいてから再度お試しください"),
+            Some(None)
+        );
+        assert_eq!(rate_limit_wait("ログイン ETC利用照会サービス"), None);
+        // 一般的なシステムエラーページは制限ページではない
+        assert_eq!(
+            rate_limit_wait(include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/etc_system_error.html"
+            ))),
+            None
+        );
+        assert_eq!(
+            parse_wait_duration("1時間30分後に"),
+            Some(Duration::from_secs(5400))
+        );
+
+        let err = ScraperError::RateLimited { retry_after: None };
+        assert!(!err.is_retryable());
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(15 * 60)));
+        assert_eq!(ScraperError::Maintenance(String::new()).retry_after(), None);
+    }
+
+    #[test]
+    fn test_select_newest() {
+        let older = std::time::UNIX_EPOCH + Duration::from_secs(100);
+        let newer = std::time::UNIX_EPOCH + Duration::from_secs(200);
+
+        assert_eq!(select_newest(Vec::new()), None);
+        assert_eq!(
+            select_newest(vec![
+                (PathBuf::from("new.csv"), newer),
+                (PathBuf::from("stale.csv"), older),
+            ]),
+            Some(PathBuf::from("new.csv"))
+        );
+        // 同時刻ならパス名順で決定的に選ぶ
+        assert_eq!(
+            select_newest(vec![
+                (PathBuf::from("b.csv"), newer),
+                (PathBuf::from("a.csv"), newer),
+            ]),
+            Some(PathBuf::from("b.csv"))
+        );
+    }
+
+    #[test]
+    fn test_clean_download_dir() {
+        let dir = std::env::temp_dir().join(format!("etc_clean_dl_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        for name in [
+            "old.csv",
+            "OLD2.CSV",
+            "partial.crdownload",
+            "keep.txt",
+            "sub/nested.csv",
+        ] {
+            std::fs::write(dir.join(name), b"x").unwrap();
+        }
+
+        let config = ScraperConfig::new("user", "pass")
+            .with_download_path(&dir)
+            .with_clean_download_dir(true);
+        EtcScraper::new(config).clean_download_dir().unwrap();
+
+        let mut remaining: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect();
+        remaining.sort();
+        let nested_kept = dir.join("sub/nested.csv").exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(remaining, vec!["keep.txt", "sub"]);
+        assert!(nested_kept);
+    }
+
+    #[test]
+    fn test_download_dir_not_writable() {
+        // download_path に既存ファイルを指定するとディレクトリを作成できない
+        let file_path = std::env::temp_dir().join(format!("etc_not_a_dir_{}", std::process::id()));
+        std::fs::write(&file_path, b"").unwrap();
+
+        let config = ScraperConfig::new("user", "pass").with_download_path(&file_path);
+        let scraper = EtcScraper::new(config);
+        let err = scraper.ensure_download_dir_writable().unwrap_err();
+        std::fs::remove_file(&file_path).unwrap();
+
+        match err {
+            ScraperError::DownloadPathNotWritable { path, .. } => assert_eq!(path, file_path),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
 }
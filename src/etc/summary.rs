@@ -0,0 +1,125 @@
+//! 利用明細検索結果の件数・合計金額の解析
+//!
+//! 検索結果ページの集計欄のテキストから、ラベル（「件数」「合計金額」等）の直後の数値を読み取り
+//! [`ResultSummary`] に変換する。CSVをダウンロードする前の簡易的な整合性確認に使う。
+
+use crate::error::ScraperError;
+
+use super::types::ResultSummary;
+use super::usage::parse_yen_amount;
+
+/// 件数のラベル（先に一致したものを使う）
+///
+/// 単独の「件数」は「表示件数」（1ページの表示件数の選択欄）にも一致するため使わない。
+const COUNT_LABELS: [&str; 2] = ["該当件数", "検索件数"];
+/// 合計金額のラベル（先に一致したものを使う）
+///
+/// 単独の「合計」は「割引前料金合計」にも一致するため使わない。
+const TOTAL_LABELS: [&str; 2] = ["通行料金合計", "合計金額"];
+/// ラベルから数値までの間に許容する文字数（区切り記号・タグ等）
+const LABEL_WINDOW: usize = 16;
+
+/// ラベルの直後（`LABEL_WINDOW` 文字以内）にある数値表記を取り出す（直前の符号を含む）
+fn number_after<'a>(text: &'a str, label: &str) -> Option<&'a str> {
+    let is_digit = |c: char| c.is_ascii_digit() || ('０'..='９').contains(&c);
+    let is_sign = |c: char| matches!(c, '-' | '－' | '▲');
+    text.match_indices(label).find_map(|(i, _)| {
+        let rest = &text[i + label.len()..];
+        let (digits_start, _) = rest
+            .char_indices()
+            .take(LABEL_WINDOW)
+            .find(|&(_, c)| is_digit(c))?;
+        let start = rest[..digits_start]
+            .char_indices()
+            .next_back()
+            .filter(|&(_, c)| is_sign(c))
+            .map_or(digits_start, |(j, _)| j);
+        let end = rest[digits_start..]
+            .char_indices()
+            .find(|&(_, c)| !(is_digit(c) || c == ',' || c == '，'))
+            .map_or(rest.len(), |(j, _)| digits_start + j);
+        Some(&rest[start..end])
+    })
+}
+
+fn find_number(text: &str, labels: &[&str]) -> Option<i64> {
+    labels
+        .iter()
+        .find_map(|label| number_after(text, label))
+        .and_then(parse_yen_amount)
+}
+
+/// 検索結果ページのテキストから件数・合計金額を取り出す
+///
+/// いずれかが見つからない場合は `ScraperError::Extraction` を返す。
+pub(crate) fn parse_result_summary(text: &str) -> Result<ResultSummary, ScraperError> {
+    let record_count = find_number(text, &COUNT_LABELS).ok_or_else(|| {
+        ScraperError::Extraction(format!(
+            "検索結果の件数が見つかりません（{}）",
+            COUNT_LABELS.join(" / ")
+        ))
+    })?;
+    let total_amount = find_number(text, &TOTAL_LABELS).ok_or_else(|| {
+        ScraperError::Extraction(format!(
+            "検索結果の合計金額が見つかりません（{}）",
+            TOTAL_LABELS.join(" / ")
+        ))
+    })?;
+
+    let record_count = usize::try_from(record_count).map_err(|_| {
+        ScraperError::Extraction(format!("検索結果の件数が負の値です: {}", record_count))
+    })?;
+
+    Ok(ResultSummary {
+        record_count,
+        total_amount,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_result_summary() {
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/etc_search_result.html"
+        ));
+        assert_eq!(
+            parse_result_summary(fixture).unwrap(),
+            ResultSummary {
+                record_count: 12,
+                total_amount: 15840,
+            }
+        );
+        // 集計欄（`table.summary`）のみのテキストでも読み取れる
+        let table = fixture
+            .split(r#"<table class="summary">"#)
+            .nth(1)
+            .and_then(|rest| rest.split("</table>").next())
+            .unwrap();
+        assert_eq!(
+            parse_result_summary(table).unwrap(),
+            parse_result_summary(fixture).unwrap()
+        );
+
+        let summary = parse_result_summary("該当件数: 3件\n合計金額: ￥２，６４０").unwrap();
+        assert_eq!(summary.record_count, 3);
+        assert_eq!(summary.total_amount, 2640);
+        let summary = parse_result_summary("該当件数: 1件\n通行料金合計: ▲1,320円").unwrap();
+        assert_eq!(summary.total_amount, -1320);
+
+        let err = parse_result_summary("利用明細検索結果 2024/04/01").unwrap_err();
+        assert!(matches!(err, ScraperError::Extraction(_)));
+
+        // 「表示件数」「割引前料金合計」を件数・合計金額として読まない
+        let err = parse_result_summary("表示件数 50件\n通行料金合計 15,840円").unwrap_err();
+        assert!(matches!(err, ScraperError::Extraction(msg) if msg.contains("件数")));
+        let err = parse_result_summary("該当件数 12件\n割引前料金合計 17,600円").unwrap_err();
+        assert!(matches!(err, ScraperError::Extraction(msg) if msg.contains("合計金額")));
+
+        let err = parse_result_summary("該当件数: -3件\n合計金額: 0円").unwrap_err();
+        assert!(matches!(err, ScraperError::Extraction(msg) if msg.contains("負の値")));
+    }
+}
@@ -0,0 +1,169 @@
+//! ETC 関連の型定義
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// 検索結果ページからダウンロードするファイルの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum DownloadKind {
+    /// 明細CSV
+    #[default]
+    DetailCsv,
+    /// 集計CSV（月次サマリー）
+    SummaryCsv,
+    /// PDF
+    Pdf,
+}
+
+impl DownloadKind {
+    /// 安定した識別子
+    pub fn id(&self) -> &'static str {
+        match self {
+            DownloadKind::DetailCsv => "detail_csv",
+            DownloadKind::SummaryCsv => "summary_csv",
+            DownloadKind::Pdf => "pdf",
+        }
+    }
+
+    /// ダウンロードされるファイルの拡張子
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DownloadKind::DetailCsv | DownloadKind::SummaryCsv => "csv",
+            DownloadKind::Pdf => "pdf",
+        }
+    }
+
+    /// リンクテキストから種類を判定
+    pub fn classify(link_text: &str) -> Option<Self> {
+        let is_csv = link_text.contains("CSV") || link_text.contains("ＣＳＶ");
+        let is_pdf = link_text.contains("PDF") || link_text.contains("ＰＤＦ");
+
+        if is_csv && link_text.contains("集計") {
+            Some(DownloadKind::SummaryCsv)
+        } else if is_csv && link_text.contains("明細") {
+            Some(DownloadKind::DetailCsv)
+        } else if is_pdf {
+            Some(DownloadKind::Pdf)
+        } else {
+            None
+        }
+    }
+}
+
+/// ダウンロードする明細の種類
+///
+/// 請求明細は割引適用後の金額を含み、利用明細とは列構成が異なる。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ReportType {
+    /// 利用明細
+    #[default]
+    Usage,
+    /// 請求明細
+    Billing,
+}
+
+impl ReportType {
+    /// 安定した識別子
+    pub fn id(&self) -> &'static str {
+        match self {
+            ReportType::Usage => "usage",
+            ReportType::Billing => "billing",
+        }
+    }
+
+    /// 検索条件ページの選択欄の表示名
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReportType::Usage => "利用明細",
+            ReportType::Billing => "請求明細",
+        }
+    }
+}
+
+/// 利用明細検索結果の集計（件数・合計金額）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResultSummary {
+    /// 明細の件数
+    pub record_count: usize,
+    /// 通行料金の合計（円）
+    pub total_amount: i64,
+}
+
+/// アカウントに登録されたETCカードと車両
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisteredVehicle {
+    /// ETCカード番号（全角数字・ハイフンは半角に変換）
+    pub card_number: String,
+    /// 車両番号（未登録の場合は空文字列）
+    pub vehicle_number: String,
+    /// カード発行日（表示されていない場合は `None`）
+    pub issue_date: Option<NaiveDate>,
+}
+
+/// ページ内の `<a>` 要素（`LinkMatcher` に渡す）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageLink {
+    /// ページ内の `<a>` 要素のインデックス
+    pub index: usize,
+    /// リンクテキスト（前後の空白を除去）
+    pub text: String,
+    /// `href` 属性（未指定の場合は空文字列）
+    pub href: String,
+}
+
+/// 検索結果ページで利用可能なダウンロードリンク
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadOption {
+    /// 安定した識別子（種類が判定できない場合は `link_<index>`）
+    pub id: String,
+    /// リンクテキスト
+    pub text: String,
+    /// 判定した種類
+    pub kind: Option<DownloadKind>,
+    /// ページ内の `<a>` 要素のインデックス
+    pub(crate) link_index: usize,
+}
+
+impl DownloadOption {
+    pub(crate) fn new(link_index: usize, text: String) -> Self {
+        let kind = DownloadKind::classify(&text);
+        let id = match kind {
+            Some(kind) => kind.id().to_string(),
+            None => format!("link_{}", link_index),
+        };
+        Self {
+            id,
+            text,
+            kind,
+            link_index,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(
+            DownloadKind::classify("利用明細ＣＳＶ出力"),
+            Some(DownloadKind::DetailCsv)
+        );
+        assert_eq!(
+            DownloadKind::classify("集計CSV出力"),
+            Some(DownloadKind::SummaryCsv)
+        );
+        assert_eq!(
+            DownloadKind::classify("利用明細PDF出力"),
+            Some(DownloadKind::Pdf)
+        );
+        assert_eq!(DownloadKind::classify("検索条件の指定"), None);
+    }
+
+    #[test]
+    fn test_download_option_id() {
+        assert_eq!(DownloadOption::new(3, "明細CSV".into()).id, "detail_csv");
+        assert_eq!(DownloadOption::new(7, "CSVについて".into()).id, "link_7");
+    }
+}
@@ -0,0 +1,443 @@
+//! ETC 利用明細レコードと差分抽出・集計
+//!
+//! 利用照会サービスは毎回全履歴を返すため、前回までに取り込んだレコードを
+//! キーで管理し、新規行のみを取り出すためのヘルパーを提供する。
+//! あわせて明細CSVのパース、合計・車両別・月別の料金集計と、明細の金額表記のパースを提供する。
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::ScraperError;
+
+use super::schema::{decode, split_fields};
+
+/// ETC 利用明細の1行
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EtcUsageRecord {
+    /// 利用日
+    pub date: NaiveDate,
+    /// 入口IC
+    pub entry_ic: String,
+    /// 出口IC
+    pub exit_ic: String,
+    /// 料金（円、割引適用後）
+    pub amount: i64,
+    /// ETC割引額（円）。割引額の列がない古い形式のCSVでは 0
+    #[serde(default)]
+    pub discount_amount: i64,
+    /// マイレージのポイント。列がない古い形式のCSVでは `None`
+    #[serde(default)]
+    pub mileage_points: Option<i64>,
+    /// 車両番号
+    pub vehicle_number: String,
+    /// ETCカード番号
+    pub card_number: String,
+}
+
+impl EtcUsageRecord {
+    /// 割引前の料金（円）
+    pub fn gross_amount(&self) -> i64 {
+        self.amount + self.discount_amount
+    }
+
+    /// レコードの安定キー
+    ///
+    /// (利用日, 入口IC, 出口IC, 料金, カード番号) の FNV-1a 64bit ハッシュを16進文字列で返す。
+    /// プロセスやRustのバージョンに依存しないため、永続化して次回実行時の比較に使える。
+    pub fn record_key(&self) -> String {
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let date = self.date.format("%Y-%m-%d").to_string();
+        let amount = self.amount.to_string();
+        let fields = [
+            date.as_str(),
+            self.entry_ic.trim(),
+            self.exit_ic.trim(),
+            amount.as_str(),
+            self.card_number.trim(),
+        ];
+
+        let mut hash = FNV_OFFSET;
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
+                // フィールド境界を区切り文字で明示（"ab"+"c" と "a"+"bc" を区別）
+                hash ^= 0x1f;
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+            for byte in field.bytes() {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+        }
+        format!("{:016x}", hash)
+    }
+
+    /// 内容比較用の正規化した1行（文字列の前後・連続する空白は無視）
+    fn normalized_line(&self) -> String {
+        let normalize = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ");
+        format!(
+            "{}\x1f{}\x1f{}\x1f{}\x1f{}\x1f{}\x1f{}\x1f{}",
+            self.date.format("%Y-%m-%d"),
+            normalize(&self.entry_ic),
+            normalize(&self.exit_ic),
+            self.amount,
+            self.discount_amount,
+            self.mileage_points
+                .map(|p| p.to_string())
+                .unwrap_or_default(),
+            normalize(&self.vehicle_number),
+            normalize(&self.card_number),
+        )
+    }
+}
+
+/// 前回実行時との明細の比較結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordsChange {
+    /// 前回と同じ明細（新しい利用なし）
+    Unchanged,
+    /// 明細が変わった（または前回のハッシュがない）。`hash` を次回の比較用に保存する
+    Changed { hash: String },
+}
+
+/// 明細の集合の SHA-256（小文字16進）
+///
+/// 行の順序と、各項目の前後・連続する空白の違いは無視する（同一行の重複は区別する）。
+/// サイト側で並び順や空白が変わっても、内容が同じなら同じ値になる。
+pub fn records_hash(records: &[EtcUsageRecord]) -> String {
+    let mut lines: Vec<String> = records
+        .iter()
+        .map(EtcUsageRecord::normalized_line)
+        .collect();
+    lines.sort_unstable();
+    let mut hasher = Sha256::new();
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            hasher.update(b"\n");
+        }
+        hasher.update(line.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// 前回実行時のハッシュと比較し、明細が変わったかを判定
+pub fn compare_records_hash(records: &[EtcUsageRecord], previous: Option<&str>) -> RecordsChange {
+    let hash = records_hash(records);
+    if previous == Some(hash.as_str()) {
+        RecordsChange::Unchanged
+    } else {
+        RecordsChange::Changed { hash }
+    }
+}
+
+/// 既出キーに含まれない新規レコードのみを返す
+///
+/// `since` を指定した場合はその日付以降のレコードに限定する（元の順序を維持）。
+/// `record_key` は時刻を含まないため、同じ日に同じ区間・料金・カードで複数回利用した場合は同じキーになる。
+/// 同一バッチ内で同じキーの行はまとめず、すべて返す。
+pub fn filter_new_records(
+    records: impl IntoIterator<Item = EtcUsageRecord>,
+    seen_keys: &HashSet<String>,
+    since: Option<NaiveDate>,
+) -> Vec<EtcUsageRecord> {
+    records
+        .into_iter()
+        .filter(|record| since.is_none_or(|since| record.date >= since))
+        .filter(|record| !seen_keys.contains(&record.record_key()))
+        .collect()
+}
+
+/// 明細CSVの列名
+const COLUMN_DATE: &str = "利用年月日（自）";
+const COLUMN_ENTRY_IC: &str = "利用ＩＣ（自）";
+const COLUMN_EXIT_IC: &str = "利用ＩＣ（至）";
+const COLUMN_AMOUNT: &str = "通行料金";
+const COLUMN_VEHICLE_NUMBER: &str = "車両番号";
+const COLUMN_CARD_NUMBER: &str = "ＥＴＣカード番号";
+/// 割引額の列（名前の一部で照合。古い形式のCSVにはない）
+const COLUMN_DISCOUNT: &str = "割引額";
+/// マイレージの列（名前の一部で照合。古い形式のCSVにはない）
+const COLUMN_MILEAGE: &str = "マイレージ";
+
+/// 明細CSVを `EtcUsageRecord` に変換
+///
+/// 列はヘッダー行の列名で対応付ける。割引額・マイレージの列がない古い形式のCSVでは
+/// `discount_amount` を 0、`mileage_points` を `None` とする。
+/// 文字コードは自動判定する（[`super::detect_csv_encoding`]）。
+/// 必須の列がない場合や、利用日・料金を解釈できない行がある場合は `ScraperError::CsvSchema` を返す。
+pub fn parse_usage_csv(bytes: &[u8]) -> Result<Vec<EtcUsageRecord>, ScraperError> {
+    let text = decode(bytes);
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let header = split_fields(lines.next().unwrap_or_default());
+
+    let find = |matches: &dyn Fn(&str) -> bool| header.iter().position(|column| matches(column));
+    let required = |name: &str| {
+        find(&|column| column == name)
+            .ok_or_else(|| ScraperError::CsvSchema(format!("必須の列がありません: {}", name)))
+    };
+    let date = required(COLUMN_DATE)?;
+    let entry_ic = required(COLUMN_ENTRY_IC)?;
+    let exit_ic = required(COLUMN_EXIT_IC)?;
+    let amount = required(COLUMN_AMOUNT)?;
+    let vehicle_number = required(COLUMN_VEHICLE_NUMBER)?;
+    let card_number = required(COLUMN_CARD_NUMBER)?;
+    let discount = find(&|column| column.contains(COLUMN_DISCOUNT));
+    let mileage = find(&|column| column.contains(COLUMN_MILEAGE));
+
+    lines
+        .enumerate()
+        .map(|(i, line)| {
+            let fields = split_fields(line);
+            let field = |index: usize| fields.get(index).map(String::as_str).unwrap_or_default();
+            let invalid = |name: &str| {
+                ScraperError::CsvSchema(format!(
+                    "{}行目の{}を解釈できません: {}",
+                    i + 2,
+                    name,
+                    line
+                ))
+            };
+
+            Ok(EtcUsageRecord {
+                date: parse_usage_date(field(date)).ok_or_else(|| invalid(COLUMN_DATE))?,
+                entry_ic: field(entry_ic).to_string(),
+                exit_ic: field(exit_ic).to_string(),
+                amount: parse_yen_amount(field(amount)).ok_or_else(|| invalid(COLUMN_AMOUNT))?,
+                discount_amount: discount
+                    .and_then(|index| parse_yen_amount(field(index)))
+                    .unwrap_or(0),
+                mileage_points: mileage.and_then(|index| parse_yen_amount(field(index))),
+                vehicle_number: field(vehicle_number).to_string(),
+                card_number: field(card_number).to_string(),
+            })
+        })
+        .collect()
+}
+
+/// 利用日（`2024/01/05` または `24/01/05`）をパース
+fn parse_usage_date(text: &str) -> Option<NaiveDate> {
+    let text = text.trim();
+    // %Y は2桁の年も受け付けてしまうため桁数で書式を選ぶ
+    let format = if text.len() == 8 {
+        "%y/%m/%d"
+    } else {
+        "%Y/%m/%d"
+    };
+    NaiveDate::parse_from_str(text, format).ok()
+}
+
+/// 明細の金額表記を円単位の整数に変換
+///
+/// 桁区切りのカンマ（全角含む）、全角数字、`円` / `¥` / `￥` の記号（Shift_JIS 由来の `\` を含む）、前後の空白を許容する。
+/// 負数（`-` / `－` / `▲` 始まり）にも対応する。数字を含まない場合は `None` を返す。
+pub fn parse_yen_amount(text: &str) -> Option<i64> {
+    let mut negative = false;
+    let mut digits = String::new();
+    for c in text.trim().chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            '０'..='９' => digits.extend(char::from_digit(c as u32 - '０' as u32, 10)),
+            ',' | '，' | '円' | '¥' | '￥' | '\\' => {}
+            '-' | '－' | '▲' if digits.is_empty() => negative = true,
+            c if c.is_whitespace() => {}
+            _ => return None,
+        }
+    }
+    let amount: i64 = digits.parse().ok()?;
+    Some(if negative { -amount } else { amount })
+}
+
+/// 利用明細の集計
+///
+/// `Vec<EtcUsageRecord>` / スライスに対して合計・車両別・月別の料金を求める。
+pub trait EtcUsageSummary {
+    /// 料金の合計（円）
+    fn total_amount(&self) -> i64;
+    /// 車両番号ごとの料金合計（円）
+    fn by_vehicle(&self) -> HashMap<String, i64>;
+    /// 利用年月 (年, 月) ごとの料金合計（円）
+    fn by_month(&self) -> BTreeMap<(i32, u32), i64>;
+}
+
+impl EtcUsageSummary for [EtcUsageRecord] {
+    fn total_amount(&self) -> i64 {
+        self.iter().map(|record| record.amount).sum()
+    }
+
+    fn by_vehicle(&self) -> HashMap<String, i64> {
+        let mut totals = HashMap::new();
+        for record in self {
+            *totals
+                .entry(record.vehicle_number.trim().to_string())
+                .or_insert(0) += record.amount;
+        }
+        totals
+    }
+
+    fn by_month(&self) -> BTreeMap<(i32, u32), i64> {
+        let mut totals = BTreeMap::new();
+        for record in self {
+            *totals
+                .entry((record.date.year(), record.date.month()))
+                .or_insert(0) += record.amount;
+        }
+        totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(day: u32, exit_ic: &str, amount: i64) -> EtcUsageRecord {
+        EtcUsageRecord {
+            date: NaiveDate::from_ymd_opt(2024, 4, day).unwrap(),
+            entry_ic: "東京".to_string(),
+            exit_ic: exit_ic.to_string(),
+            amount,
+            discount_amount: 0,
+            mileage_points: None,
+            vehicle_number: "品川100あ1234".to_string(),
+            card_number: "1234-5678-9012-3456".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_record_key_stable() {
+        let a = record(1, "横浜", 1320);
+        assert_eq!(a.record_key(), a.clone().record_key());
+        assert_ne!(a.record_key(), record(1, "横浜", 1330).record_key());
+        assert_ne!(a.record_key(), record(2, "横浜", 1320).record_key());
+
+        // 車両番号はキーに含めない
+        let b = EtcUsageRecord {
+            vehicle_number: "別車両".to_string(),
+            ..a.clone()
+        };
+        assert_eq!(a.record_key(), b.record_key());
+    }
+
+    #[test]
+    fn test_records_hash() {
+        let records = vec![record(1, "横浜", 1320), record(2, "厚木", 2100)];
+        let hash = records_hash(&records);
+
+        let reordered = vec![
+            record(2, " 厚木 ", 2100),
+            EtcUsageRecord {
+                vehicle_number: " 品川100あ1234\t".to_string(),
+                ..record(1, "横浜　", 1320)
+            },
+        ];
+        assert_eq!(
+            compare_records_hash(&reordered, Some(&hash)),
+            RecordsChange::Unchanged
+        );
+
+        let added = vec![
+            record(1, "横浜", 1320),
+            record(2, "厚木", 2100),
+            record(3, "横浜", 1320),
+        ];
+        assert!(matches!(
+            compare_records_hash(&added, Some(&hash)),
+            RecordsChange::Changed { hash: new_hash } if new_hash != hash
+        ));
+        assert_eq!(
+            compare_records_hash(&records, None),
+            RecordsChange::Changed { hash }
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut records = vec![
+            record(1, "横浜", 1320),
+            record(2, "厚木", 2100),
+            EtcUsageRecord {
+                vehicle_number: "品川100あ5678".to_string(),
+                ..record(3, "横浜", 1000)
+            },
+        ];
+        records.push(EtcUsageRecord {
+            date: NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
+            ..record(1, "横浜", 500)
+        });
+
+        assert_eq!(records.total_amount(), 4920);
+        assert_eq!(records.by_vehicle()["品川100あ1234"], 3920);
+        assert_eq!(records.by_vehicle()["品川100あ5678"], 1000);
+        assert_eq!(
+            records.by_month().into_iter().collect::<Vec<_>>(),
+            vec![((2024, 4), 4420), ((2024, 5), 500)]
+        );
+        assert_eq!(Vec::<EtcUsageRecord>::new().total_amount(), 0);
+    }
+
+    #[test]
+    fn test_parse_yen_amount() {
+        assert_eq!(parse_yen_amount("1320"), Some(1320));
+        assert_eq!(parse_yen_amount(" 1,320 "), Some(1320));
+        assert_eq!(parse_yen_amount("１，３２０円"), Some(1320));
+        assert_eq!(parse_yen_amount("￥12,345"), Some(12345));
+        assert_eq!(parse_yen_amount("-100"), Some(-100));
+        assert_eq!(parse_yen_amount("▲１００"), Some(-100));
+        assert_eq!(parse_yen_amount(""), None);
+        assert_eq!(parse_yen_amount("無料"), None);
+    }
+
+    #[test]
+    fn test_parse_usage_csv() {
+        let header = super::super::EXPECTED_CSV_COLUMNS.join(",");
+        let csv = format!(
+            "{},マイレージ\n2024/04/01,08:00,2024/04/01,09:10,東京,横浜,1650,330,1320,普通車,品川100あ1234,1234-5678-9012-3456,,13\n",
+            header
+        );
+        let records = parse_usage_csv(csv.as_bytes()).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].amount, 1320);
+        assert_eq!(records[0].discount_amount, 330);
+        assert_eq!(records[0].gross_amount(), 1650);
+        assert_eq!(records[0].mileage_points, Some(13));
+
+        // 割引額・マイレージの列がない古い形式
+        let csv =
+            "利用年月日（自）,利用ＩＣ（自）,利用ＩＣ（至）,通行料金,車両番号,ＥＴＣカード番号\n\
+                   24/04/01,東京,横浜,\"1,320\",品川100あ1234,1234-5678-9012-3456\n";
+        let records = parse_usage_csv(csv.as_bytes()).unwrap();
+        assert_eq!(records, vec![record(1, "横浜", 1320)]);
+
+        let err = parse_usage_csv("利用年月日（自）,車両番号\n".as_bytes()).unwrap_err();
+        assert!(matches!(err, ScraperError::CsvSchema(_)));
+    }
+
+    #[test]
+    fn test_filter_new_records() {
+        let seen: HashSet<String> = [record(1, "横浜", 1320).record_key()].into();
+        let records = vec![
+            record(1, "横浜", 1320),
+            record(2, "横浜", 1320),
+            record(2, "横浜", 1320),
+            record(3, "厚木", 2100),
+        ];
+
+        // 同じ日の同一区間の利用2件はどちらも残す
+        let new = filter_new_records(records.clone(), &seen, None);
+        assert_eq!(
+            new,
+            vec![
+                record(2, "横浜", 1320),
+                record(2, "横浜", 1320),
+                record(3, "厚木", 2100)
+            ]
+        );
+
+        let since = NaiveDate::from_ymd_opt(2024, 4, 3);
+        let new = filter_new_records(records, &seen, since);
+        assert_eq!(new, vec![record(3, "厚木", 2100)]);
+    }
+}
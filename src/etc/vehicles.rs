@@ -0,0 +1,115 @@
+//! ETCカード・登録車両一覧の解析
+//!
+//! カード情報ページの一覧表（ヘッダー行 + データ行のセル文字列）を
+//! [`RegisteredVehicle`] に変換する。列の並びはヘッダーの文言で判定する。
+
+use chrono::NaiveDate;
+
+use crate::error::ScraperError;
+
+use super::types::RegisteredVehicle;
+
+/// カード番号列のヘッダー
+const CARD_NUMBER_HEADER: &str = "カード番号";
+/// 車両番号列のヘッダー
+const VEHICLE_NUMBER_HEADER: &str = "車両番号";
+/// 発行日列のヘッダー（「カード発行日」「発行年月日」等）
+const ISSUE_DATE_HEADER: &str = "発行";
+
+/// 全角数字・全角ハイフンを半角に変換し、前後の空白を除去
+fn normalize_cell(text: &str) -> String {
+    text.trim()
+        .chars()
+        .map(|c| match c {
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap_or(c),
+            '－' | 'ー' | '―' => '-',
+            _ => c,
+        })
+        .collect()
+}
+
+/// 発行日の表記（`2024/01/15` / `2024-01-15` / `2024年1月15日`）をパース
+fn parse_issue_date(text: &str) -> Option<NaiveDate> {
+    let text = normalize_cell(text);
+    ["%Y/%m/%d", "%Y-%m-%d", "%Y年%m月%d日"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(&text, format).ok())
+}
+
+/// 一覧表の行（セル文字列）を登録車両に変換
+///
+/// カード番号・車両番号の列を含む行をヘッダーとし、以降のカード番号が空でない行を対象とする。
+/// 発行日の列がない、またはパースできない場合は `issue_date` を `None` にする。
+pub(crate) fn parse_registered_vehicles(
+    rows: &[Vec<String>],
+) -> Result<Vec<RegisteredVehicle>, ScraperError> {
+    let column = |header: &[String], name: &str| header.iter().position(|cell| cell.contains(name));
+
+    let (header_index, card_col, vehicle_col) = rows
+        .iter()
+        .enumerate()
+        .find_map(|(i, row)| {
+            Some((
+                i,
+                column(row, CARD_NUMBER_HEADER)?,
+                column(row, VEHICLE_NUMBER_HEADER)?,
+            ))
+        })
+        .ok_or_else(|| {
+            ScraperError::Extraction(format!(
+                "カード一覧のヘッダー（{} / {}）が見つかりません",
+                CARD_NUMBER_HEADER, VEHICLE_NUMBER_HEADER
+            ))
+        })?;
+    let date_col = column(&rows[header_index], ISSUE_DATE_HEADER);
+
+    let vehicles = rows[header_index + 1..]
+        .iter()
+        .filter_map(|row| {
+            let card_number = normalize_cell(row.get(card_col)?);
+            if card_number.is_empty() {
+                return None;
+            }
+            Some(RegisteredVehicle {
+                card_number,
+                vehicle_number: row
+                    .get(vehicle_col)
+                    .map(|cell| cell.trim().to_string())
+                    .unwrap_or_default(),
+                issue_date: date_col
+                    .and_then(|col| row.get(col))
+                    .and_then(|cell| parse_issue_date(cell)),
+            })
+        })
+        .collect();
+    Ok(vehicles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_registered_vehicles() {
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/etc_card_list.json"
+        ));
+        let rows: Vec<Vec<String>> = serde_json::from_str(fixture).unwrap();
+
+        let vehicles = parse_registered_vehicles(&rows).unwrap();
+        assert_eq!(vehicles.len(), 3);
+        assert_eq!(vehicles[0].card_number, "1234-5678-9012-3456");
+        assert_eq!(vehicles[0].vehicle_number, "品川100あ1234");
+        assert_eq!(vehicles[0].issue_date, NaiveDate::from_ymd_opt(2023, 4, 1));
+        assert_eq!(vehicles[1].card_number, "1234-5678-9012-9999");
+        assert_eq!(vehicles[1].issue_date, NaiveDate::from_ymd_opt(2024, 1, 15));
+        assert_eq!(vehicles[2].vehicle_number, "");
+        assert_eq!(vehicles[2].issue_date, None);
+
+        assert!(matches!(
+            parse_registered_vehicles(&rows[1..]),
+            Err(ScraperError::Extraction(_))
+        ));
+    }
+}
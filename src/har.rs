@@ -0,0 +1,442 @@
+//! ネットワークログのHAR (HTTP Archive 1.2) 形式での記録
+//!
+//! CDPの `Network` イベントを購読してリクエスト／レスポンスを記録し、
+//! サイト側の障害（CSVリンクの500等）を事後に調査できるようHARファイルに書き出す。
+//! レスポンスボディは記録しない。
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use chromiumoxide::cdp::browser_protocol::network::{
+    EnableParams as NetworkEnableParams, EventLoadingFailed, EventLoadingFinished,
+    EventRequestWillBeSent, EventResponseReceived, Response,
+};
+use chromiumoxide::Page;
+use futures::stream::{self, BoxStream, StreamExt};
+use serde_json::{json, Value};
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+
+use crate::browser::spawn_on;
+use crate::error::ScraperError;
+
+/// 記録中のネットワークログ
+///
+/// CDPイベントの時刻（`timestamp` は単調時刻、`wallTime` はUNIX時刻、いずれも秒）から
+/// HARのエントリを組み立てる。
+#[derive(Debug, Default)]
+struct HarLog {
+    entries: Vec<HarEntry>,
+    /// requestId → 未完了エントリの `entries` 内の位置
+    in_flight: HashMap<String, usize>,
+}
+
+#[derive(Debug)]
+struct HarEntry {
+    wall_time: f64,
+    start: f64,
+    end: Option<f64>,
+    method: String,
+    url: String,
+    request_headers: Value,
+    response: Option<HarResponse>,
+    body_size: Option<f64>,
+    error: Option<String>,
+}
+
+#[derive(Debug)]
+struct HarResponse {
+    status: i64,
+    status_text: String,
+    http_version: String,
+    headers: Value,
+    mime_type: String,
+    redirect_url: String,
+}
+
+impl HarResponse {
+    fn from_cdp(response: &Response) -> Self {
+        Self {
+            status: response.status,
+            status_text: response.status_text.clone(),
+            http_version: response.protocol.clone().unwrap_or_default(),
+            headers: response.headers.inner().clone(),
+            mime_type: response.mime_type.clone(),
+            redirect_url: String::new(),
+        }
+    }
+}
+
+impl HarLog {
+    fn on_request(
+        &mut self,
+        request_id: &str,
+        method: &str,
+        url: &str,
+        headers: &Value,
+        wall_time: f64,
+        timestamp: f64,
+    ) {
+        self.entries.push(HarEntry {
+            wall_time,
+            start: timestamp,
+            end: None,
+            method: method.to_string(),
+            url: url.to_string(),
+            request_headers: headers.clone(),
+            response: None,
+            body_size: None,
+            error: None,
+        });
+        self.in_flight
+            .insert(request_id.to_string(), self.entries.len() - 1);
+    }
+
+    /// リダイレクト時は同じ requestId で新しいリクエストが始まるため、前のエントリを完了させる
+    fn on_redirect(
+        &mut self,
+        request_id: &str,
+        mut response: HarResponse,
+        timestamp: f64,
+        location: &str,
+    ) {
+        if let Some(index) = self.in_flight.remove(request_id) {
+            let entry = &mut self.entries[index];
+            response.redirect_url = location.to_string();
+            entry.response = Some(response);
+            entry.end = Some(timestamp);
+            entry.body_size = Some(0.0);
+        }
+    }
+
+    fn on_response(&mut self, request_id: &str, response: HarResponse) {
+        if let Some(&index) = self.in_flight.get(request_id) {
+            self.entries[index].response = Some(response);
+        }
+    }
+
+    fn on_finished(&mut self, request_id: &str, timestamp: f64, encoded_data_length: f64) {
+        if let Some(index) = self.in_flight.remove(request_id) {
+            let entry = &mut self.entries[index];
+            entry.end = Some(timestamp);
+            entry.body_size = Some(encoded_data_length);
+        }
+    }
+
+    fn on_failed(&mut self, request_id: &str, timestamp: f64, error_text: &str) {
+        if let Some(index) = self.in_flight.remove(request_id) {
+            let entry = &mut self.entries[index];
+            entry.end = Some(timestamp);
+            entry.error = Some(error_text.to_string());
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let entries: Vec<Value> = self.entries.iter().map(HarEntry::to_json).collect();
+        json!({
+            "log": {
+                "version": "1.2",
+                "creator": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+                "pages": [],
+                "entries": entries,
+            }
+        })
+    }
+}
+
+impl HarEntry {
+    fn to_json(&self) -> Value {
+        let started = chrono::DateTime::from_timestamp_millis((self.wall_time * 1000.0) as i64)
+            .unwrap_or_default()
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        // 完了していないリクエストは -1（HARの「不明」）
+        let time = self
+            .end
+            .map_or(-1.0, |end| ((end - self.start) * 1000.0).max(0.0));
+
+        let response = match self.response {
+            Some(ref response) => json!({
+                "status": response.status,
+                "statusText": response.status_text,
+                "httpVersion": response.http_version,
+                "headers": har_headers(&response.headers),
+                "cookies": [],
+                "content": {
+                    "size": self.body_size.unwrap_or(-1.0),
+                    "mimeType": response.mime_type,
+                },
+                "redirectURL": response.redirect_url,
+                "headersSize": -1,
+                "bodySize": self.body_size.unwrap_or(-1.0),
+            }),
+            // レスポンスなし（通信エラー・未完了）は status 0
+            None => json!({
+                "status": 0,
+                "statusText": "",
+                "httpVersion": "",
+                "headers": [],
+                "cookies": [],
+                "content": { "size": 0, "mimeType": "" },
+                "redirectURL": "",
+                "headersSize": -1,
+                "bodySize": -1,
+            }),
+        };
+
+        let mut entry = json!({
+            "startedDateTime": started,
+            "time": time,
+            "request": {
+                "method": self.method,
+                "url": self.url,
+                "httpVersion": self.response.as_ref().map_or("", |r| r.http_version.as_str()),
+                "headers": har_headers(&self.request_headers),
+                "queryString": query_string(&self.url),
+                "cookies": [],
+                "headersSize": -1,
+                "bodySize": -1,
+            },
+            "response": response,
+            "cache": {},
+            "timings": { "send": 0, "wait": time, "receive": 0 },
+        });
+        if let Some(ref error) = self.error {
+            entry["_error"] = Value::String(error.clone());
+        }
+        entry
+    }
+}
+
+/// CDPのヘッダー（オブジェクト）をHARの `[{name, value}]` 形式に変換
+fn har_headers(headers: &Value) -> Vec<Value> {
+    headers
+        .as_object()
+        .map(|headers| {
+            headers
+                .iter()
+                .map(|(name, value)| {
+                    let value = value
+                        .as_str()
+                        .map_or_else(|| value.to_string(), str::to_string);
+                    json!({ "name": name, "value": value })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// URLのクエリ文字列をHARの `[{name, value}]` 形式に分解（デコードはしない）
+fn query_string(url: &str) -> Vec<Value> {
+    let Some((_, query)) = url.split_once('?') else {
+        return Vec::new();
+    };
+    let query = query.split('#').next().unwrap_or_default();
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            json!({ "name": name, "value": value })
+        })
+        .collect()
+}
+
+enum NetworkEvent {
+    Request(Arc<EventRequestWillBeSent>),
+    Response(Arc<EventResponseReceived>),
+    Finished(Arc<EventLoadingFinished>),
+    Failed(Arc<EventLoadingFailed>),
+}
+
+/// ページのネットワークログの記録
+///
+/// ドロップ時に購読タスクを停止する。
+pub(crate) struct HarRecorder {
+    log: Arc<Mutex<HarLog>>,
+    task: JoinHandle<()>,
+}
+
+impl HarRecorder {
+    /// ページのネットワークログの記録を開始（`runtime` は [`spawn_on`] を参照）
+    pub(crate) async fn start(page: &Page, runtime: Option<&Handle>) -> Result<Self, ScraperError> {
+        let listener_error = |e: chromiumoxide::error::CdpError| {
+            ScraperError::BrowserInit(format!("ネットワーク購読エラー: {}", e))
+        };
+
+        let requests = page
+            .event_listener::<EventRequestWillBeSent>()
+            .await
+            .map_err(listener_error)?;
+        let responses = page
+            .event_listener::<EventResponseReceived>()
+            .await
+            .map_err(listener_error)?;
+        let finished = page
+            .event_listener::<EventLoadingFinished>()
+            .await
+            .map_err(listener_error)?;
+        let failed = page
+            .event_listener::<EventLoadingFailed>()
+            .await
+            .map_err(listener_error)?;
+        page.execute(NetworkEnableParams::default())
+            .await
+            .map_err(|e| ScraperError::BrowserInit(format!("Network.enable エラー: {}", e)))?;
+
+        // 到着順に処理するため1本のストリームにまとめる
+        let streams: Vec<BoxStream<'static, NetworkEvent>> = vec![
+            requests.map(NetworkEvent::Request).boxed(),
+            responses.map(NetworkEvent::Response).boxed(),
+            finished.map(NetworkEvent::Finished).boxed(),
+            failed.map(NetworkEvent::Failed).boxed(),
+        ];
+        let mut events = stream::select_all(streams);
+
+        let log = Arc::new(Mutex::new(HarLog::default()));
+        let task_log = Arc::clone(&log);
+        let task = spawn_on(runtime, async move {
+            while let Some(event) = events.next().await {
+                let mut log = task_log.lock().unwrap_or_else(|e| e.into_inner());
+                match event {
+                    NetworkEvent::Request(event) => {
+                        let request_id = event.request_id.inner();
+                        let timestamp = *event.timestamp.inner();
+                        if let Some(ref redirect) = event.redirect_response {
+                            let response = HarResponse::from_cdp(redirect);
+                            log.on_redirect(request_id, response, timestamp, &event.request.url);
+                        }
+                        log.on_request(
+                            request_id,
+                            &event.request.method,
+                            &event.request.url,
+                            event.request.headers.inner(),
+                            *event.wall_time.inner(),
+                            timestamp,
+                        );
+                    }
+                    NetworkEvent::Response(event) => {
+                        log.on_response(
+                            event.request_id.inner(),
+                            HarResponse::from_cdp(&event.response),
+                        );
+                    }
+                    NetworkEvent::Finished(event) => {
+                        log.on_finished(
+                            event.request_id.inner(),
+                            *event.timestamp.inner(),
+                            event.encoded_data_length,
+                        );
+                    }
+                    NetworkEvent::Failed(event) => {
+                        log.on_failed(
+                            event.request_id.inner(),
+                            *event.timestamp.inner(),
+                            &event.error_text,
+                        );
+                    }
+                }
+            }
+        });
+
+        Ok(Self { log, task })
+    }
+
+    /// これまでの記録をHARファイルとして書き出す
+    pub(crate) fn write(&self, path: &Path) -> Result<(), ScraperError> {
+        let har = self.log.lock().unwrap_or_else(|e| e.into_inner()).to_json();
+        let json =
+            serde_json::to_vec_pretty(&har).map_err(|e| ScraperError::Json(e.to_string()))?;
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+impl Drop for HarRecorder {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: i64) -> HarResponse {
+        HarResponse {
+            status,
+            status_text: String::new(),
+            http_version: "http/1.1".to_string(),
+            headers: json!({ "Content-Type": "text/csv" }),
+            mime_type: "text/csv".to_string(),
+            redirect_url: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_har_log_entries() {
+        let mut log = HarLog::default();
+        let headers = json!({ "Accept": "*/*" });
+
+        // リダイレクト → 500
+        log.on_request(
+            "1",
+            "GET",
+            "https://example.com/a?x=1&y",
+            &headers,
+            1_700_000_000.0,
+            10.0,
+        );
+        log.on_redirect("1", response(302), 10.1, "https://example.com/b");
+        log.on_request(
+            "1",
+            "GET",
+            "https://example.com/b",
+            &headers,
+            1_700_000_000.1,
+            10.1,
+        );
+        log.on_response("1", response(500));
+        log.on_finished("1", 10.35, 128.0);
+
+        // 通信エラー
+        log.on_request(
+            "2",
+            "POST",
+            "https://example.com/c",
+            &headers,
+            1_700_000_001.0,
+            11.0,
+        );
+        log.on_failed("2", 11.5, "net::ERR_CONNECTION_RESET");
+
+        let har = log.to_json();
+        assert_eq!(har["log"]["version"], "1.2");
+        let entries = har["log"]["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(entries[0]["response"]["status"], 302);
+        assert_eq!(
+            entries[0]["response"]["redirectURL"],
+            "https://example.com/b"
+        );
+        assert_eq!(entries[0]["request"]["queryString"][0]["name"], "x");
+        assert_eq!(entries[0]["request"]["queryString"][1]["value"], "");
+        assert_eq!(entries[0]["startedDateTime"], "2023-11-14T22:13:20.000Z");
+
+        assert_eq!(entries[1]["response"]["status"], 500);
+        assert_eq!(entries[1]["response"]["bodySize"], 128.0);
+        assert_eq!(entries[1]["time"].as_f64().unwrap().round(), 250.0);
+        assert_eq!(entries[1]["request"]["headers"][0]["name"], "Accept");
+
+        assert_eq!(entries[2]["response"]["status"], 0);
+        assert_eq!(entries[2]["_error"], "net::ERR_CONNECTION_RESET");
+        assert!(log.in_flight.is_empty());
+    }
+}
@@ -18,7 +18,9 @@
 //!         .with_headless(false);
 //!
 //!     let result = service.call(request).await.unwrap();
-//!     println!("CSV downloaded: {:?}", result.csv_path);
+//!     if let Some(etc) = result.as_etc() {
+//!         println!("CSV downloaded: {:?}", etc.csv_path);
+//!     }
 //! }
 //! ```
 //!
@@ -42,23 +44,55 @@
 //!     println!("Vehicles: {:?}", result.vehicles.len());
 //! }
 //! ```
+//!
+//! # ランタイム
+//!
+//! ブラウザ（CDP）のイベントハンドラーは `tokio` のタスクとしてバックグラウンドで動作し、
+//! スクレイパーの処理と並行して進む必要がある。既定では呼び出し元のランタイムで起動するため、
+//! マルチスレッドのランタイム（`#[tokio::main]` の既定）で実行するか、
+//! `ScraperConfig::with_runtime` / `DtakologConfig::runtime` でタスクを起動するランタイムを指定する。
+//! ハンドラータスクのハンドルは `take_handler_task` で取り出して監視・join できる。
 
+mod browser;
+#[cfg(feature = "bundle")]
+mod bundle;
+mod har;
+mod telemetry;
 pub mod config;
 pub mod dtakolog;
 pub mod error;
 pub mod etc;
+pub mod processor;
 pub mod service;
 pub mod traits;
 
 // 主要な型をリエクスポート
-pub use config::ScraperConfig;
-pub use error::ScraperError;
-pub use etc::EtcScraper;
-pub use service::{ScrapeRequest, ScrapeResult, ScraperService};
+#[cfg(feature = "bundle")]
+pub use bundle::{bundle_result, bundle_result_with_files, BundleEntry, BundleManifest};
+pub use config::{
+    HeadlessMode, LinkMatcher, LoginStrategy, NavigateHook, OtpProvider, RetryBudget, RetryPolicy,
+    ScraperConfig,
+};
+pub use error::{ErrorCategory, ScraperError};
+#[cfg(feature = "parquet")]
+pub use etc::etc_csv_to_parquet;
+pub use etc::{
+    check_browser_available, compare_records_hash, detect_csv_encoding, filter_new_records,
+    parse_usage_csv, parse_yen_amount, records_hash, run_batch, validate_csv_header, Account,
+    AccountStore, BatchRunner, CsvEncoding, DownloadKind, DownloadOption, EtcScraper,
+    EtcUsageRecord, EtcUsageSummary, PageLink, RecordsChange, RegisteredVehicle, ReportType,
+    ResultSummary,
+};
+pub use processor::{ParseCsvProcessor, ResultProcessor};
+pub use service::{
+    AuditOutcome, AuditRecord, EtcScrapeResult, ScrapeRequest, ScrapeResult, ScrapeTarget,
+    ScraperFactory, ScraperService,
+};
 pub use traits::Scraper;
 
 // Dtakolog 関連の型もリエクスポート
 pub use dtakolog::{
-    DtakologConfig, DtakologData, DtakologResult, DtakologScraper, GrpcResponse, VehicleData,
+    flatten_records, DtakologConfig, DtakologData, DtakologResult, DtakologScraper,
+    DvrNotification, GrpcResponse, PendingDownload, RequestedDownloads, SendSummary, VehicleData,
     VideoNotificationResult,
 };
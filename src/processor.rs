@@ -0,0 +1,43 @@
+//! スクレイピング結果の後処理パイプライン
+//!
+//! `ScraperService::with_processor` で登録した [`ResultProcessor`] を、
+//! ダウンロード完了後に登録順に適用する（絞り込み・変換・アップロード等）。
+
+use crate::error::ScraperError;
+use crate::service::ScrapeResult;
+
+/// スクレイピング結果の後処理
+///
+/// エラーを返した場合は以降の後処理を行わず、リクエスト全体を
+/// `ScraperError::Processor`（後処理の名前付き）で失敗させる。
+pub trait ResultProcessor: std::fmt::Debug + Send + Sync {
+    /// エラーメッセージに含める名前
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// 結果を変換して返す
+    fn process(&self, result: ScrapeResult) -> Result<ScrapeResult, ScraperError>;
+}
+
+/// ETCの明細CSVをパースして `EtcScrapeResult::records` に設定する
+///
+/// Dtakologの結果はそのまま返す。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseCsvProcessor;
+
+impl ResultProcessor for ParseCsvProcessor {
+    fn name(&self) -> &str {
+        "ParseCsvProcessor"
+    }
+
+    fn process(&self, result: ScrapeResult) -> Result<ScrapeResult, ScraperError> {
+        match result {
+            ScrapeResult::Etc(mut etc) => {
+                etc.records = Some(crate::etc::parse_usage_csv(&etc.csv_content)?);
+                Ok(ScrapeResult::Etc(etc))
+            }
+            other => Ok(other),
+        }
+    }
+}
@@ -1,36 +1,128 @@
+use std::collections::HashSet;
 use std::future::Future;
 use std::path::PathBuf;
 use std::pin::Pin;
+use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
 use std::time::Duration;
 
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc;
 use tower::Service;
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::config::ScraperConfig;
-use crate::error::ScraperError;
-use crate::etc::EtcScraper;
+use crate::config::{LoginStrategy, RetryBudget, ScraperConfig};
+use crate::dtakolog::{DtakologConfig, DtakologResult, DtakologScraper, RequestedDownloads};
+use crate::error::{ErrorCategory, ScraperError};
+use crate::etc::{CsvEncoding, EtcScraper, EtcUsageRecord};
+use crate::processor::ResultProcessor;
 use crate::traits::Scraper;
 
+/// スクレイピング対象サイト
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum ScrapeTarget {
+    /// ETC利用照会サービス（CSVダウンロード）
+    #[default]
+    Etc,
+    /// Dtakolog（Vehicleデータ取得）
+    Dtakolog,
+}
+
 /// スクレイピングリクエスト
-#[derive(Debug, Clone)]
+///
+/// `Debug` ではパスワードを伏せ字にする。
+#[derive(Clone)]
 pub struct ScrapeRequest {
+    pub target: ScrapeTarget,
     pub user_id: String,
     pub password: String,
     pub download_path: PathBuf,
+    /// ヘッドレスモード（ETC・Dtakolog共通。特定アカウントのみ画面表示で実行する場合に指定）
     pub headless: bool,
+    /// `download_path/{user_id}/` にダウンロードする（ETCのみ使用）
+    pub namespace_by_user: bool,
+    /// 会社ID（Dtakologのみ使用）
+    pub comp_id: String,
+    /// ログイン済みセッションのクッキー (name, value, domain, path)（ETCのみ使用）
+    pub session_cookies: Vec<(String, String, String, String)>,
+    /// クッキー復元後に直接開く検索画面のURL（ETCのみ使用）
+    pub session_url: Option<String>,
+    /// ダウンロードファイルの最大サイズ（バイト、ETCのみ使用）
+    pub max_download_size: Option<u64>,
+    /// 起動済みブラウザのCDPエンドポイント（ETC・Dtakolog共通。指定時は起動オプションを無視）
+    pub cdp_endpoint: Option<String>,
+    /// テスト専用: ブラウザを使わずに `dir/{user_id}.csv` を返す（ETCのみ使用）
+    pub fixture_dir: Option<PathBuf>,
+    /// 冪等キー（同じキーのリクエストが実行中なら `ScraperError::DuplicateInProgress`）
+    pub idempotency_key: Option<String>,
+    /// 保存済みセッションの使い方（ETC・Dtakolog共通）
+    pub login_strategy: LoginStrategy,
+}
+
+impl std::fmt::Debug for ScrapeRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScrapeRequest")
+            .field("target", &self.target)
+            .field("user_id", &self.user_id)
+            .field("password", &"***")
+            .field("download_path", &self.download_path)
+            .field("headless", &self.headless)
+            .field("namespace_by_user", &self.namespace_by_user)
+            .field("comp_id", &self.comp_id)
+            .field("session_cookies", &self.session_cookies.len())
+            .field("session_url", &self.session_url.as_ref().map(|_| "***"))
+            .field("max_download_size", &self.max_download_size)
+            .field("cdp_endpoint", &self.cdp_endpoint)
+            .field("fixture_dir", &self.fixture_dir)
+            .field("idempotency_key", &self.idempotency_key)
+            .field("login_strategy", &self.login_strategy)
+            .finish()
+    }
 }
 
 impl ScrapeRequest {
     pub fn new(user_id: impl Into<String>, password: impl Into<String>) -> Self {
         Self {
+            target: ScrapeTarget::default(),
             user_id: user_id.into(),
             password: password.into(),
             download_path: PathBuf::from("./downloads"),
             headless: true,
+            namespace_by_user: false,
+            comp_id: String::new(),
+            session_cookies: Vec::new(),
+            session_url: None,
+            max_download_size: None,
+            cdp_endpoint: None,
+            fixture_dir: None,
+            idempotency_key: None,
+            login_strategy: LoginStrategy::default(),
         }
     }
 
+    /// Dtakolog向けリクエストを作成
+    pub fn dtakolog(
+        comp_id: impl Into<String>,
+        user_name: impl Into<String>,
+        user_pass: impl Into<String>,
+    ) -> Self {
+        Self::new(user_name, user_pass)
+            .with_target(ScrapeTarget::Dtakolog)
+            .with_comp_id(comp_id)
+    }
+
+    pub fn with_target(mut self, target: ScrapeTarget) -> Self {
+        self.target = target;
+        self
+    }
+
+    pub fn with_comp_id(mut self, comp_id: impl Into<String>) -> Self {
+        self.comp_id = comp_id.into();
+        self
+    }
+
     pub fn with_download_path(mut self, path: impl Into<PathBuf>) -> Self {
         self.download_path = path.into();
         self
@@ -40,6 +132,52 @@ impl ScrapeRequest {
         self.headless = headless;
         self
     }
+
+    pub fn with_namespace_by_user(mut self, namespace_by_user: bool) -> Self {
+        self.namespace_by_user = namespace_by_user;
+        self
+    }
+
+    /// テスト専用: ブラウザを使わずにフィクスチャのCSVを返す（ETCのみ）
+    pub fn with_fixture_mode(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.fixture_dir = Some(dir.into());
+        self
+    }
+
+    pub fn with_cdp_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.cdp_endpoint = Some(endpoint.into());
+        self
+    }
+
+    pub fn with_login_strategy(mut self, strategy: LoginStrategy) -> Self {
+        self.login_strategy = strategy;
+        self
+    }
+
+    /// 冪等キーを指定（ジョブキューの重複配信で同じアカウントを同時に実行しない）
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    pub fn with_max_download_size(mut self, max_bytes: u64) -> Self {
+        self.max_download_size = Some(max_bytes);
+        self
+    }
+
+    /// ログイン済みセッションのクッキーを指定（ETCのみ、ログイン操作を省略）
+    ///
+    /// クッキーが無効な場合はパスワードが指定されていれば通常ログインにフォールバックし、
+    /// 指定されていなければ `ScraperError::Session` を返す。
+    pub fn with_cookies(
+        mut self,
+        cookies: Vec<(String, String, String, String)>,
+        session_url: impl Into<String>,
+    ) -> Self {
+        self.session_cookies = cookies;
+        self.session_url = Some(session_url.into());
+        self
+    }
 }
 
 impl From<ScrapeRequest> for ScraperConfig {
@@ -49,39 +187,308 @@ impl From<ScrapeRequest> for ScraperConfig {
             password: req.password,
             download_path: req.download_path,
             headless: req.headless,
+            namespace_by_user: req.namespace_by_user,
+            session_cookies: req.session_cookies,
+            session_url: req.session_url,
+            max_download_size: req.max_download_size,
+            cdp_endpoint: req.cdp_endpoint,
+            fixture_dir: req.fixture_dir,
+            login_strategy: req.login_strategy,
             timeout: Duration::from_secs(60),
-            chrome_path: std::env::var("CHROME_PATH").ok().map(std::path::PathBuf::from),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<ScrapeRequest> for DtakologConfig {
+    fn from(req: ScrapeRequest) -> Self {
+        DtakologConfig {
+            comp_id: req.comp_id,
+            user_name: req.user_id,
+            user_pass: req.password,
+            headless: req.headless,
+            cdp_endpoint: req.cdp_endpoint,
+            login_strategy: req.login_strategy,
+            ..Default::default()
         }
     }
 }
 
 /// スクレイピング結果
 #[derive(Debug)]
-pub struct ScrapeResult {
+pub enum ScrapeResult {
+    Etc(EtcScrapeResult),
+    Dtakolog(Box<DtakologResult>),
+}
+
+impl ScrapeResult {
+    /// ETC結果を取得
+    pub fn as_etc(&self) -> Option<&EtcScrapeResult> {
+        match self {
+            ScrapeResult::Etc(result) => Some(result),
+            _ => None,
+        }
+    }
+
+    /// ETCのダウンロードファイルのサイズ（バイト）
+    pub fn size_bytes(&self) -> Option<usize> {
+        self.as_etc().map(EtcScrapeResult::size_bytes)
+    }
+
+    /// ETCのダウンロード内容の SHA-256（16進）
+    pub fn content_hash(&self) -> Option<String> {
+        self.as_etc().map(|etc| etc.content_hash.clone())
+    }
+
+    /// ETCのCSVを BOM 付き UTF-8 に変換して取得（Excel向け）
+    pub fn csv_utf8_with_bom(&self) -> Option<Vec<u8>> {
+        self.as_etc().map(EtcScrapeResult::csv_utf8_with_bom)
+    }
+
+    /// Dtakolog結果を取得
+    pub fn as_dtakolog(&self) -> Option<&DtakologResult> {
+        match self {
+            ScrapeResult::Dtakolog(result) => Some(result.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// スクレイピングの結果区分（監査ログ用）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", content = "category", rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Success,
+    Error(ErrorCategory),
+}
+
+/// 1回のスクレイピングの監査ログ
+///
+/// `ScraperService` の `audit_sink` に、成功・失敗を問わずリクエストごとに1件送信する。
+/// パスワード・クッキー等の認証情報は含めない。エラーメッセージも含めず分類のみを記録する。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditRecord {
+    pub target: ScrapeTarget,
+    pub user_id: String,
+    /// 会社ID（Dtakologのみ）
+    pub comp_id: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub outcome: AuditOutcome,
+    /// ETCのダウンロードファイルのサイズ（バイト）
+    pub size_bytes: Option<usize>,
+    /// ETCのダウンロード内容の SHA-256（16進）
+    pub content_hash: Option<String>,
+    /// Dtakologで取得した車両数
+    pub vehicle_count: Option<usize>,
+}
+
+impl AuditRecord {
+    fn new(
+        identity: AuditIdentity,
+        started_at: DateTime<Utc>,
+        result: &Result<ScrapeResult, ScraperError>,
+    ) -> Self {
+        let AuditIdentity {
+            target,
+            user_id,
+            comp_id,
+        } = identity;
+        let (outcome, result) = match result {
+            Ok(result) => (AuditOutcome::Success, Some(result)),
+            Err(e) => (AuditOutcome::Error(e.category()), None),
+        };
+        Self {
+            target,
+            user_id,
+            comp_id,
+            started_at,
+            finished_at: Utc::now(),
+            outcome,
+            size_bytes: result.and_then(ScrapeResult::size_bytes),
+            content_hash: result.and_then(ScrapeResult::content_hash),
+            vehicle_count: result
+                .and_then(ScrapeResult::as_dtakolog)
+                .map(|result| result.vehicles.len()),
+        }
+    }
+}
+
+/// 監査ログに残すリクエストの識別情報（`ScrapeRequest` から認証情報を除いたもの）
+struct AuditIdentity {
+    target: ScrapeTarget,
+    user_id: String,
+    comp_id: Option<String>,
+}
+
+impl From<&ScrapeRequest> for AuditIdentity {
+    fn from(req: &ScrapeRequest) -> Self {
+        Self {
+            target: req.target,
+            user_id: req.user_id.clone(),
+            comp_id: (!req.comp_id.is_empty()).then(|| req.comp_id.clone()),
+        }
+    }
+}
+
+/// ETCスクレイピング結果
+#[derive(Debug)]
+pub struct EtcScrapeResult {
     pub csv_path: PathBuf,
     pub csv_content: Vec<u8>,
+    /// `csv_content` の文字コード（自動判定）
+    pub encoding: CsvEncoding,
+    /// `csv_content` の SHA-256（小文字16進、読み込み時に計算）
+    ///
+    /// 前回実行時の値と比較して、内容が変わっていないダウンロードを検出する用途向け。
+    pub content_hash: String,
+    /// パース済みの明細（`ParseCsvProcessor` 適用時のみ）
+    pub records: Option<Vec<EtcUsageRecord>>,
 }
 
-impl ScrapeResult {
+impl EtcScrapeResult {
     pub fn new(csv_path: PathBuf) -> std::io::Result<Self> {
         let csv_content = std::fs::read(&csv_path)?;
         Ok(Self {
+            encoding: crate::etc::detect_csv_encoding(&csv_content),
+            content_hash: format!("{:x}", Sha256::digest(&csv_content)),
             csv_path,
             csv_content,
+            records: None,
+        })
+    }
+
+    /// サイズ上限を確認してから読み込む（上限超過時は `ScraperError::Download`）
+    pub fn with_max_size(csv_path: PathBuf, max_size: Option<u64>) -> Result<Self, ScraperError> {
+        crate::etc::ensure_within_max_size(&csv_path, max_size)?;
+        Ok(Self::new(csv_path)?)
+    }
+
+    /// ダウンロードファイルのサイズ（バイト）
+    pub fn size_bytes(&self) -> usize {
+        self.csv_content.len()
+    }
+
+    /// CSVを BOM 付き UTF-8 に変換（UTF-8 以外の場合はデコードする）
+    pub fn csv_utf8_with_bom(&self) -> Vec<u8> {
+        crate::etc::csv_to_utf8_with_bom(&self.csv_content)
+    }
+}
+
+/// リクエストの対象サイトに応じたスクレイパーを生成する
+///
+/// 具体型を意識せずに `Vec<Box<dyn Scraper>>` として扱う用途（汎用ランナー・プール等）向け。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScraperFactory;
+
+impl ScraperFactory {
+    pub fn create(req: &ScrapeRequest) -> Box<dyn Scraper> {
+        match req.target {
+            ScrapeTarget::Etc => Box::new(EtcScraper::new(req.clone().into())),
+            ScrapeTarget::Dtakolog => Box::new(DtakologScraper::new(req.clone().into())),
+        }
+    }
+}
+
+/// 実行中の冪等キーを登録し、drop 時（完了・失敗・キャンセル）に解除する
+struct InFlightGuard {
+    keys: Arc<Mutex<HashSet<String>>>,
+    key: String,
+}
+
+impl InFlightGuard {
+    fn acquire(keys: &Arc<Mutex<HashSet<String>>>, key: String) -> Result<Self, ScraperError> {
+        if !keys
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key.clone())
+        {
+            return Err(ScraperError::DuplicateInProgress(key));
+        }
+        Ok(Self {
+            keys: keys.clone(),
+            key,
         })
     }
 }
 
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.keys
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.key);
+    }
+}
+
 /// tower::Serviceを実装したスクレイパーサービス
 #[derive(Debug, Clone, Default)]
 pub struct ScraperService {
-    // 将来的な拡張用（レートリミット、キャッシュなど）
+    /// 全リクエストで共有するリトライ予算（ETCのブラウザ初期化のリトライに適用）
+    retry_budget: Option<Arc<RetryBudget>>,
+    /// 実行中のリクエストの冪等キー（クローンしたサービス間で共有）
+    in_flight: Arc<Mutex<HashSet<String>>>,
+    /// 監査ログの送信先
+    audit_sink: Option<mpsc::Sender<AuditRecord>>,
+    /// 結果の後処理（登録順に適用）
+    processors: Vec<Arc<dyn ResultProcessor>>,
+    /// Dtakologの動画ダウンロードリクエスト済みの記録（全リクエストで共有）
+    requested_downloads: RequestedDownloads,
 }
 
 impl ScraperService {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// 全リクエストで共有するリトライ予算を設定
+    ///
+    /// 多数のアカウントを実行する際、障害中にリトライが集中するのを防ぐ。
+    pub fn with_retry_budget(mut self, budget: Arc<RetryBudget>) -> Self {
+        self.retry_budget = Some(budget);
+        self
+    }
+
+    /// 監査ログの送信先を設定
+    ///
+    /// リクエストごとに `AuditRecord` を1件送信する（重複で拒否したリクエストを含む）。
+    /// 取りこぼさないよう、チャネルに空きが出るまで結果の返却を待つ。
+    pub fn with_audit_sink(mut self, sink: mpsc::Sender<AuditRecord>) -> Self {
+        self.audit_sink = Some(sink);
+        self
+    }
+
+    /// 結果の後処理を追加（ダウンロード完了後、追加した順に適用）
+    pub fn with_processor(mut self, processor: impl ResultProcessor + 'static) -> Self {
+        self.processors.push(Arc::new(processor));
+        self
+    }
+}
+
+/// 後処理を順に適用（失敗した後処理の名前をエラーに含める）
+fn apply_processors(
+    processors: &[Arc<dyn ResultProcessor>],
+    mut result: ScrapeResult,
+) -> Result<ScrapeResult, ScraperError> {
+    for processor in processors {
+        result = processor
+            .process(result)
+            .map_err(|e| ScraperError::Processor {
+                name: processor.name().to_string(),
+                source: Box::new(e),
+            })?;
+    }
+    Ok(result)
+}
+
+/// 監査ログを送信（受信側が閉じている場合は警告のみ）
+async fn send_audit(sink: Option<mpsc::Sender<AuditRecord>>, record: AuditRecord) {
+    let Some(sink) = sink else {
+        return;
+    };
+    if sink.send(record).await.is_err() {
+        warn!("監査ログの送信先が閉じられています");
+    }
 }
 
 impl Service<ScrapeRequest> for ScraperService {
@@ -94,27 +501,110 @@ impl Service<ScrapeRequest> for ScraperService {
     }
 
     fn call(&mut self, req: ScrapeRequest) -> Self::Future {
-        info!("スクレイピングリクエスト受信: user_id={}", req.user_id);
+        info!(target = ?req.target, user_id = %req.user_id, "スクレイピングリクエスト受信");
+        let retry_budget = self.retry_budget.clone();
+        let audit_sink = self.audit_sink.clone();
+        let processors = self.processors.clone();
+        let requested_downloads = self.requested_downloads.clone();
+        let audit_identity = AuditIdentity::from(&req);
+        let started_at = Utc::now();
+
+        // 同じ冪等キーのリクエストが実行中なら、ブラウザを起動せずに即座に失敗させる
+        let guard = match req.idempotency_key.clone() {
+            Some(key) => match InFlightGuard::acquire(&self.in_flight, key) {
+                Ok(guard) => Some(guard),
+                Err(e) => {
+                    warn!(error = %e, "重複リクエストを拒否");
+                    return Box::pin(async move {
+                        let result = Err(e);
+                        send_audit(
+                            audit_sink,
+                            AuditRecord::new(audit_identity, started_at, &result),
+                        )
+                        .await;
+                        result
+                    });
+                }
+            },
+            None => None,
+        };
 
         Box::pin(async move {
-            let config: ScraperConfig = req.into();
-            let mut scraper = EtcScraper::new(config);
+            let _guard = guard;
+            let result = match req.target {
+                ScrapeTarget::Etc => scrape_etc(req, retry_budget).await.map(ScrapeResult::Etc),
+                ScrapeTarget::Dtakolog => scrape_dtakolog(req, requested_downloads)
+                    .await
+                    .map(|result| ScrapeResult::Dtakolog(Box::new(result))),
+            };
+            let result = result.and_then(|result| apply_processors(&processors, result));
+            // 呼び出し側が HTTP ステータス等に対応付けられるよう分類をログに残す
+            let result = result
+                .inspect_err(|e| warn!(category = ?e.category(), error = %e, "スクレイピング失敗"));
+            send_audit(
+                audit_sink,
+                AuditRecord::new(audit_identity, started_at, &result),
+            )
+            .await;
+            result
+        })
+    }
+}
 
-            // スクレイピング実行
-            let csv_path = scraper.execute().await?;
+/// ETCスクレイピングを実行
+async fn scrape_etc(
+    req: ScrapeRequest,
+    retry_budget: Option<Arc<RetryBudget>>,
+) -> Result<EtcScrapeResult, ScraperError> {
+    let mut config: ScraperConfig = req.into();
+    if let Some(budget) = retry_budget {
+        config.init_retry = config.init_retry.with_budget(budget);
+    }
+    let max_download_size = config.max_download_size;
+    let mut scraper = EtcScraper::new(config);
 
-            // 結果を作成
-            let result = ScrapeResult::new(csv_path)?;
+    // スクレイピング実行
+    let csv_path = scraper.execute().await?;
 
-            info!(
-                "スクレイピング完了: path={:?}, size={}bytes",
-                result.csv_path,
-                result.csv_content.len()
-            );
+    // 結果を作成
+    let result = EtcScrapeResult::with_max_size(csv_path, max_download_size)?;
 
-            Ok(result)
-        })
-    }
+    info!(
+        path = ?result.csv_path,
+        size_bytes = result.size_bytes(),
+        encoding = ?result.encoding,
+        content_hash = %result.content_hash,
+        "スクレイピング完了"
+    );
+
+    Ok(result)
+}
+
+/// Dtakologスクレイピングを実行
+///
+/// リクエストごとにスクレイパーを作成するため、動画のリクエスト済みの記録はサービスのものを使う。
+async fn scrape_dtakolog(
+    req: ScrapeRequest,
+    requested_downloads: RequestedDownloads,
+) -> Result<DtakologResult, ScraperError> {
+    let config = DtakologConfig {
+        requested_downloads,
+        ..req.into()
+    };
+    let mut scraper = DtakologScraper::new(config);
+
+    scraper.initialize().await?;
+    let result = scraper.scrape(None, false).await;
+    scraper.close().await?;
+    let result = result?;
+
+    info!(
+        vehicle_count = result.vehicles.len(),
+        video_notification_count = result.video_notifications.len(),
+        "スクレイピング完了"
+    );
+
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -141,4 +631,193 @@ mod tests {
         assert_eq!(config.user_id, "user");
         assert_eq!(config.password, "pass");
     }
+
+    #[test]
+    fn test_namespaced_download_dir() {
+        let req = ScrapeRequest::new("user/01", "pass")
+            .with_download_path("/tmp/dl")
+            .with_namespace_by_user(true);
+        let config: ScraperConfig = req.into();
+        assert_eq!(config.download_dir(), PathBuf::from("/tmp/dl/user_01"));
+
+        let config = config.with_namespace_by_user(false);
+        assert_eq!(config.download_dir(), PathBuf::from("/tmp/dl"));
+    }
+
+    #[test]
+    fn test_scrape_request_with_cookies() {
+        let cookie = (
+            "JSESSIONID".to_string(),
+            "abc".to_string(),
+            "www.etc-meisai.jp".to_string(),
+            "/".to_string(),
+        );
+        let req = ScrapeRequest::new("user", "")
+            .with_cookies(vec![cookie], "https://www.etc-meisai.jp/search");
+        let config: ScraperConfig = req.into();
+        assert!(config.has_session());
+        assert_eq!(config.session_cookies.len(), 1);
+        assert!(config.validate().is_ok());
+
+        let config: ScraperConfig = ScrapeRequest::new("user", "").into();
+        assert!(config.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scraper_factory() {
+        let requests = [
+            ScrapeRequest::new("user", "pass"),
+            ScrapeRequest::dtakolog("comp", "user", "pass"),
+        ];
+        let scrapers: Vec<Box<dyn Scraper>> = requests.iter().map(ScraperFactory::create).collect();
+        assert_eq!(scrapers.len(), 2);
+
+        for mut scraper in scrapers {
+            // 未初期化でも close は成功する
+            assert!(scraper.close().await.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_headless_follows_request_for_all_targets() {
+        for headless in [true, false] {
+            let etc: ScraperConfig = ScrapeRequest::new("user", "pass")
+                .with_headless(headless)
+                .into();
+            assert_eq!(etc.headless, headless);
+
+            let dtakolog: DtakologConfig = ScrapeRequest::dtakolog("comp", "user", "pass")
+                .with_headless(headless)
+                .into();
+            assert_eq!(dtakolog.headless, headless);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fixture_mode() {
+        let dir = std::env::temp_dir().join(format!("etc_fixture_{}", std::process::id()));
+        let fixtures = dir.join("fixtures");
+        std::fs::create_dir_all(&fixtures).unwrap();
+        std::fs::write(fixtures.join("user.csv"), "利用年月日（自）,車両番号\n").unwrap();
+
+        let (sink, mut audit) = mpsc::channel(4);
+        let mut service = ScraperService::new().with_audit_sink(sink);
+        let req = ScrapeRequest::new("user", "secret-pass")
+            .with_download_path(dir.join("downloads"))
+            .with_fixture_mode(&fixtures);
+        assert!(!format!("{:?}", req).contains("secret-pass"));
+        let result = service.call(req).await.unwrap();
+        let etc = result.as_etc().unwrap();
+        assert_eq!(etc.csv_path, dir.join("downloads").join("user_user.csv"));
+        assert!(etc.csv_content.starts_with("利用年月日".as_bytes()));
+        assert_eq!(etc.encoding, CsvEncoding::Utf8);
+        assert_eq!(result.size_bytes(), Some(etc.csv_content.len()));
+        assert_eq!(
+            result.content_hash(),
+            Some(format!("{:x}", Sha256::digest(&etc.csv_content)))
+        );
+
+        let record = audit.recv().await.unwrap();
+        assert_eq!(record.user_id, "user");
+        assert_eq!(record.outcome, AuditOutcome::Success);
+        assert_eq!(record.size_bytes, result.size_bytes());
+        assert_eq!(record.content_hash, result.content_hash());
+        assert!(record.started_at <= record.finished_at);
+
+        let req = ScrapeRequest::new("other", "secret-pass")
+            .with_download_path(dir.join("downloads"))
+            .with_fixture_mode(&fixtures);
+        assert!(service.call(req).await.is_err());
+        let record = audit.recv().await.unwrap();
+        assert!(matches!(record.outcome, AuditOutcome::Error(_)));
+        assert_eq!(record.content_hash, None);
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(!json.contains("secret-pass"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_key() {
+        let mut service = ScraperService::new();
+        let guard = InFlightGuard::acquire(&service.in_flight, "job-1".into()).unwrap();
+
+        let req = ScrapeRequest::new("user", "pass").with_idempotency_key("job-1");
+        let err = service.clone().call(req).await.unwrap_err();
+        assert!(matches!(err, ScraperError::DuplicateInProgress(_)));
+        drop(guard);
+
+        // 失敗した場合もキーは解除される
+        let dir = std::env::temp_dir().join(format!("etc_idempotency_{}", std::process::id()));
+        let req = ScrapeRequest::new("user", "pass")
+            .with_download_path(&dir)
+            .with_fixture_mode(dir.join("missing"))
+            .with_idempotency_key("job-1");
+        assert!(service.call(req).await.is_err());
+        assert!(service.in_flight.lock().unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[derive(Debug)]
+    struct FailingProcessor;
+
+    impl ResultProcessor for FailingProcessor {
+        fn name(&self) -> &str {
+            "FailingProcessor"
+        }
+
+        fn process(&self, _result: ScrapeResult) -> Result<ScrapeResult, ScraperError> {
+            Err(ScraperError::Config("upload target not set".into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_result_processors() {
+        let dir = std::env::temp_dir().join(format!("etc_processor_{}", std::process::id()));
+        let fixtures = dir.join("fixtures");
+        std::fs::create_dir_all(&fixtures).unwrap();
+        std::fs::write(
+            fixtures.join("user.csv"),
+            format!(
+                "{}\n2024/04/01,08:00,2024/04/01,09:10,東京,横浜,1650,330,1320,普通車,品川100あ1234,1234-5678-9012-3456,\n",
+                crate::etc::EXPECTED_CSV_COLUMNS.join(",")
+            ),
+        )
+        .unwrap();
+        let req = ScrapeRequest::new("user", "pass")
+            .with_download_path(dir.join("downloads"))
+            .with_fixture_mode(&fixtures);
+
+        let mut service = ScraperService::new().with_processor(crate::ParseCsvProcessor);
+        let result = service.call(req.clone()).await.unwrap();
+        let records = result.as_etc().unwrap().records.as_ref().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].discount_amount, 330);
+
+        let mut service = service.with_processor(FailingProcessor);
+        let err = service.call(req).await.unwrap_err();
+        assert!(matches!(&err, ScraperError::Processor { name, .. } if name == "FailingProcessor"));
+        assert_eq!(err.category(), ErrorCategory::Config);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_scrape_request_default_target() {
+        let req = ScrapeRequest::new("user", "pass");
+        assert_eq!(req.target, ScrapeTarget::Etc);
+    }
+
+    #[test]
+    fn test_dtakolog_request_to_config() {
+        let req = ScrapeRequest::dtakolog("comp", "user", "pass")
+            .with_headless(false)
+            .with_login_strategy(LoginStrategy::ReuseOnly);
+        assert_eq!(req.target, ScrapeTarget::Dtakolog);
+
+        let config: DtakologConfig = req.into();
+        assert_eq!(config.comp_id, "comp");
+        assert_eq!(config.user_name, "user");
+        assert_eq!(config.user_pass, "pass");
+        assert!(!config.headless);
+        assert_eq!(config.login_strategy, LoginStrategy::ReuseOnly);
+    }
 }
@@ -0,0 +1,38 @@
+//! スクレイプ結果のメトリクス出力
+//!
+//! `metrics` feature 有効時のみ `metrics` クレートのファサード経由で記録する。
+//! エクスポーター（Prometheus等）の選択・登録はアプリケーション側で行う。
+//! feature 無効時はすべて何もしない。
+
+use std::path::Path;
+use std::time::Duration;
+
+/// スクレイプ1回分の結果を記録
+///
+/// - `scrapes_total{scraper, outcome}`
+/// - `scrape_failures_total{scraper}`
+/// - `scrape_duration_seconds{scraper, outcome}`
+#[cfg(feature = "metrics")]
+pub(crate) fn record_scrape(scraper: &'static str, success: bool, elapsed: Duration) {
+    let outcome = if success { "success" } else { "failure" };
+    ::metrics::counter!("scrapes_total", "scraper" => scraper, "outcome" => outcome).increment(1);
+    if !success {
+        ::metrics::counter!("scrape_failures_total", "scraper" => scraper).increment(1);
+    }
+    ::metrics::histogram!("scrape_duration_seconds", "scraper" => scraper, "outcome" => outcome)
+        .record(elapsed.as_secs_f64());
+}
+
+#[cfg(not(feature = "metrics"))]
+pub(crate) fn record_scrape(_scraper: &'static str, _success: bool, _elapsed: Duration) {}
+
+/// ダウンロードしたファイルのサイズを `download_bytes_total{scraper}` に加算
+#[cfg(feature = "metrics")]
+pub(crate) fn record_download(scraper: &'static str, path: &Path) {
+    if let Ok(metadata) = std::fs::metadata(path) {
+        ::metrics::counter!("download_bytes_total", "scraper" => scraper).increment(metadata.len());
+    }
+}
+
+#[cfg(not(feature = "metrics"))]
+pub(crate) fn record_download(_scraper: &'static str, _path: &Path) {}
@@ -1,6 +1,8 @@
 use async_trait::async_trait;
-use std::path::PathBuf;
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
 
+use crate::config::RetryPolicy;
 use crate::error::ScraperError;
 
 #[async_trait]
@@ -8,6 +10,41 @@ pub trait Scraper: Send + Sync {
     /// ブラウザ初期化
     async fn initialize(&mut self) -> Result<(), ScraperError>;
 
+    /// リトライ可能なエラー（[`ScraperError::is_retryable`]）の間、ブラウザ初期化を再試行
+    ///
+    /// 失敗のたびに `close` でリソースを解放してから待機・再試行する。
+    /// `policy.budget` が設定されている場合、予算が尽きた時点でリトライせずに失敗を返す。
+    async fn initialize_with_retry(&mut self, policy: &RetryPolicy) -> Result<(), ScraperError> {
+        let max_attempts = policy.max_attempts.max(1);
+        if let Some(ref budget) = policy.budget {
+            budget.deposit();
+        }
+        let mut attempt = 0;
+        loop {
+            match self.initialize().await {
+                Ok(()) => return Ok(()),
+                Err(e) if e.is_retryable() && attempt + 1 < max_attempts => {
+                    if policy.budget.as_ref().is_some_and(|b| !b.try_withdraw()) {
+                        tracing::warn!(error = %e, "リトライ予算が尽きたためリトライしません");
+                        return Err(e);
+                    }
+                    let backoff = policy.backoff(attempt);
+                    tracing::warn!(
+                        attempt = attempt + 1,
+                        max_attempts,
+                        backoff_ms = backoff.as_millis() as u64,
+                        error = %e,
+                        "初期化に失敗しました。リトライします"
+                    );
+                    let _ = self.close().await;
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
     /// ログイン実行
     async fn login(&mut self) -> Result<(), ScraperError>;
 
@@ -17,6 +54,32 @@ pub trait Scraper: Send + Sync {
     /// リソース解放
     async fn close(&mut self) -> Result<(), ScraperError>;
 
+    /// 現在のページでJavaScript式を評価し、結果をJSONで返す
+    ///
+    /// # セキュリティ
+    /// `script` はログイン済みセッションの権限でそのまま実行される。
+    /// 外部入力を連結してスクリプトを組み立てないこと（スクリプトインジェクションになる）。
+    async fn evaluate(&self, _script: &str) -> Result<serde_json::Value, ScraperError> {
+        Err(ScraperError::Unsupported("evaluate".into()))
+    }
+
+    /// 現在のページでJavaScript式を評価し、結果を任意の型にデシリアライズ
+    ///
+    /// ログイン後の任意ページ（残高照会など）からデータを取得する用途向け。
+    /// セキュリティ上の注意は [`Scraper::evaluate`] を参照。
+    async fn extract<T: DeserializeOwned>(&self, script: &str) -> Result<T, ScraperError>
+    where
+        Self: Sized,
+    {
+        let value = self.evaluate(script).await?;
+        serde_json::from_value(value).map_err(|e| ScraperError::Json(e.to_string()))
+    }
+
+    /// 現在のページ全体のスクリーンショットをPNGで保存
+    async fn screenshot(&self, _path: &Path) -> Result<(), ScraperError> {
+        Err(ScraperError::Unsupported("screenshot".into()))
+    }
+
     /// 一括実行（initialize → login → download → close）
     async fn execute(&mut self) -> Result<PathBuf, ScraperError> {
         self.initialize().await?;
@@ -26,3 +89,111 @@ pub trait Scraper: Send + Sync {
         Ok(path)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RetryBudget;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// 指定回数だけ BrowserInit で失敗するスクレイパー
+    struct FlakyScraper {
+        failures: u32,
+        attempts: u32,
+        closes: u32,
+    }
+
+    #[async_trait]
+    impl Scraper for FlakyScraper {
+        async fn initialize(&mut self) -> Result<(), ScraperError> {
+            self.attempts += 1;
+            if self.attempts <= self.failures {
+                return Err(ScraperError::BrowserInit("launch failed".into()));
+            }
+            Ok(())
+        }
+
+        async fn login(&mut self) -> Result<(), ScraperError> {
+            Ok(())
+        }
+
+        async fn download(&mut self) -> Result<PathBuf, ScraperError> {
+            Ok(PathBuf::new())
+        }
+
+        async fn close(&mut self) -> Result<(), ScraperError> {
+            self.closes += 1;
+            Ok(())
+        }
+    }
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+            budget: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_initialize_with_retry() {
+        let mut scraper = FlakyScraper {
+            failures: 2,
+            attempts: 0,
+            closes: 0,
+        };
+        scraper.initialize_with_retry(&policy(3)).await.unwrap();
+        assert_eq!(scraper.attempts, 3);
+        assert_eq!(scraper.closes, 2);
+
+        let mut scraper = FlakyScraper {
+            failures: 2,
+            attempts: 0,
+            closes: 0,
+        };
+        let err = scraper.initialize_with_retry(&policy(2)).await.unwrap_err();
+        assert!(matches!(err, ScraperError::BrowserInit(_)));
+        assert_eq!(scraper.attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_initialize_with_retry_budget() {
+        // 1スクレイプあたり0.5回、時間による補充なし
+        let budget = Arc::new(RetryBudget::new(0.5, 0));
+        let policy = policy(5).with_budget(budget.clone());
+
+        // 1回目: 0.5 トークンではリトライできない
+        let mut scraper = FlakyScraper {
+            failures: 1,
+            attempts: 0,
+            closes: 0,
+        };
+        assert!(scraper.initialize_with_retry(&policy).await.is_err());
+        assert_eq!(scraper.attempts, 1);
+
+        // 2回目: 積み増しで1トークンになり1回だけリトライできる
+        let mut scraper = FlakyScraper {
+            failures: 1,
+            attempts: 0,
+            closes: 0,
+        };
+        scraper.initialize_with_retry(&policy).await.unwrap();
+        assert_eq!(scraper.attempts, 2);
+        assert!(budget.available() < 1.0);
+    }
+
+    #[test]
+    fn test_retry_budget_deposit_is_capped() {
+        let budget = RetryBudget::new(0.5, 0);
+        for _ in 0..1000 {
+            budget.deposit();
+        }
+        assert_eq!(budget.available(), 10.0);
+        let withdrawals = std::iter::from_fn(|| budget.try_withdraw().then_some(()))
+            .take(1000)
+            .count();
+        assert_eq!(withdrawals, 10);
+    }
+}